@@ -2,9 +2,12 @@ package com.plugin.webdriver
 
 import android.app.Activity
 import android.graphics.Bitmap
+import android.graphics.PointF
 import android.graphics.pdf.PdfDocument
 import android.os.Handler
 import android.os.Looper
+import android.os.SystemClock
+import android.view.InputDevice
 import android.view.MotionEvent
 import android.webkit.JavascriptInterface
 import android.webkit.CookieManager
@@ -12,6 +15,8 @@ import android.webkit.JsPromptResult
 import android.webkit.JsResult
 import android.webkit.WebChromeClient
 import android.webkit.WebView
+import androidx.webkit.CookieManagerCompat
+import androidx.webkit.WebViewFeature
 import app.tauri.annotation.Command
 import app.tauri.annotation.InvokeArg
 import app.tauri.annotation.TauriPlugin
@@ -19,18 +24,37 @@ import app.tauri.plugin.Invoke
 import app.tauri.plugin.JSObject
 import app.tauri.plugin.Plugin
 import java.io.ByteArrayOutputStream
+import java.text.ParseException
+import java.text.SimpleDateFormat
+import java.util.Date
+import java.util.Locale
+import java.util.TimeZone
 import java.util.concurrent.ConcurrentHashMap
 import kotlin.math.roundToInt
 
+/** How long to let the WebView paint after each scroll of a full-page screenshot */
+private const val FULL_PAGE_SCROLL_SETTLE_MS = 100L
+
 @InvokeArg
 class EvaluateJsArgs {
     lateinit var script: String
     var timeoutMs: Long = 30000
 }
 
+@InvokeArg
+class ScreenshotClip {
+    var x: Double = 0.0
+    var y: Double = 0.0
+    var width: Double = 0.0
+    var height: Double = 0.0
+    var viewportWidth: Double = 0.0
+}
+
 @InvokeArg
 class ScreenshotArgs {
     var timeoutMs: Long = 30000
+    var clip: ScreenshotClip? = null
+    var fullPage: Boolean = false
 }
 
 @InvokeArg
@@ -53,6 +77,7 @@ class TouchArgs {
     lateinit var type: String  // "down", "up", "move"
     var x: Int = 0
     var y: Int = 0
+    var pointerId: Int = 0
 }
 
 @InvokeArg
@@ -124,6 +149,10 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
     // Cache cookie metadata since CookieManager doesn't expose attributes
     // Key format: "domain:path:name"
     private val cookieMetadataCache = ConcurrentHashMap<String, CookieMetadata>()
+    // Pointers currently touching the screen, in the order they went down
+    // (only accessed on the main thread)
+    private val activeTouches = LinkedHashMap<Int, PointF>()
+    private var touchDownTime = 0L
 
     override fun load(webView: WebView) {
         this.webView = webView
@@ -308,6 +337,7 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
      */
     @Command
     fun takeScreenshot(invoke: Invoke) {
+        val args = invoke.parseArgs(ScreenshotArgs::class.java)
         val wv = webView
 
         if (wv == null) {
@@ -315,28 +345,99 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
             return
         }
 
+        if (args.fullPage) {
+            mainHandler.post {
+                try {
+                    captureFullPage(wv, invoke)
+                } catch (e: Exception) {
+                    invoke.reject("Screenshot failed: ${e.message}")
+                }
+            }
+            return
+        }
+
         mainHandler.post {
             try {
-                val bitmap = Bitmap.createBitmap(wv.width, wv.height, Bitmap.Config.ARGB_8888)
-                val canvas = android.graphics.Canvas(bitmap)
+                val fullBitmap = Bitmap.createBitmap(wv.width, wv.height, Bitmap.Config.ARGB_8888)
+                val canvas = android.graphics.Canvas(fullBitmap)
                 wv.draw(canvas)
 
-                val outputStream = ByteArrayOutputStream()
-                bitmap.compress(Bitmap.CompressFormat.PNG, 100, outputStream)
-                val base64 = android.util.Base64.encodeToString(outputStream.toByteArray(), android.util.Base64.NO_WRAP)
-
-                val ret = JSObject()
-                ret.put("success", true)
-                ret.put("value", base64)
-                invoke.resolve(ret)
+                // Crop to the element area, given in CSS pixels
+                val clip = args.clip
+                val bitmap = if (clip != null) {
+                    val scale = if (clip.viewportWidth > 0) fullBitmap.width / clip.viewportWidth else 1.0
+                    val left = (clip.x * scale).roundToInt().coerceIn(0, fullBitmap.width - 1)
+                    val top = (clip.y * scale).roundToInt().coerceIn(0, fullBitmap.height - 1)
+                    val width = (clip.width * scale).roundToInt().coerceIn(1, fullBitmap.width - left)
+                    val height = (clip.height * scale).roundToInt().coerceIn(1, fullBitmap.height - top)
+                    Bitmap.createBitmap(fullBitmap, left, top, width, height).also {
+                        if (it != fullBitmap) fullBitmap.recycle()
+                    }
+                } else {
+                    fullBitmap
+                }
 
-                bitmap.recycle()
+                resolveScreenshot(invoke, bitmap)
             } catch (e: Exception) {
                 invoke.reject("Screenshot failed: ${e.message}")
             }
         }
     }
 
+    private fun resolveScreenshot(invoke: Invoke, bitmap: Bitmap) {
+        val outputStream = ByteArrayOutputStream()
+        bitmap.compress(Bitmap.CompressFormat.PNG, 100, outputStream)
+        val base64 = android.util.Base64.encodeToString(outputStream.toByteArray(), android.util.Base64.NO_WRAP)
+
+        val ret = JSObject()
+        ret.put("success", true)
+        ret.put("value", base64)
+        invoke.resolve(ret)
+
+        bitmap.recycle()
+    }
+
+    /**
+     * Capture the whole document by scrolling through it one viewport at a time and
+     * stitching the pieces, then restore the scroll position. Must run on the main thread.
+     */
+    private fun captureFullPage(wv: WebView, invoke: Invoke) {
+        val viewportHeight = wv.height
+        val contentHeight = maxOf((wv.contentHeight * wv.scale).roundToInt(), viewportHeight)
+        val result = Bitmap.createBitmap(wv.width, contentHeight, Bitmap.Config.ARGB_8888)
+        val canvas = android.graphics.Canvas(result)
+        val originalX = wv.scrollX
+        val originalY = wv.scrollY
+
+        fun captureFrom(offset: Int) {
+            wv.scrollTo(originalX, offset)
+            // Let the newly exposed area paint before drawing it
+            mainHandler.postDelayed({
+                try {
+                    // The last viewport may stop short of `offset` at the bottom of the page
+                    val top = minOf(wv.scrollY, contentHeight - viewportHeight)
+                    canvas.save()
+                    canvas.translate(0f, top.toFloat())
+                    wv.draw(canvas)
+                    canvas.restore()
+
+                    if (top + viewportHeight < contentHeight) {
+                        captureFrom(top + viewportHeight)
+                    } else {
+                        wv.scrollTo(originalX, originalY)
+                        resolveScreenshot(invoke, result)
+                    }
+                } catch (e: Exception) {
+                    wv.scrollTo(originalX, originalY)
+                    result.recycle()
+                    invoke.reject("Screenshot failed: ${e.message}")
+                }
+            }, FULL_PAGE_SCROLL_SETTLE_MS)
+        }
+
+        captureFrom(0)
+    }
+
     /**
      * Print page to PDF using PdfDocument
      */
@@ -430,28 +531,72 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
 
         mainHandler.post {
             try {
+                if (args.type !in setOf("down", "up", "move")) {
+                    invoke.reject("Unknown touch type: ${args.type}")
+                    return@post
+                }
+                val id = args.pointerId
+                val point = PointF(args.x.toFloat(), args.y.toFloat())
+                val eventTime = SystemClock.uptimeMillis()
+
+                // Moves of a pointer that is not down have nothing to report
+                if (args.type != "down" && !activeTouches.containsKey(id)) {
+                    invoke.resolve()
+                    return@post
+                }
+                if (args.type == "down" && activeTouches.isEmpty()) {
+                    touchDownTime = eventTime
+                }
+                activeTouches[id] = point
+
+                val ids = activeTouches.keys.toList()
+                val index = ids.indexOf(id)
+                val pointerShift = index shl MotionEvent.ACTION_POINTER_INDEX_SHIFT
                 val action = when (args.type) {
-                    "down" -> MotionEvent.ACTION_DOWN
-                    "up" -> MotionEvent.ACTION_UP
-                    "move" -> MotionEvent.ACTION_MOVE
-                    else -> {
-                        invoke.reject("Unknown touch type: ${args.type}")
-                        return@post
-                    }
+                    "down" -> if (ids.size == 1) MotionEvent.ACTION_DOWN
+                        else MotionEvent.ACTION_POINTER_DOWN or pointerShift
+                    "up" -> if (ids.size == 1) MotionEvent.ACTION_UP
+                        else MotionEvent.ACTION_POINTER_UP or pointerShift
+                    else -> MotionEvent.ACTION_MOVE
                 }
 
-                val downTime = System.currentTimeMillis()
-                val eventTime = System.currentTimeMillis()
+                val properties = ids.map { pointerId ->
+                    MotionEvent.PointerProperties().apply {
+                        this.id = pointerId
+                        toolType = MotionEvent.TOOL_TYPE_FINGER
+                    }
+                }.toTypedArray()
+                val coords = ids.map { pointerId ->
+                    val p = activeTouches.getValue(pointerId)
+                    MotionEvent.PointerCoords().apply {
+                        x = p.x
+                        y = p.y
+                        pressure = 1f
+                        size = 1f
+                    }
+                }.toTypedArray()
 
                 val event = MotionEvent.obtain(
-                    downTime,
+                    touchDownTime,
                     eventTime,
                     action,
-                    args.x.toFloat(),
-                    args.y.toFloat(),
+                    ids.size,
+                    properties,
+                    coords,
+                    0,
+                    0,
+                    1f,
+                    1f,
+                    0,
+                    0,
+                    InputDevice.SOURCE_TOUCHSCREEN,
                     0
                 )
 
+                if (args.type == "up") {
+                    activeTouches.remove(id)
+                }
+
                 wv.dispatchTouchEvent(event)
                 event.recycle()
 
@@ -564,39 +709,13 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
         mainHandler.post {
             try {
                 val cookieManager = CookieManager.getInstance()
-                val cookieString = cookieManager.getCookie(args.url)
-
                 val cookiesArray = org.json.JSONArray()
 
-                if (cookieString != null) {
-                    val cookies = cookieString.split(";").map { it.trim() }
-                    for (cookie in cookies) {
-                        val parts = cookie.split("=", limit = 2)
-                        if (parts.size >= 2) {
-                            val name = parts[0].trim()
-                            val value = parts[1].trim()
-
-                            // Look up metadata from cache
-                            val metadata = findCookieMetadata(name)
-
-                            val cookieObj = org.json.JSONObject()
-                            cookieObj.put("name", name)
-                            cookieObj.put("value", value)
-                            cookieObj.put("path", metadata?.path ?: "/")
-                            if (metadata?.domain != null) {
-                                cookieObj.put("domain", metadata.domain)
-                            }
-                            cookieObj.put("secure", metadata?.secure ?: false)
-                            cookieObj.put("httpOnly", metadata?.httpOnly ?: false)
-                            if (metadata?.expiry != null) {
-                                cookieObj.put("expiry", metadata.expiry)
-                            }
-                            if (metadata?.sameSite != null) {
-                                cookieObj.put("sameSite", metadata.sameSite)
-                            }
-                            cookiesArray.put(cookieObj)
-                        }
-                    }
+                val cookieInfo = cookieInfo(cookieManager, args.url)
+                if (cookieInfo != null) {
+                    cookieInfo.forEach { cookiesArray.put(it) }
+                } else {
+                    appendCachedCookies(cookieManager.getCookie(args.url), cookiesArray)
                 }
 
                 val ret = JSObject()
@@ -609,6 +728,101 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    /**
+     * Cookies for a URL with full attributes, including HttpOnly and server-set ones.
+     * Returns null when the WebView is too old to report cookie attributes.
+     */
+    private fun cookieInfo(cookieManager: CookieManager, url: String): List<org.json.JSONObject>? {
+        if (!WebViewFeature.isFeatureSupported(WebViewFeature.GET_COOKIE_INFO)) {
+            return null
+        }
+        return CookieManagerCompat.getCookieInfo(cookieManager, url).mapNotNull { parseCookieInfo(it) }
+    }
+
+    /**
+     * Parse a Set-Cookie style line from getCookieInfo into a WebDriver cookie object
+     */
+    private fun parseCookieInfo(info: String): org.json.JSONObject? {
+        val parts = info.split(";").map { it.trim() }
+        val nameValue = parts.first().split("=", limit = 2)
+        if (nameValue.size < 2) return null
+
+        val cookieObj = org.json.JSONObject()
+        cookieObj.put("name", nameValue[0].trim())
+        cookieObj.put("value", nameValue[1].trim())
+        cookieObj.put("path", "/")
+        cookieObj.put("secure", false)
+        cookieObj.put("httpOnly", false)
+        for (attribute in parts.drop(1)) {
+            val pair = attribute.split("=", limit = 2)
+            val value = pair.getOrNull(1)?.trim()
+            when (pair[0].trim().lowercase(Locale.US)) {
+                "domain" -> value?.let { cookieObj.put("domain", it) }
+                "path" -> value?.let { cookieObj.put("path", it) }
+                "secure" -> cookieObj.put("secure", true)
+                "httponly" -> cookieObj.put("httpOnly", true)
+                "expires" -> value?.let { parseCookieDate(it) }?.let { cookieObj.put("expiry", it) }
+                "samesite" -> when (value?.lowercase(Locale.US)) {
+                    "strict" -> cookieObj.put("sameSite", "Strict")
+                    "lax" -> cookieObj.put("sameSite", "Lax")
+                    "none", "no_restriction" -> cookieObj.put("sameSite", "None")
+                }
+            }
+        }
+        return cookieObj
+    }
+
+    /**
+     * Parse a cookie Expires date into seconds since the epoch
+     */
+    private fun parseCookieDate(date: String): Long? {
+        for (pattern in listOf("EEE, dd MMM yyyy HH:mm:ss zzz", "EEE, dd-MMM-yyyy HH:mm:ss zzz")) {
+            try {
+                val format = SimpleDateFormat(pattern, Locale.US)
+                format.timeZone = TimeZone.getTimeZone("GMT")
+                return format.parse(date)?.time?.div(1000)
+            } catch (e: ParseException) {
+                // Try the next format
+            }
+        }
+        return null
+    }
+
+    /**
+     * Cookies from a Cookie header value, with attributes from cookies this plugin set
+     */
+    private fun appendCachedCookies(cookieString: String?, cookiesArray: org.json.JSONArray) {
+        if (cookieString == null) return
+        val cookies = cookieString.split(";").map { it.trim() }
+        for (cookie in cookies) {
+            val parts = cookie.split("=", limit = 2)
+            if (parts.size >= 2) {
+                val name = parts[0].trim()
+                val value = parts[1].trim()
+
+                // Look up metadata from cache
+                val metadata = findCookieMetadata(name)
+
+                val cookieObj = org.json.JSONObject()
+                cookieObj.put("name", name)
+                cookieObj.put("value", value)
+                cookieObj.put("path", metadata?.path ?: "/")
+                if (metadata?.domain != null) {
+                    cookieObj.put("domain", metadata.domain)
+                }
+                cookieObj.put("secure", metadata?.secure ?: false)
+                cookieObj.put("httpOnly", metadata?.httpOnly ?: false)
+                if (metadata?.expiry != null) {
+                    cookieObj.put("expiry", metadata.expiry)
+                }
+                if (metadata?.sameSite != null) {
+                    cookieObj.put("sameSite", metadata.sameSite)
+                }
+                cookiesArray.put(cookieObj)
+            }
+        }
+    }
+
     /**
      * Find cookie metadata by name (searches all entries)
      */
@@ -633,7 +847,11 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
                 args.domain?.let { cookieStr.append("; domain=$it") }
                 if (args.secure) cookieStr.append("; secure")
                 if (args.httpOnly) cookieStr.append("; httponly")
-                args.expiry?.let { cookieStr.append("; max-age=$it") }
+                args.expiry?.let {
+                    val format = SimpleDateFormat("EEE, dd MMM yyyy HH:mm:ss 'GMT'", Locale.US)
+                    format.timeZone = TimeZone.getTimeZone("GMT")
+                    cookieStr.append("; expires=${format.format(Date(it * 1000))}")
+                }
                 args.sameSite?.let { cookieStr.append("; samesite=$it") }
 
                 cookieManager.setCookie(args.url, cookieStr.toString())
@@ -672,10 +890,12 @@ class WebDriverPlugin(private val activity: Activity) : Plugin(activity) {
             try {
                 val cookieManager = CookieManager.getInstance()
 
-                // Look up metadata to get the original path/domain
+                // Look up the original path/domain, which must match for the deletion to apply
+                val info = cookieInfo(cookieManager, args.url)
+                    ?.find { it.optString("name") == args.name }
                 val metadata = findCookieMetadata(args.name)
-                val path = metadata?.path ?: "/"
-                val domain = metadata?.domain
+                val path = info?.optString("path", null) ?: metadata?.path ?: "/"
+                val domain = info?.optString("domain", null) ?: metadata?.domain
 
                 // Delete by setting expired cookie with same path/domain
                 val deleteCookie = StringBuilder("${args.name}=; expires=Thu, 01 Jan 1970 00:00:00 GMT; path=$path")
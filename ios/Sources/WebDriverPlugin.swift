@@ -13,16 +13,19 @@ class EvaluateJsArgs: Decodable {
 class AsyncScriptArgs: Decodable {
     let script: String
     var timeoutMs: Int64?
+    var worldName: String?
 }
 
-class TouchArgs: Decodable {
-    let type: String
-    let x: Int
-    let y: Int
+class ScreenshotClip: Decodable {
+    let x: Double
+    let y: Double
+    let width: Double
+    let height: Double
 }
 
 class ScreenshotArgs: Decodable {
     var timeoutMs: Int64?
+    var clip: ScreenshotClip?
 }
 
 class PrintArgs: Decodable {
@@ -64,7 +67,8 @@ class PendingAlert {
 
 class WebDriverPlugin: Plugin, WKUIDelegate {
     private var webView: WKWebView?
-    private var pendingAlert: PendingAlert?
+    // Dialogs in the order they opened; commands act on the first one
+    private var pendingAlerts: [PendingAlert] = []
     private let alertLock = NSLock()
     private var originalUIDelegate: WKUIDelegate?
 
@@ -82,29 +86,29 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
 
     func webView(_ webView: WKWebView, runJavaScriptAlertPanelWithMessage message: String, initiatedByFrame frame: WKFrameInfo, completionHandler: @escaping () -> Void) {
         alertLock.lock()
-        pendingAlert = PendingAlert(message: message, type: "alert") { accepted, _ in
+        pendingAlerts.append(PendingAlert(message: message, type: "alert") { accepted, _ in
             completionHandler()
-        }
+        })
         alertLock.unlock()
     }
 
     func webView(_ webView: WKWebView, runJavaScriptConfirmPanelWithMessage message: String, initiatedByFrame frame: WKFrameInfo, completionHandler: @escaping (Bool) -> Void) {
         alertLock.lock()
-        pendingAlert = PendingAlert(message: message, type: "confirm") { accepted, _ in
+        pendingAlerts.append(PendingAlert(message: message, type: "confirm") { accepted, _ in
             completionHandler(accepted)
-        }
+        })
         alertLock.unlock()
     }
 
     func webView(_ webView: WKWebView, runJavaScriptTextInputPanelWithPrompt prompt: String, defaultText: String?, initiatedByFrame frame: WKFrameInfo, completionHandler: @escaping (String?) -> Void) {
         alertLock.lock()
-        pendingAlert = PendingAlert(message: prompt, type: "prompt", defaultText: defaultText) { accepted, text in
+        pendingAlerts.append(PendingAlert(message: prompt, type: "prompt", defaultText: defaultText) { accepted, text in
             if accepted {
                 completionHandler(text ?? defaultText ?? "")
             } else {
                 completionHandler(nil)
             }
-        }
+        })
         alertLock.unlock()
     }
 
@@ -187,12 +191,15 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
         });
         """
 
+        // A named world is an isolated one sharing the page's DOM but not its globals
+        let world: WKContentWorld = args.worldName.map { WKContentWorld.world(name: $0) } ?? .page
+
         DispatchQueue.main.async {
             wv.callAsyncJavaScript(
                 promiseScript,
                 arguments: [:],
                 in: nil,
-                in: .page,
+                in: world,
                 completionHandler: { result in
                     switch result {
                     case .success(let value):
@@ -231,9 +238,14 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
             invoke.reject("WebView not available")
             return
         }
+        let clip = (try? invoke.parseArgs(ScreenshotArgs.self))?.clip
 
         DispatchQueue.main.async {
             let config = WKSnapshotConfiguration()
+            // The snapshot rect is in view coordinates, which match CSS pixels
+            if let clip = clip {
+                config.rect = CGRect(x: clip.x, y: clip.y, width: clip.width, height: clip.height)
+            }
 
             wv.takeSnapshot(with: config) { image, error in
                 if let error = error {
@@ -297,64 +309,9 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
         }
     }
 
-    @objc public func dispatchTouch(_ invoke: Invoke) {
-        guard let args = try? invoke.parseArgs(TouchArgs.self) else {
-            invoke.reject("Failed to parse arguments")
-            return
-        }
-
-        guard let wv = webView else {
-            invoke.reject("WebView not available")
-            return
-        }
-
-        // Use JavaScript to dispatch touch/pointer events
-        // Native UITouch injection is complex and requires private APIs
-        let eventType: String
-        switch args.type {
-        case "down":
-            eventType = "pointerdown"
-        case "up":
-            eventType = "pointerup"
-        case "move":
-            eventType = "pointermove"
-        default:
-            invoke.reject("Unknown touch type: \(args.type)")
-            return
-        }
-
-        let script = """
-        (function() {
-            var el = document.elementFromPoint(\(args.x), \(args.y));
-            if (el) {
-                var event = new PointerEvent('\(eventType)', {
-                    bubbles: true,
-                    cancelable: true,
-                    clientX: \(args.x),
-                    clientY: \(args.y),
-                    pointerId: 1,
-                    pointerType: 'touch',
-                    isPrimary: true
-                });
-                el.dispatchEvent(event);
-            }
-        })();
-        """
-
-        DispatchQueue.main.async {
-            wv.evaluateJavaScript(script) { _, error in
-                if let error = error {
-                    invoke.reject("Touch dispatch failed: \(error.localizedDescription)")
-                } else {
-                    invoke.resolve()
-                }
-            }
-        }
-    }
-
     @objc public func getAlertText(_ invoke: Invoke) {
         alertLock.lock()
-        let alert = pendingAlert
+        let alert = pendingAlerts.first
         alertLock.unlock()
 
         if let alert = alert {
@@ -368,8 +325,7 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
 
     @objc public func acceptAlert(_ invoke: Invoke) {
         alertLock.lock()
-        let alert = pendingAlert
-        pendingAlert = nil
+        let alert = pendingAlerts.isEmpty ? nil : pendingAlerts.removeFirst()
         alertLock.unlock()
 
         if let alert = alert {
@@ -383,8 +339,7 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
 
     @objc public func dismissAlert(_ invoke: Invoke) {
         alertLock.lock()
-        let alert = pendingAlert
-        pendingAlert = nil
+        let alert = pendingAlerts.isEmpty ? nil : pendingAlerts.removeFirst()
         alertLock.unlock()
 
         if let alert = alert {
@@ -402,7 +357,7 @@ class WebDriverPlugin: Plugin, WKUIDelegate {
         }
 
         alertLock.lock()
-        let alert = pendingAlert
+        let alert = pendingAlerts.first
         alertLock.unlock()
 
         if let alert = alert {
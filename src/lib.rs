@@ -1,5 +1,8 @@
+use std::sync::Arc;
+
 use tauri::{
-    plugin::{Builder, TauriPlugin},
+    plugin::{self, TauriPlugin},
+    webview::PageLoadEvent,
     Manager, Runtime,
 };
 
@@ -15,6 +18,8 @@ mod webdriver;
 
 pub use error::{Error, Result};
 
+use webdriver::locator::LocatorRegistry;
+
 /// Default port for the `WebDriver` HTTP server
 pub const DEFAULT_PORT: u16 = 4445;
 
@@ -28,12 +33,7 @@ pub const PORT_ENV_VAR: &str = "TAURI_WEBDRIVER_PORT";
 /// 2. Default port (4445)
 #[must_use]
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    let port = std::env::var(PORT_ENV_VAR)
-        .ok()
-        .and_then(|s| s.parse::<u16>().ok())
-        .unwrap_or(DEFAULT_PORT);
-
-    init_with_port(port)
+    Builder::new().build()
 }
 
 /// Initializes the plugin with a custom port.
@@ -41,30 +41,134 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 /// This ignores the `TAURI_WEBDRIVER_PORT` environment variable.
 #[must_use]
 pub fn init_with_port<R: Runtime>(port: u16) -> TauriPlugin<R> {
-    Builder::new("webdriver")
-        .setup(move |app, api| {
-            #[cfg(mobile)]
-            let webdriver = mobile::init(app, api)?;
-            #[cfg(desktop)]
-            let webdriver = desktop::init(app, api);
-            app.manage(webdriver);
-
-            // Manage async script state for native message handlers (Windows only)
-            #[cfg(target_os = "windows")]
-            app.manage(platform::AsyncScriptState::default());
-
-            // Manage per-window alert state
-            app.manage(platform::AlertStateManager::default());
-
-            // Start the WebDriver HTTP server
-            let app_handle = app.app_handle().clone();
-            server::start(app_handle, port);
-            tracing::info!("WebDriver plugin initialized on port {port}");
-
-            Ok(())
-        })
-        .on_webview_ready(|webview| {
-            platform::register_webview_handlers(&webview);
-        })
-        .build()
+    Builder::new().port(port).build()
+}
+
+/// Builder for configuring the plugin beyond the port.
+#[derive(Debug)]
+pub struct Builder {
+    port: u16,
+    locators: LocatorRegistry,
+    expose_closed_shadow_roots: bool,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Creates a builder using the port from `TAURI_WEBDRIVER_PORT` or the default port.
+    #[must_use]
+    pub fn new() -> Self {
+        let port = std::env::var(PORT_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok())
+            .unwrap_or(DEFAULT_PORT);
+
+        Self {
+            port,
+            locators: LocatorRegistry::default(),
+            expose_closed_shadow_roots: false,
+        }
+    }
+
+    /// Sets the port of the `WebDriver` HTTP server.
+    #[must_use]
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Registers a custom locator strategy available to the `using` field of find requests.
+    ///
+    /// The function receives the search root expression (`document`, an element or a
+    /// shadow root) and the locator value as a quoted JavaScript string literal, and must
+    /// return a JavaScript expression evaluating to an array-like list of matching elements.
+    ///
+    /// ```rust,ignore
+    /// tauri_plugin_webdriver::Builder::new()
+    ///     .locator_strategy("component", |root, value| {
+    ///         format!("{root}.querySelectorAll('[data-component=\"' + {value} + '\"]')")
+    ///     })
+    ///     .build()
+    /// ```
+    #[must_use]
+    pub fn locator_strategy<F>(mut self, name: impl Into<String>, script: F) -> Self
+    where
+        F: Fn(&str, &str) -> String + Send + Sync + 'static,
+    {
+        self.locators.register(name, Arc::new(script));
+        self
+    }
+
+    /// Sets the attribute matched by the `test id` locator strategy (default `data-testid`).
+    #[must_use]
+    pub fn test_id_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.locators.set_test_id_attribute(attribute);
+        self
+    }
+
+    /// Records closed shadow roots through an `attachShadow` patch so sessions with the
+    /// `closedShadowRoots` option can reach them. Intended for test builds only.
+    #[must_use]
+    pub fn expose_closed_shadow_roots(mut self, enabled: bool) -> Self {
+        self.expose_closed_shadow_roots = enabled;
+        self
+    }
+
+    /// Builds the plugin.
+    #[must_use]
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let Self {
+            port,
+            locators,
+            expose_closed_shadow_roots,
+        } = self;
+
+        let mut builder = plugin::Builder::new("webdriver");
+        if expose_closed_shadow_roots {
+            builder = builder.js_init_script_on_all_frames(platform::CLOSED_SHADOW_ROOTS_JS);
+        }
+
+        builder
+            .setup(move |app, api| {
+                #[cfg(mobile)]
+                let webdriver = mobile::init(app, api)?;
+                #[cfg(desktop)]
+                let webdriver = desktop::init(app, api);
+                app.manage(webdriver);
+
+                // Manage async script state for native message handlers
+                #[cfg(any(target_os = "windows", target_os = "linux"))]
+                app.manage(platform::AsyncScriptState::default());
+
+                // Manage per-window alert state
+                app.manage(webdriver::AlertStateManager::default());
+
+                // Track every webview, including child webviews of multi-webview windows
+                app.manage(platform::WebviewRegistry::<R>::default());
+
+                // Start the WebDriver HTTP server
+                let app_handle = app.app_handle().clone();
+                server::start(app_handle, port, locators, expose_closed_shadow_roots);
+                tracing::info!("WebDriver plugin initialized on port {port}");
+
+                Ok(())
+            })
+            .on_webview_ready(|webview| {
+                if let Some(registry) = webview.try_state::<platform::WebviewRegistry<R>>() {
+                    registry.register(&webview);
+                }
+                platform::register_webview_handlers(&webview);
+            })
+            .on_page_load(|webview, payload| {
+                if payload.event() == PageLoadEvent::Started {
+                    server::reset_frames_on_navigation(webview);
+                }
+                server::track_page_load(webview, payload.event(), payload.url());
+            })
+            .build()
+    }
 }
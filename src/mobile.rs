@@ -41,12 +41,17 @@ pub struct TouchArgs {
     pub r#type: String,
     pub x: i32,
     pub y: i32,
+    pub pointer_id: u32,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScreenshotArgs {
     pub timeout_ms: u64,
+    /// Area to crop to, in CSS pixels of the viewport
+    pub clip: Option<crate::platform::ScreenshotClip>,
+    /// Capture the whole scrollable document instead of the viewport
+    pub full_page: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -1,14 +1,15 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{Manager, Runtime, WebviewWindow};
+use tauri::{Manager, Runtime, Webview};
 
 use crate::mobile::{
     AlertResult, EvaluateJsArgs, JsResult, ScreenshotArgs, SendAlertTextArgs, TouchArgs, Webdriver,
 };
 use crate::platform::{
-    wrap_script_for_frame_context, Cookie, FrameId, PlatformExecutor, PointerEventType,
-    PrintOptions, WindowRect,
+    frame_function_script, frame_window_script, wrap_script_for_frame_context, Cookie,
+    FrameContext, PlatformExecutor, PointerEventType, PointerInput, PrintOptions, ScreenshotClip,
+    WindowRect, DESERIALIZE_ARG_JS, SERIALIZE_RESULT_JS,
 };
 use crate::server::response::WebDriverErrorResponse;
 use crate::webdriver::Timeouts;
@@ -16,19 +17,55 @@ use crate::webdriver::Timeouts;
 /// Android `WebView` executor using Tauri's mobile plugin bridge
 #[derive(Clone)]
 pub struct AndroidExecutor<R: Runtime> {
-    window: WebviewWindow<R>,
+    webview: Webview<R>,
     timeouts: Timeouts,
-    frame_context: Vec<FrameId>,
+    frame_context: FrameContext,
 }
 
 impl<R: Runtime> AndroidExecutor<R> {
-    pub fn new(window: WebviewWindow<R>, timeouts: Timeouts, frame_context: Vec<FrameId>) -> Self {
+    pub fn new(webview: Webview<R>, timeouts: Timeouts, frame_context: FrameContext) -> Self {
         Self {
-            window,
+            webview,
             timeouts,
             frame_context,
         }
     }
+
+    /// Take a screenshot of the webview, cropped natively to `clip` if given, or of the
+    /// whole document with `full_page`
+    async fn snapshot(
+        &self,
+        clip: Option<ScreenshotClip>,
+        full_page: bool,
+    ) -> Result<String, WebDriverErrorResponse> {
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
+
+        let args = ScreenshotArgs {
+            timeout_ms: self.timeouts.script_ms,
+            clip,
+            full_page,
+        };
+
+        let result: JsResult = webdriver
+            .0
+            .run_mobile_plugin_async("takeScreenshot", args)
+            .await
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        if result.success {
+            if let Some(Value::String(base64)) = result.value {
+                Ok(base64)
+            } else {
+                Err(WebDriverErrorResponse::unknown_error(
+                    "Screenshot returned invalid data",
+                ))
+            }
+        } else {
+            Err(WebDriverErrorResponse::unknown_error(
+                result.error.as_deref().unwrap_or("Screenshot failed"),
+            ))
+        }
+    }
 }
 
 // =============================================================================
@@ -85,14 +122,14 @@ struct CookiesResult {
 
 #[async_trait]
 impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
-    fn window(&self) -> &WebviewWindow<R> {
-        &self.window
+    fn webview(&self) -> &Webview<R> {
+        &self.webview
     }
 
     async fn evaluate_js(&self, script: &str) -> Result<Value, WebDriverErrorResponse> {
         let wrapped_script = wrap_script_for_frame_context(script, &self.frame_context);
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let args = EvaluateJsArgs {
             script: wrapped_script,
@@ -127,7 +164,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
             if error_msg.to_lowercase().contains("timeout") {
                 Err(WebDriverErrorResponse::script_timeout())
             } else {
-                Err(WebDriverErrorResponse::javascript_error(error_msg, None))
+                Err(WebDriverErrorResponse::from_js_error(error_msg, None))
             }
         }
     }
@@ -143,37 +180,30 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
         let async_id = uuid::Uuid::new_v4().to_string();
 
         // Build wrapper that includes argument deserialization and callback
+        let frame_window = frame_window_script(&self.frame_context);
+        let function = frame_function_script(script, &self.frame_context);
         let wrapper = format!(
             r"(function() {{
-                var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-                function deserializeArg(arg) {{
-                    if (arg === null || arg === undefined) return arg;
-                    if (Array.isArray(arg)) return arg.map(deserializeArg);
-                    if (typeof arg === 'object') {{
-                        if (arg[ELEMENT_KEY]) {{
-                            var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                            if (!el) throw new Error('stale element reference');
-                            return el;
-                        }}
-                        var result = {{}};
-                        for (var key in arg) {{
-                            if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                        }}
-                        return result;
-                    }}
-                    return arg;
-                }}
-                var __args = {args_json}.map(deserializeArg);
-                __args.push(__done);
+                var __ctx;
+                var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, __ctx); }};
                 try {{
-                    (function() {{ {script} }}).apply(null, __args);
+                    __ctx = {frame_window};
+                    var __args = {args_json}.map(deserializeArg);
+                    __args.push(function(result) {{
+                        try {{
+                            __done(({SERIALIZE_RESULT_JS})(result, __ctx));
+                        }} catch (e) {{
+                            __done(null, e.message || String(e));
+                        }}
+                    }});
+                    {function}.apply(null, __args);
                 }} catch (e) {{
                     __done(null, e.message || String(e));
                 }}
             }})()"
         );
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let plugin_args = AsyncScriptArgs {
             async_id,
@@ -203,62 +233,39 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
             if error_msg.to_lowercase().contains("timeout") {
                 Err(WebDriverErrorResponse::script_timeout())
             } else {
-                Err(WebDriverErrorResponse::javascript_error(error_msg, None))
+                Err(WebDriverErrorResponse::from_js_error(error_msg, None))
             }
         }
     }
 
     async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
-
-        let args = ScreenshotArgs {
-            timeout_ms: self.timeouts.script_ms,
-        };
-
-        let result: JsResult = webdriver
-            .0
-            .run_mobile_plugin_async("takeScreenshot", args)
-            .await
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
-
-        if result.success {
-            if let Some(Value::String(base64)) = result.value {
-                Ok(base64)
-            } else {
-                Err(WebDriverErrorResponse::unknown_error(
-                    "Screenshot returned invalid data",
-                ))
-            }
-        } else {
-            Err(WebDriverErrorResponse::unknown_error(
-                result.error.as_deref().unwrap_or("Screenshot failed"),
-            ))
-        }
+        self.snapshot(None, false).await
     }
 
     async fn take_element_screenshot(
         &self,
         js_var: &str,
     ) -> Result<String, WebDriverErrorResponse> {
-        // Scroll element into view first
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
-                return true;
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
+        let clip = self.get_element_screenshot_clip(js_var).await?;
+        self.snapshot(Some(clip), false).await
+    }
 
-        // Take full screenshot (element clipping can be added later)
-        self.take_screenshot().await
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        self.snapshot(None, true).await
     }
 
     async fn print_page(&self, options: PrintOptions) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        if options
+            .page_ranges
+            .as_ref()
+            .is_some_and(|ranges| !ranges.is_empty())
+        {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "Printing page ranges is not supported on Android",
+            ));
+        }
+
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let result: JsResult = webdriver
             .0
@@ -288,8 +295,9 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
         x: i32,
         y: i32,
         _button: u32,
+        pointer: &PointerInput,
     ) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let touch_type = match event_type {
             PointerEventType::Down => "down",
@@ -301,6 +309,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
             r#type: touch_type.to_string(),
             x,
             y,
+            pointer_id: pointer.pointer_id,
         };
 
         let _result: Value = webdriver
@@ -312,9 +321,19 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
         Ok(())
     }
 
+    // Native touches produce their own boundary events, and touch input has no hover state
+    async fn update_hover(
+        &self,
+        _source_id: &str,
+        _x: i32,
+        _y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        Ok(())
+    }
+
     // Alert handling via plugin
     async fn get_alert_text(&self) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let result: AlertResult = webdriver
             .0
@@ -334,7 +353,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
     }
 
     async fn accept_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -352,7 +371,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
     }
 
     async fn dismiss_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -370,7 +389,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
     }
 
     async fn send_alert_text(&self, text: &str) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -403,12 +422,12 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
 
     async fn get_all_cookies(&self) -> Result<Vec<Cookie>, WebDriverErrorResponse> {
         let url = self
-            .window
+            .webview
             .url()
             .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?
             .to_string();
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let result: CookiesResult = webdriver
             .0
@@ -442,7 +461,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
 
     async fn add_cookie(&self, mut cookie: Cookie) -> Result<(), WebDriverErrorResponse> {
         let url = self
-            .window
+            .webview
             .url()
             .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
 
@@ -456,7 +475,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
             cookie.path = Some("/".to_string());
         }
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -482,12 +501,12 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
 
     async fn delete_cookie(&self, name: &str) -> Result<(), WebDriverErrorResponse> {
         let url = self
-            .window
+            .webview
             .url()
             .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?
             .to_string();
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -505,7 +524,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
     }
 
     async fn delete_all_cookies(&self) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -522,7 +541,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for AndroidExecutor<R> {
 
     async fn get_window_rect(&self) -> Result<WindowRect, WebDriverErrorResponse> {
         // Get viewport size from Kotlin plugin
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         webdriver
             .0
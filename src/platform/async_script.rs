@@ -0,0 +1,88 @@
+//! Pending `execute_async_script` operations on desktop platforms whose JavaScript
+//! evaluation can't await a Promise on its own.
+//!
+//! The injected wrapper posts `{ handler, id, result, error }` to a native message
+//! handler, which completes the matching operation.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+/// Handler name used for postMessage calls
+pub const HANDLER_NAME: &str = "webdriver_async";
+
+/// Shared state for pending async script operations.
+/// This is managed via Tauri's state system (`app.manage()`).
+#[derive(Default)]
+pub struct AsyncScriptState {
+    pending: Mutex<HashMap<String, oneshot::Sender<Result<Value, String>>>>,
+}
+
+impl AsyncScriptState {
+    /// Register a pending async operation. It is cancelled when the returned handle is
+    /// dropped, however the wait for it ends.
+    pub fn register(&self, id: String) -> PendingScript<'_> {
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id.clone(), tx);
+        }
+        PendingScript {
+            state: self,
+            id,
+            receiver: rx,
+        }
+    }
+
+    /// Complete a pending async operation with a result
+    pub fn complete(&self, id: &str, result: Result<Value, String>) {
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(tx) = pending.remove(id) {
+                let _ = tx.send(result);
+            }
+        }
+    }
+
+    /// Complete the operation a posted message belongs to. Messages for other handlers
+    /// are ignored.
+    pub fn complete_message(&self, message: &Value) {
+        if message.get("handler").and_then(Value::as_str) != Some(HANDLER_NAME) {
+            return;
+        }
+        let Some(id) = message.get("id").and_then(Value::as_str) else {
+            tracing::warn!("Message missing 'id' field");
+            return;
+        };
+
+        match message.get("error").and_then(Value::as_str) {
+            Some(error) if !error.is_empty() => self.complete(id, Err(error.to_string())),
+            _ => {
+                let result = message.get("result").cloned().unwrap_or(Value::Null);
+                self.complete(id, Ok(result));
+            }
+        }
+    }
+
+    /// Cancel a pending async operation
+    pub fn cancel(&self, id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(id);
+        }
+    }
+}
+
+/// A registered async operation. Dropping it unregisters the operation, so waits that
+/// time out, fail or are abandoned along with their request don't leave it behind.
+pub struct PendingScript<'a> {
+    state: &'a AsyncScriptState,
+    id: String,
+    /// Receives the outcome the page posts back
+    pub receiver: oneshot::Receiver<Result<Value, String>>,
+}
+
+impl Drop for PendingScript<'_> {
+    fn drop(&mut self) {
+        self.state.cancel(&self.id);
+    }
+}
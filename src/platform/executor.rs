@@ -1,16 +1,829 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::webview::Cookie as TauriCookie;
-use tauri::{Runtime, WebviewWindow};
+use tauri::{Runtime, Webview, Window};
 
-#[cfg(desktop)]
-use tauri::{PhysicalPosition, PhysicalSize};
+use tauri::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
 
 use tauri::Manager;
 
-use crate::platform::alert_state::{AlertStateManager, AlertType};
 use crate::server::response::WebDriverErrorResponse;
+use crate::webdriver::alert::{AlertStateManager, AlertType};
+use crate::webdriver::element::OptionSelector;
+use crate::webdriver::keys::{
+    is_modifier_key, is_shifted_character, key_code, parse_key_sequence, KeyInput, NULL_KEY,
+};
+#[cfg(desktop)]
+use crate::webdriver::window::fit_to_work_areas;
+
+/// JavaScript expression for the registry symbol keying the element reference store
+pub const ELEMENT_REFS_SYMBOL_JS: &str = "Symbol.for('tauri-plugin-webdriver:element-refs')";
+
+/// JavaScript expression for the element reference store of the current realm.
+/// References live in a `Map` behind a non-enumerable symbol property of `window`, so they
+/// neither show up among the page's globals nor can be clobbered by it.
+pub const ELEMENT_REFS_JS: &str = "(window[Symbol.for('tauri-plugin-webdriver:element-refs')] || Object.defineProperty(window, Symbol.for('tauri-plugin-webdriver:element-refs'), { value: new Map() })[Symbol.for('tauri-plugin-webdriver:element-refs')])";
+
+/// JavaScript function deserializing script arguments in the realm of `global`.
+/// Web element, shadow root, window and frame references are resolved to live objects.
+pub const DESERIALIZE_ARG_JS: &str = r"function(value, global) {
+    var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
+    var SHADOW_KEY = 'shadow-6066-11e4-a52e-4f735466cecf';
+    var WINDOW_KEY = 'window-fcc6-11e5-b4f8-330a88ab9d7f';
+    var FRAME_KEY = 'frame-075b-4da1-b6ba-e579c2d3230a';
+    var refs = global[Symbol.for('tauri-plugin-webdriver:element-refs')] || new Map();
+
+    function deserialize(arg) {
+        if (arg === null || arg === undefined) return arg;
+        if (Array.isArray(arg)) return arg.map(deserialize);
+        if (typeof arg === 'object') {
+            if (arg[ELEMENT_KEY]) {
+                var el = refs.get(arg[ELEMENT_KEY]);
+                if (!el || !el.isConnected) throw new Error('__wd:stale element reference');
+                return el;
+            }
+            if (arg[SHADOW_KEY]) {
+                var shadow = refs.get(arg[SHADOW_KEY]);
+                if (!shadow) throw new Error('__wd:no such shadow root');
+                if (!shadow.host || !shadow.host.isConnected) throw new Error('__wd:detached shadow root');
+                return shadow;
+            }
+            // Window handles are validated by the server, only the current one gets here
+            if (arg[WINDOW_KEY]) return global.top;
+            if (arg[FRAME_KEY]) {
+                var frame = refs.get(arg[FRAME_KEY]);
+                if (!frame || frame.closed) throw new Error('__wd:no such frame');
+                return frame;
+            }
+            var result = {};
+            for (var key in arg) {
+                if (Object.prototype.hasOwnProperty.call(arg, key)) result[key] = deserialize(arg[key]);
+            }
+            return result;
+        }
+        return arg;
+    }
+
+    return deserialize(value);
+}";
+
+/// JavaScript function storing found elements in the element reference store of `global`
+/// and returning their keys. An element that is already stored keeps its existing key.
+pub const STORE_ELEMENTS_JS: &str = r"function(elements, global) {
+    var symbol = Symbol.for('tauri-plugin-webdriver:element-refs');
+    var refs = global[symbol] || Object.defineProperty(global, symbol, { value: new Map() })[symbol];
+    var keys = new Map();
+    refs.forEach(function(node, key) { keys.set(node, key); });
+
+    return Array.prototype.map.call(elements, function(el) {
+        var key = keys.get(el);
+        if (!key) {
+            key = global.crypto && global.crypto.randomUUID ? global.crypto.randomUUID() :
+                'xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx'.replace(/[xy]/g, function(c) {
+                    var r = Math.random() * 16 | 0;
+                    return (c === 'x' ? r : (r & 0x3 | 0x8)).toString(16);
+                });
+            refs.set(key, el);
+            keys.set(el, key);
+        }
+        return key;
+    });
+}";
+
+/// JavaScript function serializing a script result into JSON-safe data.
+/// Elements, shadow roots and frame windows are stored in the element reference store of
+/// `global` and replaced by `WebDriver` references. The current top-level window becomes a
+/// window reference holding `current`, which the server swaps for the window handle.
+/// Collections become arrays and cycles are rejected. Other objects follow the JSON clone
+/// rules: `toJSON` is honored (so dates become ISO strings), and everything else, including
+/// regular expressions, maps, sets and typed arrays, is cloned from its own enumerable
+/// properties. Symbols and bigints are rejected instead of silently becoming null.
+pub const SERIALIZE_RESULT_JS: &str = r"function(value, global) {
+    var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
+    var SHADOW_KEY = 'shadow-6066-11e4-a52e-4f735466cecf';
+    var WINDOW_KEY = 'window-fcc6-11e5-b4f8-330a88ab9d7f';
+    var FRAME_KEY = 'frame-075b-4da1-b6ba-e579c2d3230a';
+    var symbol = Symbol.for('tauri-plugin-webdriver:element-refs');
+    var refs = global[symbol] || Object.defineProperty(global, symbol, { value: new Map() })[symbol];
+    var seen = [];
+
+    function newKey() {
+        if (global.crypto && global.crypto.randomUUID) return global.crypto.randomUUID();
+        return 'xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx'.replace(/[xy]/g, function(c) {
+            var r = Math.random() * 16 | 0;
+            return (c === 'x' ? r : (r & 0x3 | 0x8)).toString(16);
+        });
+    }
+
+    function reference(node) {
+        var key = null;
+        refs.forEach(function(stored, storedKey) {
+            if (stored === node) key = storedKey;
+        });
+        if (!key) {
+            key = newKey();
+            refs.set(key, node);
+        }
+        return key;
+    }
+
+    function isCollection(v) {
+        var tag = Object.prototype.toString.call(v);
+        return Array.isArray(v) || tag === '[object NodeList]' || tag === '[object HTMLCollection]' ||
+            tag === '[object Arguments]' || tag === '[object HTMLOptionsCollection]' ||
+            tag === '[object HTMLFormControlsCollection]' || tag === '[object HTMLAllCollection]' ||
+            tag === '[object FileList]';
+    }
+
+    function clone(v) {
+        if (v === null || v === undefined) return null;
+        var type = typeof v;
+        if (type === 'boolean' || type === 'string') return v;
+        if (type === 'number') return isFinite(v) ? v : null;
+        // document.all is a collection even though typeof reports undefined
+        if (type !== 'object' && type !== 'function' && !isCollection(v)) {
+            throw new Error('javascript error: values of type ' + type + ' cannot be serialized');
+        }
+
+        // Checked first, as cross-origin windows throw on most property accesses
+        if (v.window === v && v.self === v) {
+            var windowRef = {};
+            if (v === global.top) {
+                windowRef[WINDOW_KEY] = 'current';
+            } else if (v.top === v) {
+                throw new Error('javascript error: windows of other top-level browsing contexts cannot be serialized');
+            } else {
+                windowRef[FRAME_KEY] = reference(v);
+            }
+            return windowRef;
+        }
+
+        if (v.nodeType === 1) {
+            if (!v.isConnected) throw new Error('__wd:stale element reference');
+            var element = {};
+            element[ELEMENT_KEY] = reference(v);
+            return element;
+        }
+
+        if (v.nodeType === 11 && v.host) {
+            if (!v.host.isConnected) throw new Error('__wd:detached shadow root');
+            var shadowRoot = {};
+            shadowRoot[SHADOW_KEY] = reference(v);
+            return shadowRoot;
+        }
+
+        if (seen.indexOf(v) !== -1) throw new Error('javascript error: cyclic object value');
+        seen.push(v);
+        var out;
+        if (isCollection(v)) {
+            out = [];
+            for (var i = 0; i < v.length; i++) out.push(clone(v[i]));
+        } else if (typeof v.toJSON === 'function') {
+            out = v.toJSON();
+        } else {
+            out = {};
+            for (var key in v) {
+                if (Object.prototype.hasOwnProperty.call(v, key)) out[key] = clone(v[key]);
+            }
+        }
+        seen.pop();
+        return out;
+    }
+
+    return clone(value);
+}";
+
+/// JavaScript function computing the rendered text of an element.
+pub const VISIBLE_TEXT_JS: &str = r"function(root) {
+    var SKIPPED = /^(HEAD|SCRIPT|STYLE|NOSCRIPT|TEMPLATE|TITLE|META|LINK)$/;
+    var BLOCK = /^(block|flex|grid|list-item|table|table-caption|table-row|table-row-group|table-header-group|table-footer-group|flow-root)$/;
+
+    function isShown(el) {
+        if (el.tagName === 'INPUT' && el.type === 'hidden') return false;
+        for (var node = el; node && node.nodeType === 1; node = node.parentElement || (node.getRootNode() && node.getRootNode().host)) {
+            var style = window.getComputedStyle(node);
+            if (style.display === 'none') return false;
+            if (parseFloat(style.opacity) === 0) return false;
+        }
+        return true;
+    }
+
+    function transform(text, style) {
+        switch (style.textTransform) {
+            case 'uppercase': return text.toUpperCase();
+            case 'lowercase': return text.toLowerCase();
+            case 'capitalize': return text.replace(/(^|[^\w\u00C0-\uFFFF])([\w\u00C0-\uFFFF])/g, function(m, p, c) { return p + c.toUpperCase(); });
+            default: return text;
+        }
+    }
+
+    var lines = [''];
+    function current() { return lines[lines.length - 1]; }
+    function append(text) { lines[lines.length - 1] = current() + text; }
+    function newLine() { if (current() !== '') lines.push(''); }
+
+    function appendText(node, style) {
+        if (style.visibility === 'hidden' || style.visibility === 'collapse') return;
+        var text = node.nodeValue;
+        var ws = style.whiteSpace;
+        if (ws === 'pre' || ws === 'pre-wrap' || ws === 'break-spaces') {
+            text = text.replace(/\r\n?/g, '\n');
+        } else if (ws === 'pre-line') {
+            text = text.replace(/[ \t\f\r]+/g, ' ').replace(/ ?\n ?/g, '\n');
+        } else {
+            // Only ASCII whitespace collapses; non-breaking spaces survive
+            text = text.replace(/[ \t\n\r\f]+/g, ' ');
+        }
+        text = transform(text, style);
+        // Drop collapsible leading space when the line already ends in whitespace
+        if (ws !== 'pre' && ws !== 'pre-wrap' && ws !== 'break-spaces' && /(^|[ \t\n\r\f])$/.test(current())) {
+            text = text.replace(/^ /, '');
+        }
+        var parts = text.split('\n');
+        append(parts[0]);
+        for (var i = 1; i < parts.length; i++) {
+            lines.push(parts[i]);
+        }
+    }
+
+    function walk(el) {
+        if (SKIPPED.test(el.tagName) || !isShown(el)) return;
+        if (el.tagName === 'BR') { lines.push(''); return; }
+
+        var style = window.getComputedStyle(el);
+        var block = BLOCK.test(style.display) || el.tagName === 'HR';
+        if (block) newLine();
+        if (style.display === 'table-cell' && current() !== '' && !/[ \t\n\r\f]$/.test(current())) append(' ');
+
+        for (var child = el.firstChild; child; child = child.nextSibling) {
+            if (child.nodeType === 3) {
+                appendText(child, style);
+            } else if (child.nodeType === 1) {
+                walk(child);
+            }
+        }
+
+        if (block) newLine();
+    }
+
+    if (!isShown(root)) return '';
+    walk(root);
+
+    return lines
+        .map(function(line) { return line.replace(/^[ \t]+|[ \t]+$/g, '').replace(/\u00a0/g, ' '); })
+        .join('\n')
+        .replace(/^\n+|\n+$/g, '');
+}";
+
+/// JavaScript function checking that an element is rendered and can receive input.
+/// Throws `element not interactable` when it is hidden, disabled or not focusable.
+pub const ASSERT_INTERACTABLE_JS: &str = r"function(el) {
+    for (var node = el; node && node.nodeType === 1; node = node.parentElement || (node.getRootNode() && node.getRootNode().host)) {
+        var style = window.getComputedStyle(node);
+        if (style.display === 'none') {
+            throw new Error('__wd:element not interactable: element is not displayed');
+        }
+    }
+    var style = window.getComputedStyle(el);
+    if (style.visibility === 'hidden' || style.visibility === 'collapse' || el.getClientRects().length === 0) {
+        throw new Error('__wd:element not interactable: element is not displayed');
+    }
+    if (el.disabled || (el.closest && el.closest('fieldset:disabled') && el.matches('input, select, textarea, button'))) {
+        throw new Error('__wd:element not interactable: element is disabled');
+    }
+    if (el.inert || (el.closest && el.closest('[inert]'))) {
+        throw new Error('__wd:element not interactable: element is inert');
+    }
+}";
+
+/// JavaScript function returning whether an element is a mutable form control or editing host
+pub const IS_EDITABLE_JS: &str = r"function(el) {
+    if (el.isContentEditable) return true;
+    var tag = el.tagName;
+    if (tag === 'TEXTAREA') return !el.disabled && !el.readOnly;
+    if (tag !== 'INPUT') return false;
+    var type = (el.type || 'text').toLowerCase();
+    var textual = ['text', 'search', 'url', 'tel', 'email', 'password', 'date', 'month', 'week',
+        'time', 'datetime-local', 'number', 'range', 'color', 'file'];
+    return textual.indexOf(type) !== -1 && !el.disabled && !el.readOnly;
+}";
+
+/// Initialization script recording closed shadow roots so `WebDriver` can reach them.
+/// Installed by `Builder::expose_closed_shadow_roots`, read back by Get Element Shadow Root
+/// when the session enables the `closedShadowRoots` option.
+pub const CLOSED_SHADOW_ROOTS_JS: &str = r"(function() {
+    var symbol = Symbol.for('tauri-plugin-webdriver:closed-shadow-roots');
+    if (window[symbol] || !window.Element || !Element.prototype.attachShadow) return;
+    var roots = new WeakMap();
+    Object.defineProperty(window, symbol, { value: roots });
+    var attachShadow = Element.prototype.attachShadow;
+    Element.prototype.attachShadow = function(init) {
+        var root = attachShadow.call(this, init);
+        if (init && init.mode === 'closed') roots.set(this, root);
+        return root;
+    };
+})();";
+
+/// Script buffering console messages, uncaught exceptions and unhandled promise rejections
+/// of the document as `WebDriver` log entries. Installed as a preload script by sessions with the
+/// `captureLogs` option and drained by [`PlatformExecutor::take_logs`].
+pub const LOG_CAPTURE_JS: &str = r"(function() {
+    var symbol = Symbol.for('tauri-plugin-webdriver:log');
+    var uninstallSymbol = Symbol.for('tauri-plugin-webdriver:log-uninstall');
+    if (window[symbol]) return;
+    var entries = [];
+    Object.defineProperty(window, symbol, { value: entries, configurable: true });
+    var push = function(level, source, message) {
+        if (entries.length >= 1000) entries.shift();
+        entries.push({ timestamp: Date.now(), level: level, source: source, message: String(message) });
+    };
+    var onError = function(event) {
+        var location = event.filename ? ' (' + event.filename + ':' + event.lineno + ':' + event.colno + ')' : '';
+        var error = event.error;
+        push('SEVERE', 'javascript', ((error && error.stack) || event.message) + location);
+    };
+    var onRejection = function(event) {
+        var reason = event.reason;
+        var message = reason && reason.stack ? reason.stack : (reason && reason.message) || reason;
+        push('SEVERE', 'javascript', 'Unhandled promise rejection: ' + message);
+    };
+    window.addEventListener('error', onError);
+    window.addEventListener('unhandledrejection', onRejection);
+    var format = function(arg) {
+        if (typeof arg === 'string') return arg;
+        if (arg instanceof Error) return arg.stack || arg.message;
+        try {
+            var json = JSON.stringify(arg);
+            if (json !== undefined) return json;
+        } catch (e) {}
+        return String(arg);
+    };
+    var levels = { error: 'SEVERE', warn: 'WARNING', info: 'INFO', log: 'INFO', debug: 'DEBUG' };
+    var originals = {};
+    var wrappers = {};
+    Object.keys(levels).forEach(function(method) {
+        var original = console[method];
+        if (typeof original !== 'function') return;
+        originals[method] = original;
+        console[method] = wrappers[method] = function() {
+            try {
+                push(levels[method], 'console-api', Array.prototype.map.call(arguments, format).join(' '));
+            } catch (e) {}
+            return original.apply(this, arguments);
+        };
+    });
+    // Undo all of the above, leaving console methods the page replaced since alone
+    Object.defineProperty(window, uninstallSymbol, { configurable: true, value: function() {
+        window.removeEventListener('error', onError);
+        window.removeEventListener('unhandledrejection', onRejection);
+        Object.keys(wrappers).forEach(function(method) {
+            if (console[method] === wrappers[method]) console[method] = originals[method];
+        });
+        delete window[symbol];
+        delete window[uninstallSymbol];
+    } });
+})();";
+
+/// JavaScript function scrolling an element into view and returning its in-view center point
+/// as `{ x, y }` viewport coordinates. The visible area is clipped by the viewport and any
+/// overflow containers, and fixed or sticky elements covering it (e.g. a navbar) are skipped.
+/// Throws `element not interactable` when no part of the element can be brought into view.
+pub const IN_VIEW_CENTER_JS: &str = r"function(el) {
+    var view = el.ownerDocument.defaultView || window;
+
+    function visibleRect(node) {
+        var rect = node.getClientRects()[0];
+        if (!rect) return null;
+        var box = { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom };
+        // Clip by every scroll container between the element and the viewport
+        for (var parent = node.parentElement; parent; parent = parent.parentElement) {
+            var overflow = view.getComputedStyle(parent);
+            if (overflow.overflowX === 'visible' && overflow.overflowY === 'visible') continue;
+            var clip = parent.getBoundingClientRect();
+            box.left = Math.max(box.left, clip.left);
+            box.top = Math.max(box.top, clip.top);
+            box.right = Math.min(box.right, clip.right);
+            box.bottom = Math.min(box.bottom, clip.bottom);
+        }
+        box.left = Math.max(0, box.left);
+        box.top = Math.max(0, box.top);
+        box.right = Math.min(view.innerWidth, box.right);
+        box.bottom = Math.min(view.innerHeight, box.bottom);
+        if (box.right <= box.left || box.bottom <= box.top) return null;
+        return box;
+    }
+
+    function obstruction(box) {
+        var x = (box.left + box.right) / 2;
+        var y = (box.top + box.bottom) / 2;
+        var root = el.getRootNode();
+        var hit = (root.elementFromPoint ? root : el.ownerDocument).elementFromPoint(x, y);
+        if (!hit || hit === el || el.contains(hit) || hit.contains(el)) return null;
+        for (var node = hit; node && node.nodeType === 1; node = node.parentElement) {
+            var position = view.getComputedStyle(node).position;
+            if (position === 'fixed' || position === 'sticky') return node.getBoundingClientRect();
+        }
+        return null;
+    }
+
+    el.scrollIntoView({ block: 'center', inline: 'center' });
+    var box = visibleRect(el);
+    if (!box) {
+        throw new Error('__wd:element not interactable: element is not in the viewport');
+    }
+
+    // Move the point off fixed and sticky overlays into the largest uncovered band
+    for (var attempt = 0; attempt < 3; attempt++) {
+        var cover = obstruction(box);
+        if (!cover) break;
+        var bands = [
+            { left: box.left, top: box.top, right: box.right, bottom: Math.min(box.bottom, cover.top) },
+            { left: box.left, top: Math.max(box.top, cover.bottom), right: box.right, bottom: box.bottom },
+            { left: box.left, top: box.top, right: Math.min(box.right, cover.left), bottom: box.bottom },
+            { left: Math.max(box.left, cover.right), top: box.top, right: box.right, bottom: box.bottom }
+        ].filter(function(band) {
+            return band.right > band.left && band.bottom > band.top;
+        }).sort(function(a, b) {
+            return (b.right - b.left) * (b.bottom - b.top) - (a.right - a.left) * (a.bottom - a.top);
+        });
+        if (!bands.length) break;
+        box = bands[0];
+    }
+
+    return {
+        x: Math.floor((box.left + box.right) / 2),
+        y: Math.floor((box.top + box.bottom) / 2)
+    };
+}";
+
+/// JavaScript function computing an element's accessibility role.
+/// Shared by Get Computed Role and the `aria` locator strategy.
+pub const COMPUTED_ROLE_JS: &str = r"function(el) {
+    // Engines that expose their own computed role know best
+    if (typeof el.computedRole === 'string' && el.computedRole) return el.computedRole;
+
+    // Non-abstract WAI-ARIA 1.2 and graphics roles. The first valid token of a role
+    // attribute wins, so unknown fallbacks are skipped.
+    var ROLES = ('alert alertdialog application article banner blockquote button caption cell ' +
+        'checkbox code columnheader combobox comment complementary contentinfo definition ' +
+        'deletion dialog directory document emphasis feed figure form generic graphics-document ' +
+        'graphics-object graphics-symbol grid gridcell group heading image img insertion link ' +
+        'list listbox listitem log main mark marquee math menu menubar menuitem ' +
+        'menuitemcheckbox menuitemradio meter navigation none note option paragraph ' +
+        'presentation progressbar radio radiogroup region row rowgroup rowheader scrollbar ' +
+        'search searchbox separator slider spinbutton status strong subscript suggestion ' +
+        'superscript switch tab table tablist tabpanel term textbox time timer toolbar tooltip ' +
+        'tree treegrid treeitem').split(' ');
+    var roleOf = function(node) {
+        var tokens = (node.getAttribute('role') || '').trim().toLowerCase().split(/\s+/);
+        for (var i = 0; i < tokens.length; i++) {
+            if (ROLES.indexOf(tokens[i]) !== -1) return tokens[i];
+        }
+        return '';
+    };
+    var presentational = function(node) {
+        var role = roleOf(node);
+        return role === 'none' || role === 'presentation';
+    };
+    var focusable = function(node) {
+        return node.tabIndex >= 0 || node.hasAttribute('tabindex');
+    };
+    var hasGlobalAria = function(node) {
+        var globals = ['aria-describedby', 'aria-details', 'aria-keyshortcuts', 'aria-label',
+            'aria-labelledby', 'aria-live', 'aria-owns', 'aria-relevant', 'aria-roledescription'];
+        return globals.some(function(name) { return node.hasAttribute(name); });
+    };
+    var hasName = function(node) {
+        return !!((node.getAttribute('aria-label') || '').trim() ||
+            node.getAttribute('aria-labelledby') || (node.getAttribute('title') || '').trim());
+    };
+
+    // Explicit role, unless none/presentation is overridden by focusability or global ARIA
+    var explicitRole = roleOf(el);
+    if (explicitRole && !(presentational(el) && (focusable(el) || hasGlobalAria(el)))) {
+        return explicitRole;
+    }
+
+    var tag = el.tagName.toLowerCase();
+    var type = (el.getAttribute('type') || '').toLowerCase();
+    var parent = el.parentElement;
+
+    // Landmarks nested in sectioning content lose their landmark role
+    var sectioned = function(node) {
+        return !!(node.parentElement && node.parentElement.closest(
+            'article, aside, main, nav, section, [role=article], [role=complementary], ' +
+            '[role=main], [role=navigation], [role=region]'));
+    };
+
+    // Required owned elements inherit presentation from their table or list
+    var table = el.closest('table');
+    var tableRole = table ? roleOf(table) : '';
+    var inPresentationalTable = !!table && presentational(table) && !focusable(table);
+    var inGrid = tableRole === 'grid' || tableRole === 'treegrid';
+
+    switch (tag) {
+        case 'a':
+        case 'area':
+            return el.hasAttribute('href') ? 'link' : 'generic';
+        case 'article': return 'article';
+        case 'aside': return !sectioned(el) || hasName(el) ? 'complementary' : 'generic';
+        case 'header': return sectioned(el) ? 'generic' : 'banner';
+        case 'footer': return sectioned(el) ? 'generic' : 'contentinfo';
+        case 'section': return hasName(el) ? 'region' : 'generic';
+        case 'form': return hasName(el) ? 'form' : 'generic';
+        case 'main': return 'main';
+        case 'nav': return 'navigation';
+        case 'search': return 'search';
+        case 'address':
+        case 'details':
+        case 'fieldset':
+        case 'hgroup':
+        case 'optgroup':
+            return 'group';
+        case 'blockquote': return 'blockquote';
+        case 'button': return 'button';
+        case 'caption': return inPresentationalTable ? '' : 'caption';
+        case 'code': return 'code';
+        case 'datalist': return 'listbox';
+        case 'dd': return 'definition';
+        case 'del':
+        case 's':
+            return 'deletion';
+        case 'dfn':
+        case 'dt':
+            return 'term';
+        case 'dialog': return 'dialog';
+        case 'em': return 'emphasis';
+        case 'figure': return 'figure';
+        case 'h1':
+        case 'h2':
+        case 'h3':
+        case 'h4':
+        case 'h5':
+        case 'h6':
+            return 'heading';
+        case 'hr': return 'separator';
+        case 'html': return 'document';
+        case 'img':
+            if (el.getAttribute('alt') === '' && !hasName(el)) return 'presentation';
+            return 'img';
+        case 'ins': return 'insertion';
+        case 'li':
+            if (parent && presentational(parent) && !focusable(parent)) return '';
+            return parent && /^(ul|ol|menu)$/i.test(parent.tagName) ? 'listitem' : 'generic';
+        case 'mark': return 'mark';
+        case 'math': return 'math';
+        case 'menu':
+        case 'ol':
+        case 'ul':
+            return 'list';
+        case 'meter': return 'meter';
+        case 'option': return el.closest('select, datalist') ? 'option' : '';
+        case 'output': return 'status';
+        case 'p': return 'paragraph';
+        case 'progress': return 'progressbar';
+        case 'select': return el.multiple || el.size > 1 ? 'listbox' : 'combobox';
+        case 'strong': return 'strong';
+        case 'sub': return 'subscript';
+        case 'summary':
+            return parent && parent.tagName === 'DETAILS' && parent.querySelector('summary') === el ? 'button' : '';
+        case 'sup': return 'superscript';
+        case 'svg': return 'graphics-document';
+        case 'table': return 'table';
+        case 'tbody':
+        case 'tfoot':
+        case 'thead':
+            return inPresentationalTable ? '' : 'rowgroup';
+        case 'td':
+            if (inPresentationalTable) return '';
+            return inGrid ? 'gridcell' : 'cell';
+        case 'textarea': return 'textbox';
+        case 'th':
+            if (inPresentationalTable) return '';
+            var scope = (el.getAttribute('scope') || '').toLowerCase();
+            if (scope === 'row' || scope === 'rowgroup') return 'rowheader';
+            if (scope === 'col' || scope === 'colgroup') return 'columnheader';
+            // Headers in a row of data cells label that row
+            var row = el.parentElement;
+            var rowHasCells = row && Array.prototype.some.call(row.children, function(cell) {
+                return cell.tagName === 'TD';
+            });
+            return rowHasCells && !el.closest('thead') ? 'rowheader' : 'columnheader';
+        case 'time': return 'time';
+        case 'tr': return inPresentationalTable ? '' : 'row';
+        case 'b':
+        case 'bdi':
+        case 'bdo':
+        case 'body':
+        case 'data':
+        case 'div':
+        case 'i':
+        case 'pre':
+        case 'q':
+        case 'samp':
+        case 'small':
+        case 'span':
+        case 'u':
+            return 'generic';
+        case 'input':
+            var list = el.hasAttribute('list');
+            switch (type) {
+                case 'button':
+                case 'image':
+                case 'reset':
+                case 'submit':
+                    return 'button';
+                case 'checkbox': return 'checkbox';
+                case 'radio': return 'radio';
+                case 'range': return 'slider';
+                case 'number': return 'spinbutton';
+                case 'search': return list ? 'combobox' : 'searchbox';
+                case '':
+                case 'email':
+                case 'tel':
+                case 'text':
+                case 'url':
+                    return list ? 'combobox' : 'textbox';
+                // color, date, datetime-local, file, hidden, month, password, time and week
+                // have no corresponding ARIA role
+                default:
+                    return '';
+            }
+    }
+    return '';
+}";
+
+/// JavaScript function computing an element's accessible name, given [`COMPUTED_ROLE_JS`] as
+/// `computeRole`. Shared by Get Computed Label and the `aria` locator strategy.
+pub const COMPUTED_LABEL_JS: &str = r#"function(el, computeRole) {
+    var view = el.ownerDocument.defaultView || window;
+    var NAME_FROM_CONTENT = ['button', 'cell', 'checkbox', 'columnheader', 'gridcell', 'heading',
+        'link', 'menuitem', 'menuitemcheckbox', 'menuitemradio', 'option', 'radio', 'row',
+        'rowheader', 'sectionhead', 'switch', 'tab', 'tooltip', 'treeitem'];
+    var RANGE_ROLES = ['meter', 'progressbar', 'scrollbar', 'slider', 'spinbutton'];
+
+    // Same role as Get Computed Role, so both agree on which roles take their name from content
+    var roleOf = function(node) {
+        return (computeRole(node) || '').toLowerCase();
+    };
+
+    var isHidden = function(node) {
+        for (var n = node; n && n.nodeType === 1; n = n.parentElement || (n.getRootNode().host || null)) {
+            if (n.hidden || n.getAttribute('aria-hidden') === 'true') return true;
+            if (view.getComputedStyle(n).display === 'none') return true;
+        }
+        var visibility = view.getComputedStyle(node).visibility;
+        return visibility === 'hidden' || visibility === 'collapse';
+    };
+
+    var isInline = function(node) {
+        return /^inline/.test(view.getComputedStyle(node).display);
+    };
+
+    // CSS generated content from ::before / ::after
+    var pseudoText = function(node, which) {
+        var content = view.getComputedStyle(node, which).content;
+        if (!content || content === 'none' || content === 'normal') return '';
+        var strings = content.match(/"(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'/g);
+        if (!strings) return '';
+        return strings.map(function(s) {
+            return s.slice(1, -1).replace(/\\(.)/g, '$1');
+        }).join('');
+    };
+
+    // Value of a control embedded in another element's name
+    var controlValue = function(node, role) {
+        if (role === 'textbox' || role === 'searchbox') {
+            return node.value !== undefined ? node.value : node.textContent;
+        }
+        if (role === 'combobox' || role === 'listbox') {
+            if (node.tagName === 'SELECT') {
+                return Array.prototype.filter.call(node.options, function(option) {
+                    return option.selected;
+                }).map(function(option) { return option.text; }).join(' ');
+            }
+            if (node.tagName === 'INPUT') return node.value;
+            return Array.prototype.map.call(node.querySelectorAll('[aria-selected=true]'), function(option) {
+                return option.textContent;
+            }).join(' ');
+        }
+        if (RANGE_ROLES.indexOf(role) !== -1) {
+            return node.getAttribute('aria-valuetext') || node.getAttribute('aria-valuenow') ||
+                (node.value !== undefined ? String(node.value) : '');
+        }
+        return null;
+    };
+
+    var childNodesOf = function(node) {
+        if (node.shadowRoot) return node.shadowRoot.childNodes;
+        if (node.tagName === 'SLOT') {
+            var assigned = node.assignedNodes({ flatten: true });
+            if (assigned.length) return assigned;
+        }
+        return node.childNodes;
+    };
+
+    var compute;
+
+    // Name from the host language (HTML labels, alt text, captions, legends)
+    var nativeName = function(node, ctx) {
+        var tag = node.tagName.toLowerCase();
+        var nested = { visited: ctx.visited, labelledBy: ctx.labelledBy, recursive: true, hiddenOk: ctx.hiddenOk };
+        var type = (node.getAttribute('type') || '').toLowerCase();
+
+        if (node.labels && node.labels.length && type !== 'hidden') {
+            var labelText = Array.prototype.map.call(node.labels, function(label) {
+                return compute(label, nested).trim();
+            }).filter(Boolean).join(' ');
+            if (labelText) return labelText;
+        }
+        if (tag === 'input') {
+            if (type === 'submit') return node.value || 'Submit';
+            if (type === 'reset') return node.value || 'Reset';
+            if (type === 'button') return node.value || '';
+            if (type === 'image') {
+                // The title would otherwise lose to the default name
+                return node.getAttribute('alt') || (node.getAttribute('title') || '').trim() ||
+                    'Submit Query';
+            }
+        }
+        if (tag === 'img' || tag === 'area') return (node.getAttribute('alt') || '').trim();
+        var caption = null;
+        if (tag === 'fieldset') caption = node.querySelector(':scope > legend');
+        if (tag === 'figure') caption = node.querySelector(':scope > figcaption');
+        if (tag === 'table') caption = node.caption;
+        if (caption) return compute(caption, nested).trim();
+        if (tag === 'svg') {
+            var svgTitle = node.querySelector(':scope > title');
+            if (svgTitle) return svgTitle.textContent.trim();
+        }
+        return '';
+    };
+
+    compute = function(node, ctx) {
+        if (node.nodeType === 3) return node.data;
+        if (node.nodeType !== 1 || ctx.visited.indexOf(node) !== -1) return '';
+        ctx.visited.push(node);
+
+        // Hidden nodes only count when reached through a hidden aria-labelledby reference
+        if (!ctx.hiddenOk && isHidden(node)) return '';
+
+        var role = roleOf(node);
+
+        // aria-labelledby, not followed recursively
+        var labelledBy = (node.getAttribute('aria-labelledby') || '').trim();
+        if (labelledBy && !ctx.labelledBy) {
+            var root = node.getRootNode();
+            var parts = labelledBy.split(/\s+/).map(function(id) {
+                var ref = root.getElementById ? root.getElementById(id) : null;
+                if (!ref) return '';
+                return compute(ref, { visited: [], labelledBy: true, recursive: true, hiddenOk: isHidden(ref) }).trim();
+            }).filter(Boolean);
+            if (parts.length) return parts.join(' ');
+        }
+
+        // Controls embedded in another element's name contribute their value
+        if (ctx.recursive) {
+            var value = controlValue(node, role);
+            if (value !== null) return value;
+        }
+
+        var ariaLabel = (node.getAttribute('aria-label') || '').trim();
+        if (ariaLabel) return ariaLabel;
+
+        if (role !== 'none' && role !== 'presentation') {
+            var native = nativeName(node, ctx);
+            if (native) return native;
+        }
+
+        // Name from content
+        if (ctx.recursive || NAME_FROM_CONTENT.indexOf(role) !== -1) {
+            var text = pseudoText(node, '::before');
+            var childCtx = { visited: ctx.visited, labelledBy: ctx.labelledBy, recursive: true, hiddenOk: ctx.hiddenOk };
+            Array.prototype.forEach.call(childNodesOf(node), function(child) {
+                var part = compute(child, childCtx);
+                text += child.nodeType === 1 && !isInline(child) ? ' ' + part + ' ' : part;
+            });
+            text += pseudoText(node, '::after');
+            text = text.replace(/\s+/g, ' ').trim();
+            if (text) return text;
+        }
+
+        // Tooltip and placeholder as last resort
+        var title = (node.getAttribute('title') || '').trim();
+        if (title) return title;
+        if (node.placeholder) return node.placeholder;
+        return '';
+    };
+
+    return compute(el, { visited: [], labelledBy: false, recursive: false, hiddenOk: isHidden(el) })
+        .replace(/\s+/g, ' ').trim();
+}"#;
 
 /// Element bounding rectangle
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -21,8 +834,20 @@ pub struct ElementRect {
     pub height: f64,
 }
 
+/// Area of the top-level viewport to crop a screenshot to, in CSS pixels
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotClip {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Width of the top-level viewport, relating CSS pixels to screenshot pixels
+    pub viewport_width: f64,
+}
+
 /// Window rectangle (position and size)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WindowRect {
     #[serde(default)]
     pub x: i32,
@@ -32,8 +857,50 @@ pub struct WindowRect {
     pub height: u32,
 }
 
+impl WindowRect {
+    /// Convert a rect in physical pixels to CSS pixels
+    #[must_use]
+    pub fn to_logical(&self, scale_factor: f64) -> Self {
+        let position = PhysicalPosition::new(self.x, self.y).to_logical::<i32>(scale_factor);
+        let size = PhysicalSize::new(self.width, self.height).to_logical::<u32>(scale_factor);
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    /// Convert a rect in CSS pixels to physical pixels
+    #[must_use]
+    pub fn to_physical(&self, scale_factor: f64) -> Self {
+        let position = LogicalPosition::new(self.x, self.y).to_physical::<i32>(scale_factor);
+        let size = LogicalSize::new(self.width, self.height).to_physical::<u32>(scale_factor);
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// Native window state flags not expressible through the standard protocol
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_excessive_bools)]
+pub struct WindowState {
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+    pub focused: bool,
+    pub visible: bool,
+    pub always_on_top: bool,
+    pub decorated: bool,
+}
+
 /// Frame identifier for switching frames
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FrameId {
     /// Frame by index
     Index(u32),
@@ -41,6 +908,75 @@ pub enum FrameId {
     Element(String),
 }
 
+/// Source of the generations that tell frame selections apart
+static NEXT_FRAME_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Frame chain selected by a session.
+///
+/// The page caches the window the chain resolves to in a slot owned by the session, tagged
+/// with a generation that changes on every switch. Commands reuse the cached window while
+/// its frame element is attached and still shows it, and walk the chain again otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct FrameContext {
+    frames: Vec<FrameId>,
+    /// Session owning the cache slot, `None` for chains that are never cached
+    owner: Option<String>,
+    generation: u64,
+}
+
+impl FrameContext {
+    /// Create an empty chain whose resolved windows are cached for `owner`
+    pub fn new(owner: impl Into<String>) -> Self {
+        Self {
+            frames: Vec::new(),
+            owner: Some(owner.into()),
+            generation: 0,
+        }
+    }
+
+    /// Select a child frame of the current one
+    pub fn push(&mut self, frame: FrameId) {
+        self.frames.push(frame);
+        self.renew();
+    }
+
+    /// Select the parent frame
+    pub fn pop(&mut self) -> Option<FrameId> {
+        let frame = self.frames.pop();
+        self.renew();
+        frame
+    }
+
+    /// Select the top-level browsing context
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.renew();
+    }
+
+    /// Invalidate the window cached for the previous selection
+    fn renew(&mut self) {
+        self.generation = NEXT_FRAME_GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl From<Vec<FrameId>> for FrameContext {
+    fn from(frames: Vec<FrameId>) -> Self {
+        Self {
+            frames,
+            owner: None,
+            generation: 0,
+        }
+    }
+}
+
+impl std::ops::Deref for FrameContext {
+    type Target = [FrameId];
+
+    fn deref(&self) -> &[FrameId] {
+        &self.frames
+    }
+}
+
 /// Pointer event type
 #[derive(Debug, Clone, Copy)]
 pub enum PointerEventType {
@@ -49,6 +985,66 @@ pub enum PointerEventType {
     Move,
 }
 
+/// Device type of a pointer input source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerType {
+    #[default]
+    Mouse,
+    Pen,
+    Touch,
+}
+
+impl PointerType {
+    /// Value of `PointerEvent.pointerType`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mouse => "mouse",
+            Self::Pen => "pen",
+            Self::Touch => "touch",
+        }
+    }
+}
+
+/// Optional `PointerEvent` properties of a pointer action item
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tangential_pressure: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tilt_x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tilt_y: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub twist: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude_angle: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azimuth_angle: Option<f64>,
+}
+
+/// Pointer source state accompanying a dispatched pointer event
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PointerInput {
+    /// Identifier of the pointer source, unique within the session
+    pub pointer_id: u32,
+    pub pointer_type: PointerType,
+    pub properties: PointerProperties,
+    /// Number of consecutive clicks the current press belongs to (`MouseEvent.detail`)
+    pub click_count: u32,
+    /// Modifier keys held by key input sources
+    pub modifiers: ModifierState,
+    /// Buttons the pointer holds before the event, as a bitmask of `WebDriver` buttons
+    pub buttons: u32,
+}
+
 /// Cookie data
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Cookie {
@@ -68,6 +1064,25 @@ pub struct Cookie {
     pub same_site: Option<String>,
 }
 
+/// Web storage area of the current browsing context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageArea {
+    /// `window.localStorage`, persisted per origin
+    Local,
+    /// `window.sessionStorage`, per origin and top-level browsing context
+    Session,
+}
+
+impl StorageArea {
+    /// Name of the `Storage` object on `window`
+    pub fn as_js(self) -> &'static str {
+        match self {
+            Self::Local => "localStorage",
+            Self::Session => "sessionStorage",
+        }
+    }
+}
+
 /// Print options for PDF generation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PrintOptions {
@@ -106,16 +1121,35 @@ pub struct ModifierState {
 }
 
 impl ModifierState {
+    /// Whether the modifier for `key` is currently pressed
+    pub fn is_pressed(self, key: &str) -> bool {
+        match key {
+            "\u{E009}" | "\u{E051}" => self.ctrl,
+            "\u{E008}" | "\u{E050}" => self.shift,
+            "\u{E00A}" | "\u{E052}" => self.alt,
+            "\u{E03D}" | "\u{E053}" => self.meta,
+            _ => false,
+        }
+    }
+
     /// Update modifier state when a key is pressed or released
     pub fn update(&mut self, key: &str, is_down: bool) {
         match key {
-            "\u{E009}" => self.ctrl = is_down,  // Control
-            "\u{E008}" => self.shift = is_down, // Shift
-            "\u{E00A}" => self.alt = is_down,   // Alt
-            "\u{E03D}" => self.meta = is_down,  // Meta
+            "\u{E009}" | "\u{E051}" => self.ctrl = is_down, // Control
+            "\u{E008}" | "\u{E050}" => self.shift = is_down, // Shift
+            "\u{E00A}" | "\u{E052}" => self.alt = is_down,  // Alt
+            "\u{E03D}" | "\u{E053}" => self.meta = is_down, // Meta
             _ => {}
         }
     }
+
+    /// Modifier flags as a JavaScript `EventModifierInit` object literal
+    pub fn to_event_init(self) -> String {
+        format!(
+            "{{ ctrlKey: {}, shiftKey: {}, altKey: {}, metaKey: {} }}",
+            self.ctrl, self.shift, self.alt, self.meta
+        )
+    }
 }
 
 /// Platform-agnostic trait for `WebView` operations.
@@ -124,11 +1158,16 @@ impl ModifierState {
 #[allow(clippy::too_many_lines)]
 pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     // =========================================================================
-    // Window Access
+    // Webview Access
     // =========================================================================
 
-    /// Get a reference to the underlying window
-    fn window(&self) -> &WebviewWindow<R>;
+    /// Get a reference to the underlying webview
+    fn webview(&self) -> &Webview<R>;
+
+    /// Get the window hosting the webview
+    fn window(&self) -> Window<R> {
+        self.webview().window()
+    }
 
     // =========================================================================
     // Core JavaScript Execution
@@ -141,14 +1180,15 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     // Navigation
     // =========================================================================
 
-    /// Navigate to a URL
+    /// Navigate to a URL through the native webview rather than page script, so it works
+    /// whatever state the current document is in and triggers the page load hooks
     async fn navigate(&self, url: &str) -> Result<(), WebDriverErrorResponse> {
-        let script = format!(
-            r"window.location.href = '{}'; null;",
-            url.replace('\\', "\\\\").replace('\'', "\\'")
-        );
-        self.evaluate_js(&script).await?;
-        Ok(())
+        let url = tauri::Url::parse(url).map_err(|e| {
+            WebDriverErrorResponse::invalid_argument(&format!("Invalid URL {url:?}: {e}"))
+        })?;
+        self.webview()
+            .navigate(url)
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
     }
 
     /// Get current URL
@@ -197,109 +1237,91 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     // Element Operations
     // =========================================================================
 
-    /// Find element and store reference in a JavaScript variable
-    /// Returns true if element was found
+    /// Find an element and store it in the element reference store
+    /// Returns its reference key, which an element found before keeps, if one was found
     async fn find_element(
         &self,
         strategy_js: &str,
-        js_var: &str,
-    ) -> Result<bool, WebDriverErrorResponse> {
+    ) -> Result<Option<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
                 var el = {strategy_js};
-                if (el) {{
-                    window.{js_var} = el;
-                    return true;
-                }}
-                return false;
+                return ({STORE_ELEMENTS_JS})(el ? [el] : [], window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_bool_value(&result)
+        Ok(extract_string_list(&result)?.into_iter().next())
     }
 
-    /// Find multiple elements and store count
-    /// Returns the number of elements found
+    /// Find multiple elements and store them in a single roundtrip
+    /// Returns the reference keys of the elements found, in document order
     async fn find_elements(
         &self,
         strategy_js: &str,
-        js_var_prefix: &str,
-    ) -> Result<usize, WebDriverErrorResponse> {
+    ) -> Result<Vec<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
                 var elements = {strategy_js};
-                var count = elements.length;
-                for (var i = 0; i < count; i++) {{
-                    window['{js_var_prefix}' + i] = elements[i];
-                }}
-                return count;
+                return ({STORE_ELEMENTS_JS})(elements, window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_usize_value(&result)
+        extract_string_list(&result)
     }
 
-    /// Find element from a parent element and store reference
-    /// Returns true if element was found
+    /// Find an element from a parent element and store it
+    /// Returns its reference key, if one was found
     async fn find_element_from_element(
         &self,
         parent_js_var: &str,
         strategy_js: &str,
-        js_var: &str,
-    ) -> Result<bool, WebDriverErrorResponse> {
+    ) -> Result<Option<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var parent = window.{parent_js_var};
+                var parent = {ELEMENT_REFS_JS}.get('{parent_js_var}');
                 if (!parent || !parent.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var el = {strategy_js};
-                if (el) {{
-                    window.{js_var} = el;
-                    return true;
-                }}
-                return false;
+                return ({STORE_ELEMENTS_JS})(el ? [el] : [], window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_bool_value(&result)
+        Ok(extract_string_list(&result)?.into_iter().next())
     }
 
     /// Find multiple elements from a parent element
-    /// Returns count of elements found, stores as {prefix}0, {prefix}1, etc.
+    /// Returns the reference keys of the elements found
     async fn find_elements_from_element(
         &self,
         parent_js_var: &str,
         strategy_js: &str,
-        js_var_prefix: &str,
-    ) -> Result<usize, WebDriverErrorResponse> {
+    ) -> Result<Vec<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var parent = window.{parent_js_var};
+                var parent = {ELEMENT_REFS_JS}.get('{parent_js_var}');
                 if (!parent || !parent.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var elements = {strategy_js};
-                var count = elements.length;
-                for (var i = 0; i < count; i++) {{
-                    window['{js_var_prefix}' + i] = elements[i];
-                }}
-                return count;
+                return ({STORE_ELEMENTS_JS})(elements, window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_usize_value(&result)
+        extract_string_list(&result)
     }
 
-    /// Get element text content
+    /// Get element rendered text, following the visible text algorithm used by other drivers:
+    /// hidden content is skipped, whitespace is collapsed per `white-space`, block boundaries
+    /// become line breaks and `text-transform` is applied
     async fn get_element_text(&self, js_var: &str) -> Result<String, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
-                return el.textContent || '';
+                return ({VISIBLE_TEXT_JS})(el);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
@@ -310,9 +1332,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn get_element_tag_name(&self, js_var: &str) -> Result<String, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 return el.tagName.toLowerCase();
             }})()"
@@ -322,10 +1344,12 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     }
 
     /// Get element attribute value
-    /// Per W3C `WebDriver` spec, certain attributes should return current property values:
-    /// - "value" on input/textarea returns current value property
-    /// - "checked" on checkbox/radio returns current checked state
-    /// - "selected" on option returns current selected state
+    /// Follows the Selenium attribute atom that clients expect:
+    /// - "style" returns the serialized inline style
+    /// - "checked"/"selected" on selectable elements return the current state
+    /// - `href` on links and `src` on images return the resolved URL
+    /// - boolean attributes return "true" or null
+    /// - other names prefer a non-object property value and fall back to the attribute
     async fn get_element_attribute(
         &self,
         js_var: &str,
@@ -334,31 +1358,63 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         let escaped_name = name.replace('\\', "\\\\").replace('\'', "\\'");
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var attrName = '{escaped_name}'.toLowerCase();
                 var tagName = el.tagName.toLowerCase();
+                var BOOLEAN_ATTRIBUTES = [
+                    'allowfullscreen', 'allowpaymentrequest', 'allowusermedia', 'async',
+                    'autofocus', 'autoplay', 'checked', 'compact', 'complete', 'controls',
+                    'declare', 'default', 'defaultchecked', 'defaultselected', 'defer',
+                    'disabled', 'ended', 'formnovalidate', 'hidden', 'indeterminate',
+                    'iscontenteditable', 'ismap', 'itemscope', 'loop', 'multiple', 'muted',
+                    'nohref', 'nomodule', 'noresize', 'noshade', 'novalidate', 'nowrap',
+                    'open', 'paused', 'playsinline', 'pubdate', 'readonly', 'required',
+                    'reversed', 'scoped', 'seamless', 'seeking', 'selected', 'truespeed',
+                    'typemustmatch', 'willvalidate'
+                ];
+                var PROPERTY_ALIASES = {{ 'class': 'className', 'readonly': 'readOnly' }};
+
+                if (attrName === 'style') {{
+                    var cssText = el.style ? el.style.cssText.trim() : '';
+                    return cssText || el.getAttribute('style');
+                }}
 
-                // Per W3C WebDriver spec, return property values for certain attributes
-                if (attrName === 'value') {{
-                    if (tagName === 'input' || tagName === 'textarea') {{
-                        return el.value;
-                    }}
+                var selectable = tagName === 'option' ||
+                    (tagName === 'input' && (el.type === 'checkbox' || el.type === 'radio'));
+                if ((attrName === 'checked' || attrName === 'selected') && selectable) {{
+                    var state = tagName === 'option' ? el.selected : el.checked;
+                    return state ? 'true' : null;
                 }}
-                if (attrName === 'checked') {{
-                    if (tagName === 'input' && (el.type === 'checkbox' || el.type === 'radio')) {{
-                        return el.checked ? 'true' : null;
-                    }}
+
+                if ((tagName === 'a' && attrName === 'href') || (tagName === 'img' && attrName === 'src')) {{
+                    var url = el.getAttribute(attrName);
+                    return url ? String(el[attrName]) : url;
                 }}
-                if (attrName === 'selected') {{
-                    if (tagName === 'option') {{
-                        return el.selected ? 'true' : null;
+
+                if (attrName === 'spellcheck') {{
+                    var spellcheck = el.getAttribute(attrName);
+                    if (spellcheck !== null) {{
+                        return spellcheck.toLowerCase() === 'false' ? 'false' : 'true';
                     }}
+                    return el.spellcheck ? 'true' : 'false';
+                }}
+
+                var propName = PROPERTY_ALIASES[attrName] || attrName;
+                if (BOOLEAN_ATTRIBUTES.indexOf(attrName) !== -1) {{
+                    return (el.getAttribute(attrName) !== null || el[propName]) ? 'true' : null;
                 }}
 
-                return el.getAttribute('{escaped_name}');
+                var property;
+                try {{
+                    property = el[propName];
+                }} catch (e) {{}}
+                var value = (property === undefined || property === null || typeof property === 'object' || typeof property === 'function')
+                    ? el.getAttribute('{escaped_name}')
+                    : property;
+                return value === null || value === undefined ? null : String(value);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
@@ -383,9 +1439,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         let escaped_name = name.replace('\\', "\\\\").replace('\'', "\\'");
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 return el['{escaped_name}'];
             }})()"
@@ -403,9 +1459,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         let escaped_prop = property.replace('\\', "\\\\").replace('\'', "\\'");
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 return window.getComputedStyle(el).getPropertyValue('{escaped_prop}');
             }})()"
@@ -418,9 +1474,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn get_element_rect(&self, js_var: &str) -> Result<ElementRect, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var rect = el.getBoundingClientRect();
                 return {{
@@ -444,13 +1500,39 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         Ok(ElementRect::default())
     }
 
+    /// Scroll element into view and get its in-view center point in viewport coordinates
+    async fn get_element_in_view_center(
+        &self,
+        js_var: &str,
+    ) -> Result<(i32, i32), WebDriverErrorResponse> {
+        let script = format!(
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
+                if (!el || !el.isConnected) {{
+                    throw new Error('__wd:stale element reference');
+                }}
+                return ({IN_VIEW_CENTER_JS})(el);
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        let value = extract_value(&result)?;
+        let coordinate = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_i64)
+                .and_then(|v| i32::try_from(v).ok())
+                .unwrap_or(0)
+        };
+        Ok((coordinate("x"), coordinate("y")))
+    }
+
     /// Check if element is displayed
     async fn is_element_displayed(&self, js_var: &str) -> Result<bool, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var style = window.getComputedStyle(el);
                 return style.display !== 'none' && style.visibility !== 'hidden' && el.offsetParent !== null;
@@ -464,9 +1546,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn is_element_enabled(&self, js_var: &str) -> Result<bool, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 return !el.disabled;
             }})()"
@@ -479,9 +1561,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn is_element_selected(&self, js_var: &str) -> Result<bool, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 if (el.tagName === 'INPUT' && (el.type === 'checkbox' || el.type === 'radio')) {{
                     return el.checked;
@@ -500,32 +1582,149 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn click_element(&self, js_var: &str) -> Result<(), WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
-                el.click();
-                // Explicitly focus the element after click - programmatic click()
-                // doesn't always trigger focus like a real click would
-                if (typeof el.focus === 'function') {{
-                    el.focus();
+                    throw new Error('__wd:stale element reference');
                 }}
-                return true;
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
-        Ok(())
-    }
 
-    /// Clear element content (for inputs/textareas)
-    async fn clear_element(&self, js_var: &str) -> Result<(), WebDriverErrorResponse> {
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                // Options are hit-tested through their containing select
+                var target = el;
+                if (el.tagName === 'OPTION') {{
+                    target = el.closest('select, datalist') || el;
                 }}
+                if (!target.getClientRects()[0]) {{
+                    throw new Error('__wd:element not interactable: element has no size and location');
+                }}
+                var point = ({IN_VIEW_CENTER_JS})(target);
+                var x = point.x;
+                var y = point.y;
+
+                // Verify the element (or a descendant) receives events at that point
+                var root = target.getRootNode();
+                var hit = (root.elementFromPoint ? root : document).elementFromPoint(x, y);
+                if (hit && hit !== target && !target.contains(hit)) {{
+                    var describe = function(node) {{
+                        var text = '<' + node.tagName.toLowerCase();
+                        if (node.id) text += ' id=' + JSON.stringify(node.id);
+                        var className = node.getAttribute('class');
+                        if (className) text += ' class=' + JSON.stringify(className);
+                        return text + '>';
+                    }};
+                    throw new Error('__wd:element click intercepted: Element ' + describe(target) +
+                        ' is not clickable at point (' + x + ', ' + y + '). Other element would receive the click: ' +
+                        describe(hit));
+                }}
+
+                // Dispatch the event sequence of a real primary button click at that point
+                var eventTarget = el.tagName === 'OPTION' ? el : (hit || target);
+                var init = {{
+                    bubbles: true,
+                    cancelable: true,
+                    composed: true,
+                    view: window,
+                    clientX: x,
+                    clientY: y,
+                    screenX: window.screenX + x,
+                    screenY: window.screenY + y,
+                    button: 0,
+                    detail: 1
+                }};
+                var pointerInit = Object.assign({{ pointerId: 1, pointerType: 'mouse', isPrimary: true }}, init);
+                var fire = function(Type, type, options, buttons) {{
+                    var event = new Type(type, Object.assign({{}}, options, {{ buttons: buttons }}));
+                    return eventTarget.dispatchEvent(event);
+                }};
+                var PointerType = window.PointerEvent || window.MouseEvent;
+
+                var downAllowed = fire(PointerType, 'pointerdown', pointerInit, 1);
+                if (downAllowed && fire(MouseEvent, 'mousedown', init, 1)) {{
+                    // Focus follows mousedown unless it was prevented
+                    var focusTarget = eventTarget;
+                    while (focusTarget && focusTarget.nodeType === 1 && !(focusTarget.tabIndex >= 0 || focusTarget.hasAttribute('tabindex') || focusTarget.isContentEditable)) {{
+                        focusTarget = focusTarget.parentElement;
+                    }}
+                    if (focusTarget && focusTarget.nodeType === 1 && typeof focusTarget.focus === 'function') {{
+                        focusTarget.focus();
+                    }} else if (document.activeElement && document.activeElement !== document.body) {{
+                        document.activeElement.blur();
+                    }}
+                }}
+                fire(PointerType, 'pointerup', pointerInit, 0);
+                if (downAllowed) {{
+                    fire(MouseEvent, 'mouseup', init, 0);
+                }}
+                // A synthetic click still runs activation behavior (links, checkboxes, submit)
+                fire(MouseEvent, 'click', init, 0);
+
+                // Options are not toggled by click events, select them like the user would
+                if (el.tagName === 'OPTION' && !el.disabled) {{
+                    var select = el.closest('select');
+                    var selected = select && select.multiple ? !el.selected : true;
+                    if (el.selected !== selected) {{
+                        el.selected = selected;
+                        if (select) {{
+                            select.dispatchEvent(new Event('input', {{ bubbles: true, composed: true }}));
+                            select.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                        }}
+                    }}
+                }}
+                return true;
+            }})()"
+        );
+        self.evaluate_js(&script).await?;
+        Ok(())
+    }
+
+    /// Select an option of a `<select>` element and fire `input`/`change` like a user would
+    async fn select_option(
+        &self,
+        js_var: &str,
+        selector: &OptionSelector,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let matches = selector.matcher_js();
+        let script = format!(
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
+                if (!el || !el.isConnected) {{
+                    throw new Error('__wd:stale element reference');
+                }}
+                if (el.tagName !== 'SELECT') {{
+                    throw new Error('__wd:invalid element state: element is not a select');
+                }}
+                ({ASSERT_INTERACTABLE_JS})(el);
+
+                var option = Array.prototype.slice.call(el.options).filter({matches})[0];
+                if (!option) {{
+                    throw new Error('__wd:no such element: no option matches the selector');
+                }}
+                if (option.disabled) {{
+                    throw new Error('__wd:invalid element state: option is disabled');
+                }}
+                if (option.selected && !el.multiple) return true;
+
+                el.focus();
+                option.selected = true;
+                el.dispatchEvent(new Event('input', {{ bubbles: true, composed: true }}));
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                return true;
+            }})()"
+        );
+        self.evaluate_js(&script).await?;
+        Ok(())
+    }
+
+    /// Clear element content (for inputs/textareas)
+    async fn clear_element(&self, js_var: &str) -> Result<(), WebDriverErrorResponse> {
+        let script = format!(
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
+                if (!el || !el.isConnected) {{
+                    throw new Error('__wd:stale element reference');
+                }}
+                if (!({IS_EDITABLE_JS})(el)) {{
+                    throw new Error('__wd:invalid element state: element is not editable');
+                }}
+                ({ASSERT_INTERACTABLE_JS})(el);
                 el.focus();
                 if (el.tagName === 'INPUT' || el.tagName === 'TEXTAREA') {{
                     var nativeInputValueSetter = Object.getOwnPropertyDescriptor(
@@ -552,45 +1751,142 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     }
 
     /// Send keys to element
+    /// Typeable runs are inserted as text; `WebDriver` key codepoints such as Tab or Enter are
+    /// dispatched through the key event path, with modifiers toggling until released
     async fn send_keys_to_element(
         &self,
         js_var: &str,
         text: &str,
     ) -> Result<(), WebDriverErrorResponse> {
-        let escaped = text
-            .replace('\\', "\\\\")
-            .replace('`', "\\`")
-            .replace('$', "\\$");
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
+                }}
+                // File inputs are usually hidden behind a styled button, so skip interactability
+                if (el.tagName === 'INPUT' && el.type === 'file') {{
+                    return el.multiple ? 'file multiple' : 'file';
+                }}
+                ({ASSERT_INTERACTABLE_JS})(el);
+                if ((el.tagName === 'INPUT' || el.tagName === 'TEXTAREA') && el.readOnly) {{
+                    throw new Error('__wd:invalid element state: element is read-only');
+                }}
+                var focusable = el.tabIndex >= 0 || el.isContentEditable || el === document.body ||
+                    el.matches('input, select, textarea, button, a[href], [tabindex]');
+                if (!focusable) {{
+                    throw new Error('__wd:element not interactable: element is not keyboard-interactable');
                 }}
                 el.focus();
+                return 'text';
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        match extract_string_value(&result)?.as_str() {
+            "file" => return self.set_input_files(js_var, text, false).await,
+            "file multiple" => return self.set_input_files(js_var, text, true).await,
+            _ => {}
+        }
 
-                if (el.tagName === 'INPUT' || el.tagName === 'TEXTAREA') {{
-                    var nativeInputValueSetter = Object.getOwnPropertyDescriptor(
-                        el.tagName === 'INPUT' ? window.HTMLInputElement.prototype : window.HTMLTextAreaElement.prototype,
-                        'value'
-                    ).set;
+        let mut modifiers = ModifierState::default();
+        for input in parse_key_sequence(text) {
+            match input {
+                KeyInput::Text(run) if !(modifiers.ctrl || modifiers.alt || modifiers.meta) => {
+                    self.evaluate_js(&insert_text_script(js_var, &run)).await?;
+                }
+                KeyInput::Composition(run) => {
+                    self.evaluate_js(&compose_text_script(js_var, &run)).await?;
+                }
+                KeyInput::Text(run) => {
+                    // Shortcuts such as Ctrl+A need individual key events
+                    for ch in run.chars() {
+                        let key = ch.to_string();
+                        self.dispatch_key_event(&key, true, &modifiers).await?;
+                        self.dispatch_key_event(&key, false, &modifiers).await?;
+                    }
+                }
+                KeyInput::Special(NULL_KEY) => {
+                    self.release_modifiers(&mut modifiers).await?;
+                }
+                KeyInput::Special(ch) if is_modifier_key(ch) => {
+                    let key = ch.to_string();
+                    let is_down = !modifiers.is_pressed(&key);
+                    modifiers.update(&key, is_down);
+                    self.dispatch_key_event(&key, is_down, &modifiers).await?;
+                }
+                KeyInput::Special(ch) => {
+                    let key = ch.to_string();
+                    self.dispatch_key_event(&key, true, &modifiers).await?;
+                    self.dispatch_key_event(&key, false, &modifiers).await?;
+                }
+            }
+        }
+        self.release_modifiers(&mut modifiers).await
+    }
 
-                    var newValue = el.value + `{escaped}`;
-                    nativeInputValueSetter.call(el, newValue);
+    /// Select files on an `<input type=file>` from newline-separated local paths.
+    /// The files are read natively and handed to the page through a `DataTransfer`, which
+    /// updates `input.files` exactly like a file chooser would.
+    async fn set_input_files(
+        &self,
+        js_var: &str,
+        text: &str,
+        multiple: bool,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let paths: Vec<&str> = text.split('\n').filter(|path| !path.is_empty()).collect();
+        if paths.is_empty() {
+            return Err(WebDriverErrorResponse::invalid_argument(
+                "No files were given for the file input",
+            ));
+        }
+        if paths.len() > 1 && !multiple {
+            return Err(WebDriverErrorResponse::invalid_argument(
+                "The file input does not accept multiple files",
+            ));
+        }
 
-                    var inputEvent = new InputEvent('input', {{
-                        bubbles: true,
-                        cancelable: true,
-                        inputType: 'insertText',
-                        data: `{escaped}`
-                    }});
-                    el.dispatchEvent(inputEvent);
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let path = std::path::Path::new(path);
+            let data = std::fs::read(path).map_err(|e| {
+                WebDriverErrorResponse::invalid_argument(&format!(
+                    "Cannot read file {}: {e}",
+                    path.display()
+                ))
+            })?;
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            files.push(serde_json::json!({
+                "name": name,
+                "type": mime_type_for_path(path),
+                "data": BASE64_STANDARD.encode(data),
+            }));
+        }
+        let files = Value::Array(files);
 
-                    var changeEvent = new Event('change', {{ bubbles: true }});
-                    el.dispatchEvent(changeEvent);
-                }} else if (el.isContentEditable) {{
-                    document.execCommand('insertText', false, `{escaped}`);
+        let script = format!(
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
+                if (!el || !el.isConnected) {{
+                    throw new Error('__wd:stale element reference');
+                }}
+                var transfer = new DataTransfer();
+                if (el.multiple) {{
+                    Array.from(el.files || []).forEach(function(file) {{ transfer.items.add(file); }});
                 }}
+                {files}.forEach(function(file) {{
+                    var binary = atob(file.data);
+                    var bytes = new Uint8Array(binary.length);
+                    for (var i = 0; i < binary.length; i++) {{
+                        bytes[i] = binary.charCodeAt(i);
+                    }}
+                    transfer.items.add(new File([bytes], file.name, {{ type: file.type }}));
+                }});
+                el.files = transfer.files;
+                el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
                 return true;
             }})()"
         );
@@ -598,21 +1894,31 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         Ok(())
     }
 
-    /// Get the active (focused) element and store in `js_var`
-    /// Returns true if an active element was found
-    async fn get_active_element(&self, js_var: &str) -> Result<bool, WebDriverErrorResponse> {
+    /// Release every modifier still held by Element Send Keys
+    async fn release_modifiers(
+        &self,
+        modifiers: &mut ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        for key in ["\u{E009}", "\u{E008}", "\u{E00A}", "\u{E03D}"] {
+            if modifiers.is_pressed(key) {
+                modifiers.update(key, false);
+                self.dispatch_key_event(key, false, modifiers).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the active (focused) element and store it in the element reference store
+    /// Returns its reference key, which an element found before keeps, if one is focused
+    async fn get_active_element(&self) -> Result<Option<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
                 var el = document.activeElement;
-                if (el && el !== document.body) {{
-                    window.{js_var} = el;
-                    return true;
-                }}
-                return false;
+                return ({STORE_ELEMENTS_JS})(el && el !== document.body ? [el] : [], window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_bool_value(&result)
+        Ok(extract_string_list(&result)?.into_iter().next())
     }
 
     /// Get element's computed accessibility role
@@ -622,89 +1928,11 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     ) -> Result<String, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-
-                // Check for explicit role attribute first
-                var explicitRole = el.getAttribute('role');
-                if (explicitRole) return explicitRole;
-
-                // Try computedRole if available (Chrome/Edge)
-                if (el.computedRole) return el.computedRole;
-
-                // Compute implicit role based on element type
-                var tag = el.tagName.toLowerCase();
-                var type = el.type ? el.type.toLowerCase() : '';
-
-                // Map elements to their implicit ARIA roles
-                var roleMap = {{
-                    'a': el.hasAttribute('href') ? 'link' : 'generic',
-                    'article': 'article',
-                    'aside': 'complementary',
-                    'button': 'button',
-                    'datalist': 'listbox',
-                    'details': 'group',
-                    'dialog': 'dialog',
-                    'fieldset': 'group',
-                    'figure': 'figure',
-                    'footer': 'contentinfo',
-                    'form': 'form',
-                    'h1': 'heading',
-                    'h2': 'heading',
-                    'h3': 'heading',
-                    'h4': 'heading',
-                    'h5': 'heading',
-                    'h6': 'heading',
-                    'header': 'banner',
-                    'hr': 'separator',
-                    'img': el.getAttribute('alt') === '' ? 'presentation' : 'img',
-                    'li': 'listitem',
-                    'main': 'main',
-                    'menu': 'list',
-                    'meter': 'meter',
-                    'nav': 'navigation',
-                    'ol': 'list',
-                    'optgroup': 'group',
-                    'option': 'option',
-                    'output': 'status',
-                    'progress': 'progressbar',
-                    'section': 'region',
-                    'select': el.multiple ? 'listbox' : 'combobox',
-                    'summary': 'button',
-                    'table': 'table',
-                    'tbody': 'rowgroup',
-                    'td': 'cell',
-                    'textarea': 'textbox',
-                    'tfoot': 'rowgroup',
-                    'th': 'columnheader',
-                    'thead': 'rowgroup',
-                    'tr': 'row',
-                    'ul': 'list'
-                }};
-
-                // Handle input types
-                if (tag === 'input') {{
-                    var inputRoles = {{
-                        'button': 'button',
-                        'checkbox': 'checkbox',
-                        'email': 'textbox',
-                        'image': 'button',
-                        'number': 'spinbutton',
-                        'radio': 'radio',
-                        'range': 'slider',
-                        'reset': 'button',
-                        'search': 'searchbox',
-                        'submit': 'button',
-                        'tel': 'textbox',
-                        'text': 'textbox',
-                        'url': 'textbox'
-                    }};
-                    return inputRoles[type] || 'textbox';
+                    throw new Error('__wd:stale element reference');
                 }}
-
-                return roleMap[tag] || '';
+                return ({COMPUTED_ROLE_JS})(el);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
@@ -717,151 +1945,130 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         js_var: &str,
     ) -> Result<String, WebDriverErrorResponse> {
         let script = format!(
-            r#"(function() {{
-                var el = window.{js_var};
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-
-                // Try computedName if available (Chrome/Edge)
-                if (el.computedName) return el.computedName;
-
-                // Check aria-labelledby first (highest priority)
-                var labelledBy = el.getAttribute('aria-labelledby');
-                if (labelledBy) {{
-                    var labels = labelledBy.split(/\s+/).map(function(id) {{
-                        var labelEl = document.getElementById(id);
-                        return labelEl ? labelEl.textContent : '';
-                    }});
-                    var combined = labels.join(' ').trim();
-                    if (combined) return combined;
+                    throw new Error('__wd:stale element reference');
                 }}
+                return ({COMPUTED_LABEL_JS})(el, {COMPUTED_ROLE_JS});
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        extract_string_value(&result)
+    }
 
-                // Check aria-label
-                var ariaLabel = el.getAttribute('aria-label');
-                if (ariaLabel) return ariaLabel;
-
-                // For inputs, check associated label
-                var tag = el.tagName.toLowerCase();
-                if (tag === 'input' || tag === 'textarea' || tag === 'select') {{
-                    // Check for label with 'for' attribute
-                    if (el.id) {{
-                        var label = document.querySelector("label[for='" + el.id + "']");
-                        if (label) return label.textContent.trim();
-                    }}
-                    // Check for wrapping label
-                    var parentLabel = el.closest('label');
-                    if (parentLabel) {{
-                        // Get label text excluding the input's value
-                        var clone = parentLabel.cloneNode(true);
-                        var inputs = clone.querySelectorAll('input, textarea, select');
-                        inputs.forEach(function(input) {{ input.remove(); }});
-                        var labelText = clone.textContent.trim();
-                        if (labelText) return labelText;
+    /// Drop page-side element references that are no longer connected to the document.
+    /// Returns the keys among `js_refs` that were collected, including ones the page lost
+    /// (e.g. after a navigation replaced the store).
+    async fn collect_element_refs(
+        &self,
+        js_refs: &[String],
+    ) -> Result<Vec<String>, WebDriverErrorResponse> {
+        let keys = serde_json::to_string(js_refs)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let script = format!(
+            r"(function() {{
+                var refs = {ELEMENT_REFS_JS};
+                var collected = [];
+                {keys}.forEach(function(key) {{
+                    var node = refs.get(key);
+                    var alive = node && (node.window === node ? !node.closed : node.isConnected);
+                    if (!alive) {{
+                        refs.delete(key);
+                        collected.push(key);
                     }}
-                    // Check placeholder
-                    if (el.placeholder) return el.placeholder;
-                }}
-
-                // For buttons and links, use text content
-                if (tag === 'button' || tag === 'a') {{
-                    return el.textContent.trim();
-                }}
-
-                // For images, use alt text
-                if (tag === 'img') {{
-                    return el.getAttribute('alt') || '';
-                }}
-
-                // Check title attribute as last resort
-                var title = el.getAttribute('title');
-                if (title) return title;
-
-                // Fall back to text content for other elements
-                return el.textContent ? el.textContent.trim() : '';
-            }})()"#
+                }});
+                return collected;
+            }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_string_value(&result)
+        let collected = extract_value(&result)?;
+        Ok(collected
+            .as_array()
+            .map(|keys| {
+                keys.iter()
+                    .filter_map(|key| key.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default())
     }
 
     // =========================================================================
     // Shadow DOM
     // =========================================================================
 
-    /// Get element's shadow root and store in `shadow_var`
-    /// Returns true if shadow root exists
+    /// Get element's shadow root and store it in the element reference store
+    /// Returns its reference key, if the element has one. Closed roots recorded by
+    /// [`CLOSED_SHADOW_ROOTS_JS`] are only returned when `include_closed` is set
     async fn get_element_shadow_root(
         &self,
         js_var: &str,
-        shadow_var: &str,
-    ) -> Result<bool, WebDriverErrorResponse> {
+        include_closed: bool,
+    ) -> Result<Option<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var el = window.{js_var};
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
                 if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
+                    throw new Error('__wd:stale element reference');
                 }}
                 var shadow = el.shadowRoot;
-                if (shadow) {{
-                    window.{shadow_var} = shadow;
-                    return true;
+                var closedRoots = window[Symbol.for('tauri-plugin-webdriver:closed-shadow-roots')];
+                if (!shadow && {include_closed} && closedRoots) {{
+                    shadow = closedRoots.get(el) || null;
                 }}
-                return false;
+                return ({STORE_ELEMENTS_JS})(shadow ? [shadow] : [], window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_bool_value(&result)
+        Ok(extract_string_list(&result)?.into_iter().next())
     }
 
-    /// Find element within a shadow root
+    /// Find element within a shadow root and store it
+    /// Returns its reference key, if one was found
     async fn find_element_from_shadow(
         &self,
         shadow_var: &str,
         strategy_js: &str,
-        js_var: &str,
-    ) -> Result<bool, WebDriverErrorResponse> {
+    ) -> Result<Option<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var shadow = window.{shadow_var};
-                if (!shadow) {{
-                    throw new Error('no such shadow root');
+                var shadow = {ELEMENT_REFS_JS}.get('{shadow_var}');
+                if (!shadow || shadow.nodeType !== 11) {{
+                    throw new Error('__wd:no such shadow root');
                 }}
-                var el = {strategy_js};
-                if (el) {{
-                    window.{js_var} = el;
-                    return true;
+                if (!shadow.host || !shadow.host.isConnected) {{
+                    throw new Error('__wd:detached shadow root');
                 }}
-                return false;
+                var el = {strategy_js};
+                return ({STORE_ELEMENTS_JS})(el ? [el] : [], window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_bool_value(&result)
+        Ok(extract_string_list(&result)?.into_iter().next())
     }
 
     /// Find multiple elements within a shadow root
+    /// Returns the reference keys of the elements found
     async fn find_elements_from_shadow(
         &self,
         shadow_var: &str,
         strategy_js: &str,
-        js_var_prefix: &str,
-    ) -> Result<usize, WebDriverErrorResponse> {
+    ) -> Result<Vec<String>, WebDriverErrorResponse> {
         let script = format!(
             r"(function() {{
-                var shadow = window.{shadow_var};
-                if (!shadow) {{
-                    throw new Error('no such shadow root');
+                var shadow = {ELEMENT_REFS_JS}.get('{shadow_var}');
+                if (!shadow || shadow.nodeType !== 11) {{
+                    throw new Error('__wd:no such shadow root');
                 }}
-                var elements = {strategy_js};
-                var count = elements.length;
-                for (var i = 0; i < count; i++) {{
-                    window['{js_var_prefix}' + i] = elements[i];
+                if (!shadow.host || !shadow.host.isConnected) {{
+                    throw new Error('__wd:detached shadow root');
                 }}
-                return count;
+                var elements = {strategy_js};
+                return ({STORE_ELEMENTS_JS})(elements, window);
             }})()"
         );
         let result = self.evaluate_js(&script).await?;
-        extract_usize_value(&result)
+        extract_string_list(&result)
     }
 
     // =========================================================================
@@ -877,37 +2084,56 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         let args_json = serde_json::to_string(args)
             .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
 
+        // A thenable result is parked on the window and awaited through the async script
+        // machinery, which honors the script timeout
         let wrapper = format!(
             r"(function() {{
-                var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-                function deserializeArg(arg) {{
-                    if (arg === null || arg === undefined) return arg;
-                    if (Array.isArray(arg)) return arg.map(deserializeArg);
-                    if (typeof arg === 'object') {{
-                        if (arg[ELEMENT_KEY]) {{
-                            var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                            if (!el) throw new Error('stale element reference');
-                            return el;
-                        }}
-                        var result = {{}};
-                        for (var key in arg) {{
-                            if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                        }}
-                        return result;
-                    }}
-                    return arg;
-                }}
+                var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, window); }};
                 try {{
                     var args = {args_json}.map(deserializeArg);
                     var fn = function() {{ {script} }};
-                    return {{ __wd_success: true, __wd_value: fn.apply(null, args) }};
+                    var result = fn.apply(null, args);
+                    if (result !== null && (typeof result === 'object' || typeof result === 'function')
+                            && typeof result.then === 'function') {{
+                        var key = Symbol.for('tauri-plugin-webdriver:pending-promises');
+                        var pending = window[key] || Object.defineProperty(window, key, {{ value: new Map() }})[key];
+                        var id = (pending.nextId = (pending.nextId || 0) + 1);
+                        pending.set(id, Promise.resolve(result).then(
+                            function(value) {{ return {{ __wd_success: true, __wd_value: value }}; }},
+                            function(e) {{ return {{ __wd_success: false, __wd_error: (e && e.message) || String(e) }}; }}
+                        ));
+                        return {{ __wd_success: true, __wd_pending: id }};
+                    }}
+                    var value = ({SERIALIZE_RESULT_JS})(result, window);
+                    return {{ __wd_success: true, __wd_value: value }};
                 }} catch (e) {{
                     return {{ __wd_success: false, __wd_error: e.message || String(e) }};
                 }}
             }})()"
         );
         let result = self.evaluate_js(&wrapper).await?;
-        extract_script_result(&result)
+
+        let pending = result
+            .get("value")
+            .and_then(|value| value.get("__wd_pending"))
+            .and_then(Value::as_u64);
+        let Some(id) = pending else {
+            return extract_script_result(&result);
+        };
+
+        let settled = format!(
+            r"var done = arguments[arguments.length - 1];
+            var pending = window[Symbol.for('tauri-plugin-webdriver:pending-promises')];
+            var promise = pending && pending.get({id});
+            if (!promise) {{
+                done({{ __wd_success: false, __wd_error: 'Promise returned by the script was lost' }});
+                return;
+            }}
+            pending.delete({id});
+            promise.then(done);"
+        );
+        let outcome = self.execute_async_script(&settled, &[]).await?;
+        extract_script_outcome(&outcome)
     }
 
     /// Execute asynchronous JavaScript with callback.
@@ -919,6 +2145,92 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         args: &[Value],
     ) -> Result<Value, WebDriverErrorResponse>;
 
+    /// Execute JavaScript in a fresh isolated world of the selected document, which shares
+    /// its DOM but none of its JavaScript globals. Arguments must be plain JSON, as element
+    /// references belong to the page's world. With `is_async`, the script finishes by
+    /// calling the callback appended to its arguments.
+    async fn execute_script_in_new_realm(
+        &self,
+        _script: &str,
+        _args: &[Value],
+        _is_async: bool,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "New realms are not supported on this platform",
+        ))
+    }
+
+    // =========================================================================
+    // Preload Scripts
+    // =========================================================================
+
+    /// Register `script` to run in every new document of this webview, in all frames,
+    /// before page scripts. Returns an identifier for `remove_preload_script`.
+    async fn add_preload_script(&self, _script: &str) -> Result<String, WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Preload scripts are not supported on this platform",
+        ))
+    }
+
+    /// Unregister a script added with `add_preload_script`. Documents it already ran in
+    /// are unaffected.
+    async fn remove_preload_script(&self, _id: &str) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Preload scripts are not supported on this platform",
+        ))
+    }
+
+    // =========================================================================
+    // Logs
+    // =========================================================================
+
+    /// Drain the log entries buffered by [`LOG_CAPTURE_JS`] in the document and its
+    /// same-origin frames, oldest first
+    async fn take_logs(&self) -> Result<Value, WebDriverErrorResponse> {
+        let script = r"(function() {
+            var symbol = Symbol.for('tauri-plugin-webdriver:log');
+            var entries = [];
+            var collect = function(win) {
+                try {
+                    var buffer = win[symbol];
+                    if (buffer) entries = entries.concat(buffer.splice(0, buffer.length));
+                    for (var i = 0; i < win.frames.length; i++) collect(win.frames[i]);
+                } catch (e) {}
+            };
+            collect(window);
+            return entries.sort(function(a, b) { return a.timestamp - b.timestamp; });
+        })()";
+        let result = self.evaluate_js(script).await?;
+        extract_value(&result)
+    }
+
+    // =========================================================================
+    // Session Cleanup
+    // =========================================================================
+
+    /// Remove what sessions injected into the document and its same-origin frames:
+    /// element references, parked promises, input tracking, cached frame windows and the
+    /// log capture hooks. Meant for when no session is left to use them.
+    async fn clear_injected_state(&self) -> Result<(), WebDriverErrorResponse> {
+        let script = r"(function() {
+            var stores = ['element-refs', 'pending-promises', 'hovered', 'pressed-targets', 'touches', 'frame-windows'];
+            var clear = function(win) {
+                try {
+                    stores.forEach(function(name) {
+                        var store = win[Symbol.for('tauri-plugin-webdriver:' + name)];
+                        if (store && typeof store.clear === 'function') store.clear();
+                    });
+                    var uninstallLogs = win[Symbol.for('tauri-plugin-webdriver:log-uninstall')];
+                    if (uninstallLogs) uninstallLogs();
+                    for (var i = 0; i < win.frames.length; i++) clear(win.frames[i]);
+                } catch (e) {}
+            };
+            clear(window);
+        })()";
+        self.evaluate_js(script).await?;
+        Ok(())
+    }
+
     // =========================================================================
     // Screenshots
     // =========================================================================
@@ -926,14 +2238,137 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     /// Take screenshot of the page, returns base64-encoded PNG
     async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse>;
 
+    /// Scroll element into view and get the part of it visible in the top-level viewport,
+    /// offset by any enclosing frames, to crop an element screenshot to
+    async fn get_element_screenshot_clip(
+        &self,
+        js_var: &str,
+    ) -> Result<ScreenshotClip, WebDriverErrorResponse> {
+        let script = format!(
+            r"(function() {{
+                var el = {ELEMENT_REFS_JS}.get('{js_var}');
+                if (!el || !el.isConnected) {{
+                    throw new Error('__wd:stale element reference');
+                }}
+                el.scrollIntoView({{ block: 'center', inline: 'center' }});
+
+                var rect = el.getBoundingClientRect();
+                var box = {{ left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom }};
+                var clip = function(width, height) {{
+                    box.left = Math.max(box.left, 0);
+                    box.top = Math.max(box.top, 0);
+                    box.right = Math.min(box.right, width);
+                    box.bottom = Math.min(box.bottom, height);
+                }};
+                var win = window;
+                clip(win.innerWidth, win.innerHeight);
+                // Translate into the coordinates of each same-origin parent document
+                while (win !== win.top && win.frameElement) {{
+                    var frame = win.frameElement;
+                    var frameRect = frame.getBoundingClientRect();
+                    var dx = frameRect.left + frame.clientLeft;
+                    var dy = frameRect.top + frame.clientTop;
+                    box.left += dx;
+                    box.right += dx;
+                    box.top += dy;
+                    box.bottom += dy;
+                    win = win.parent;
+                    clip(win.innerWidth, win.innerHeight);
+                }}
+                if (box.right <= box.left || box.bottom <= box.top) {{
+                    throw new Error('__wd:element not interactable: element is not in the viewport');
+                }}
+                return {{
+                    x: box.left,
+                    y: box.top,
+                    width: box.right - box.left,
+                    height: box.bottom - box.top,
+                    viewportWidth: win.innerWidth
+                }};
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        let value = extract_value(&result)?;
+        serde_json::from_value(value).map_err(|e| {
+            WebDriverErrorResponse::unknown_error(&format!("Invalid element clip: {e}"))
+        })
+    }
+
     /// Take screenshot of a specific element, returns base64-encoded PNG
     async fn take_element_screenshot(&self, js_var: &str)
         -> Result<String, WebDriverErrorResponse>;
 
+    /// Device pixels per CSS pixel, relating screenshot pixels to CSS pixels
+    async fn get_device_pixel_ratio(&self) -> Result<f64, WebDriverErrorResponse> {
+        let result = self
+            .evaluate_js("(function() { return window.devicePixelRatio; })()")
+            .await?;
+        Ok(extract_value(&result)?
+            .as_f64()
+            .filter(|ratio| *ratio > 0.0)
+            .unwrap_or(1.0))
+    }
+
+    /// Take screenshot of the whole scrollable document, returns base64-encoded PNG
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Full-page screenshots are not supported on this platform",
+        ))
+    }
+
+    /// Snapshot rect covering the whole document, in view coordinates (offset by the
+    /// current scroll position). Meant for executors without a frame context.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    async fn get_full_page_clip(&self) -> Result<ScreenshotClip, WebDriverErrorResponse> {
+        let script = r"(function() {
+                var doc = document.documentElement;
+                return {
+                    x: -window.scrollX,
+                    y: -window.scrollY,
+                    width: Math.max(doc.scrollWidth, document.body ? document.body.scrollWidth : 0),
+                    height: Math.max(doc.scrollHeight, document.body ? document.body.scrollHeight : 0),
+                    viewportWidth: window.innerWidth
+                };
+            })()";
+        let result = self.evaluate_js(script).await?;
+        let value = extract_value(&result)?;
+        serde_json::from_value(value).map_err(|e| {
+            WebDriverErrorResponse::unknown_error(&format!("Invalid document size: {e}"))
+        })
+    }
+
+    /// Take screenshot of the whole native window, including titlebar and menus.
+    /// Returns base64-encoded PNG.
+    async fn take_window_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Native window screenshots are not supported on this platform",
+        ))
+    }
+
     // =========================================================================
     // Actions (Keyboard/Pointer)
     // =========================================================================
 
+    /// Size of the viewport in CSS pixels, which bounds pointer move targets
+    async fn get_viewport_size(&self) -> Result<(i32, i32), WebDriverErrorResponse> {
+        let result = self
+            .evaluate_js(
+                r"(function() {
+                    return { width: window.innerWidth, height: window.innerHeight };
+                })()",
+            )
+            .await?;
+        let value = extract_value(&result)?;
+        let dimension = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_i64)
+                .and_then(|v| i32::try_from(v).ok())
+                .unwrap_or(0)
+        };
+        Ok((dimension("width"), dimension("height")))
+    }
+
     /// Dispatch a keyboard event with modifier state
     async fn dispatch_key_event(
         &self,
@@ -971,16 +2406,14 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
             "\u{E03D}" => ("Meta", "MetaLeft", 91),
             _ => {
                 let ch = key.chars().next().unwrap_or(' ');
-                let upper = ch.to_ascii_uppercase();
-                let code = if ch.is_ascii_alphabetic() {
-                    format!("Key{upper}")
-                } else if ch.is_ascii_digit() {
-                    format!("Digit{ch}")
-                } else {
-                    key.to_string()
+                let code = key_code(ch).unwrap_or(key);
+                // Shifted characters are typed with Shift held, as on a real keyboard
+                let modifiers = ModifierState {
+                    shift: modifiers.shift || is_shifted_character(ch),
+                    ..*modifiers
                 };
                 return self
-                    .dispatch_regular_key(key, &code, is_down, modifiers)
+                    .dispatch_regular_key(key, code, is_down, &modifiers)
                     .await;
             }
         };
@@ -1256,14 +2689,22 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         Ok(())
     }
 
-    /// Dispatch a pointer/mouse event
+    /// Dispatch a pointer/mouse event carrying the held modifier keys. Pen input also
+    /// dispatches a `PointerEvent` with the action item's pressure, tilt and twist. A
+    /// release dispatches the resulting click, dblclick or auxclick, and a secondary press
+    /// opens the context menu.
     async fn dispatch_pointer_event(
         &self,
         event_type: PointerEventType,
         x: i32,
         y: i32,
         button: u32,
+        pointer: &PointerInput,
     ) -> Result<(), WebDriverErrorResponse> {
+        if pointer.pointer_type == PointerType::Touch {
+            return self.dispatch_touch_event(event_type, x, y, pointer).await;
+        }
+
         let event_name = match event_type {
             PointerEventType::Down => "mousedown",
             PointerEventType::Up => "mouseup",
@@ -1275,20 +2716,70 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         } else {
             0
         };
+        let pointer_type = pointer.pointer_type.as_str();
+        let pointer_id = pointer.pointer_id + 1;
+        let click_count = pointer.click_count.max(1);
+        let properties = serde_json::to_string(&pointer.properties)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let modifiers = pointer.modifiers.to_event_init();
         let script = format!(
             r"(function() {{
                 var el = document.elementFromPoint({x}, {y});
                 if (!el) el = document.body;
+                var modifiers = {modifiers};
+
+                if ('{pointer_type}' !== 'mouse') {{
+                    var pointerInit = Object.assign({{
+                        bubbles: true,
+                        cancelable: true,
+                        composed: true,
+                        pointerId: {pointer_id},
+                        pointerType: '{pointer_type}',
+                        isPrimary: true,
+                        clientX: {x},
+                        clientY: {y},
+                        button: '{event_name}' === 'mousemove' ? -1 : {button},
+                        buttons: {buttons}
+                    }}, modifiers, {properties});
+                    if (pointerInit.pressure === undefined) pointerInit.pressure = {buttons} ? 0.5 : 0;
+                    el.dispatchEvent(new PointerEvent('{event_name}'.replace('mouse', 'pointer'), pointerInit));
+                }}
 
-                var event = new MouseEvent('{event_name}', {{
+                var init = Object.assign({{
                     bubbles: true,
                     cancelable: true,
+                    composed: true,
                     clientX: {x},
                     clientY: {y},
                     button: {button},
-                    buttons: {buttons}
-                }});
-                el.dispatchEvent(event);
+                    buttons: {buttons},
+                    detail: '{event_name}' === 'mousemove' ? 0 : {click_count}
+                }}, modifiers);
+                el.dispatchEvent(new MouseEvent('{event_name}', init));
+
+                // Press targets by pointer, to find what a release clicks
+                var pressKey = Symbol.for('tauri-plugin-webdriver:pressed-targets');
+                var pressed = window[pressKey] || (window[pressKey] = new Map());
+                if ('{event_name}' === 'mousedown') {{
+                    pressed.set({pointer_id}, el);
+                    if ({button} === 2) el.dispatchEvent(new MouseEvent('contextmenu', init));
+                }} else if ('{event_name}' === 'mouseup') {{
+                    var pressTarget = pressed.get({pointer_id});
+                    pressed.delete({pointer_id});
+                    // Clicks go to the nearest common ancestor of the press and release targets
+                    var clickTarget = pressTarget && pressTarget.isConnected ? el : null;
+                    while (clickTarget && !clickTarget.contains(pressTarget)) {{
+                        clickTarget = clickTarget.parentElement;
+                    }}
+                    if (clickTarget) {{
+                        if ({button} === 0) {{
+                            clickTarget.dispatchEvent(new MouseEvent('click', init));
+                            if ({click_count} === 2) clickTarget.dispatchEvent(new MouseEvent('dblclick', init));
+                        }} else {{
+                            clickTarget.dispatchEvent(new MouseEvent('auxclick', init));
+                        }}
+                    }}
+                }}
                 return true;
             }})()"
         );
@@ -1297,31 +2788,272 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         Ok(())
     }
 
-    /// Dispatch a scroll/wheel event
-    async fn dispatch_scroll_event(
+    /// Dispatch a touch point change as pointer and touch events. The touch keeps the
+    /// element it started on as its target, and a tap that did not move clicks it.
+    async fn dispatch_touch_event(
         &self,
+        event_type: PointerEventType,
         x: i32,
         y: i32,
-        delta_x: i32,
-        delta_y: i32,
+        pointer: &PointerInput,
     ) -> Result<(), WebDriverErrorResponse> {
+        let phase = match event_type {
+            PointerEventType::Down => "start",
+            PointerEventType::Up => "end",
+            PointerEventType::Move => "move",
+        };
+        let properties = serde_json::to_string(&pointer.properties)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let modifiers = pointer.modifiers.to_event_init();
+        let id = pointer.pointer_id;
         let script = format!(
             r"(function() {{
-                var el = document.elementFromPoint({x}, {y});
-                if (!el) el = document.body;
+                var modifiers = {modifiers};
+                var key = Symbol.for('tauri-plugin-webdriver:touches');
+                var touches = window[key] || (window[key] = new Map());
+                var id = {id};
+                var phase = '{phase}';
+                var active = touches.get(id);
+                if (phase !== 'start' && !active) return false;
+                if (phase === 'start') {{
+                    var hit = document.elementFromPoint({x}, {y}) || document.body;
+                    // The first finger down is the primary pointer until it lifts
+                    active = {{ target: hit, startX: {x}, startY: {y}, moved: false, primary: touches.size === 0 }};
+                    touches.set(id, active);
+                }}
+                var target = active.target;
+                var properties = {properties};
+                active.x = {x};
+                active.y = {y};
+                active.width = properties.width || 1;
+                active.height = properties.height || 1;
+                active.force = properties.pressure === undefined ? 1 : properties.pressure;
+                if (Math.abs(active.x - active.startX) > 10 || Math.abs(active.y - active.startY) > 10) {{
+                    active.moved = true;
+                }}
 
-                var event = new WheelEvent('wheel', {{
+                var makeTouch = function(identifier, point) {{
+                    var init = {{
+                        identifier: identifier,
+                        target: point.target,
+                        clientX: point.x,
+                        clientY: point.y,
+                        pageX: point.x + window.scrollX,
+                        pageY: point.y + window.scrollY,
+                        screenX: point.x,
+                        screenY: point.y,
+                        radiusX: point.width / 2,
+                        radiusY: point.height / 2,
+                        rotationAngle: properties.twist || 0,
+                        force: phase === 'end' ? 0 : point.force
+                    }};
+                    try {{ return new Touch(init); }} catch (e) {{ return init; }}
+                }};
+                var changed = [makeTouch(id, active)];
+                if (phase === 'end') touches.delete(id);
+                var all = [];
+                touches.forEach(function(point, identifier) {{ all.push(makeTouch(identifier, point)); }});
+                var targetTouches = all.filter(function(touch) {{ return touch.target === target; }});
+
+                var pointerInit = Object.assign({{
                     bubbles: true,
                     cancelable: true,
+                    composed: true,
+                    pointerId: 1 + id,
+                    pointerType: 'touch',
+                    isPrimary: active.primary,
                     clientX: {x},
                     clientY: {y},
-                    deltaX: {delta_x},
-                    deltaY: {delta_y},
-                    deltaMode: 0
-                }});
-                el.dispatchEvent(event);
+                    button: phase === 'move' ? -1 : 0,
+                    buttons: phase === 'end' ? 0 : 1
+                }}, modifiers, properties);
+                if (phase === 'end') pointerInit.pressure = 0;
+                else if (pointerInit.pressure === undefined) pointerInit.pressure = 0.5;
+                target.dispatchEvent(new PointerEvent({{ start: 'pointerdown', move: 'pointermove', end: 'pointerup' }}[phase], pointerInit));
+
+                var name = 'touch' + phase;
+                var init = Object.assign({{
+                    bubbles: true,
+                    cancelable: true,
+                    composed: true,
+                    touches: all,
+                    targetTouches: targetTouches,
+                    changedTouches: changed
+                }}, modifiers);
+                var event;
+                try {{
+                    event = new TouchEvent(name, init);
+                }} catch (e) {{
+                    // No touch support in this engine; expose the touch lists on a plain event
+                    event = new Event(name, {{ bubbles: true, cancelable: true, composed: true }});
+                    ['touches', 'targetTouches', 'changedTouches'].forEach(function(list) {{
+                        Object.defineProperty(event, list, {{ value: init[list] }});
+                    }});
+                }}
+                var notCanceled = target.dispatchEvent(event);
 
-                window.scrollBy({delta_x}, {delta_y});
+                if (phase === 'end' && notCanceled && !active.moved && active.primary && touches.size === 0 && target.isConnected) {{
+                    target.dispatchEvent(new MouseEvent('click', Object.assign({{
+                        bubbles: true,
+                        cancelable: true,
+                        composed: true,
+                        clientX: {x},
+                        clientY: {y},
+                        detail: 1
+                    }}, modifiers)));
+                }}
+                return true;
+            }})()"
+        );
+
+        self.evaluate_js(&script).await?;
+        Ok(())
+    }
+
+    /// Dispatch over/enter/out/leave events when the element under pointer `source_id` changes
+    async fn update_hover(
+        &self,
+        source_id: &str,
+        x: i32,
+        y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let source = serde_json::to_string(source_id)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let script = format!(
+            r"(function() {{
+                var symbol = Symbol.for('tauri-plugin-webdriver:hovered');
+                var hovered = window[symbol] || Object.defineProperty(window, symbol, {{ value: new Map() }})[symbol];
+                var previous = hovered.get({source});
+                if (previous && !previous.isConnected) previous = null;
+
+                var next = document.elementFromPoint({x}, {y});
+                while (next && next.shadowRoot) {{
+                    var inner = next.shadowRoot.elementFromPoint({x}, {y});
+                    if (!inner || inner === next) break;
+                    next = inner;
+                }}
+                if (previous === next) return true;
+                if (next) {{
+                    hovered.set({source}, next);
+                }} else {{
+                    hovered.delete({source});
+                }}
+
+                var ancestors = function(node) {{
+                    var chain = [];
+                    for (; node && node.nodeType === 1; node = node.parentElement || (node.getRootNode().host || null)) {{
+                        chain.push(node);
+                    }}
+                    return chain;
+                }};
+                var fire = function(target, type, bubbles, related) {{
+                    var init = {{
+                        bubbles: bubbles,
+                        cancelable: bubbles,
+                        composed: true,
+                        view: window,
+                        clientX: {x},
+                        clientY: {y},
+                        relatedTarget: related
+                    }};
+                    var isPointer = type.indexOf('pointer') === 0;
+                    if (isPointer) {{
+                        init.pointerId = 1;
+                        init.pointerType = 'mouse';
+                        init.isPrimary = true;
+                    }}
+                    var Type = isPointer && window.PointerEvent ? window.PointerEvent : window.MouseEvent;
+                    target.dispatchEvent(new Type(type, init));
+                }};
+
+                var left = ancestors(previous);
+                var entered = ancestors(next);
+                if (previous) {{
+                    fire(previous, 'pointerout', true, next);
+                    fire(previous, 'mouseout', true, next);
+                    left.filter(function(node) {{ return entered.indexOf(node) === -1; }}).forEach(function(node) {{
+                        fire(node, 'pointerleave', false, next);
+                        fire(node, 'mouseleave', false, next);
+                    }});
+                }}
+                if (next) {{
+                    fire(next, 'pointerover', true, previous);
+                    fire(next, 'mouseover', true, previous);
+                    entered.filter(function(node) {{ return left.indexOf(node) === -1; }}).reverse().forEach(function(node) {{
+                        fire(node, 'pointerenter', false, previous);
+                        fire(node, 'mouseenter', false, previous);
+                    }});
+                }}
+                return true;
+            }})()"
+        );
+        self.evaluate_js(&script).await?;
+        Ok(())
+    }
+
+    /// Dispatch a wheel event at a viewport point and, unless it was canceled, scroll the
+    /// nearest ancestor of the element there that can scroll in each direction
+    async fn dispatch_scroll_event(
+        &self,
+        x: i32,
+        y: i32,
+        delta_x: i32,
+        delta_y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let script = format!(
+            r"(function() {{
+                if ({x} < 0 || {y} < 0 || {x} >= window.innerWidth || {y} >= window.innerHeight) {{
+                    throw new Error('__wd:move target out of bounds');
+                }}
+                var el = document.elementFromPoint({x}, {y});
+                if (!el) el = document.body;
+
+                var event = new WheelEvent('wheel', {{
+                    bubbles: true,
+                    cancelable: true,
+                    composed: true,
+                    clientX: {x},
+                    clientY: {y},
+                    deltaX: {delta_x},
+                    deltaY: {delta_y},
+                    deltaMode: 0
+                }});
+                if (!el.dispatchEvent(event)) return false;
+
+                var root = document.scrollingElement || document.documentElement;
+                var parentOf = function(node) {{
+                    if (node.assignedSlot) return node.assignedSlot;
+                    if (node.parentElement) return node.parentElement;
+                    var host = node.getRootNode().host;
+                    return host || null;
+                }};
+                var canScroll = function(node, horizontal, delta) {{
+                    if (node === root) return true;
+                    var style = window.getComputedStyle(node);
+                    var overflow = horizontal ? style.overflowX : style.overflowY;
+                    if (!/^(auto|scroll|overlay)$/.test(overflow)) return false;
+                    var position = horizontal ? node.scrollLeft : node.scrollTop;
+                    var max = horizontal
+                        ? node.scrollWidth - node.clientWidth
+                        : node.scrollHeight - node.clientHeight;
+                    return delta > 0 ? position < max : position > 0;
+                }};
+                var scrollAxis = function(horizontal, delta) {{
+                    if (!delta) return;
+                    var node = el;
+                    while (node && node !== root && !canScroll(node, horizontal, delta)) {{
+                        node = parentOf(node);
+                    }}
+                    if (!node || node === root) {{
+                        window.scrollBy(horizontal ? delta : 0, horizontal ? 0 : delta);
+                    }} else if (horizontal) {{
+                        node.scrollLeft += delta;
+                    }} else {{
+                        node.scrollTop += delta;
+                    }}
+                }};
+                scrollAxis(true, {delta_x});
+                scrollAxis(false, {delta_y});
                 return true;
             }})()"
         );
@@ -1330,6 +3062,70 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         Ok(())
     }
 
+    // =========================================================================
+    // Native Input
+    // =========================================================================
+
+    /// Screen position in physical pixels of a viewport point given in CSS pixels
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    fn viewport_to_screen(&self, x: i32, y: i32) -> Result<(f64, f64), WebDriverErrorResponse> {
+        let window = self.window();
+        let origin = window
+            .inner_position()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        let offset = self
+            .webview()
+            .position()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        let scale = window
+            .scale_factor()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        Ok((
+            f64::from(origin.x + offset.x) + f64::from(x) * scale,
+            f64::from(origin.y + offset.y) + f64::from(y) * scale,
+        ))
+    }
+
+    /// Inject a pointer event through the OS input system at a viewport point.
+    /// Unlike synthesized DOM events, these are trusted and drive native behavior.
+    async fn dispatch_native_pointer_event(
+        &self,
+        _event_type: PointerEventType,
+        _x: i32,
+        _y: i32,
+        _button: u32,
+        _pointer: &PointerInput,
+    ) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Native input is not supported on this platform",
+        ))
+    }
+
+    /// Inject a key press or release through the OS input system
+    async fn dispatch_native_key_event(
+        &self,
+        _key: &str,
+        _is_down: bool,
+        _modifiers: &ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Native input is not supported on this platform",
+        ))
+    }
+
+    /// Inject a wheel scroll through the OS input system at a viewport point
+    async fn dispatch_native_scroll_event(
+        &self,
+        _x: i32,
+        _y: i32,
+        _delta_x: i32,
+        _delta_y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Native input is not supported on this platform",
+        ))
+    }
+
     // =========================================================================
     // Window Management
     // =========================================================================
@@ -1359,16 +3155,27 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         &self,
         rect: WindowRect,
     ) -> Result<WindowRect, WebDriverErrorResponse> {
-        // Exit fullscreen/maximized state before setting rect
+        // Restore the window before setting rect
         // Otherwise the window manager may ignore our size/position request
-        if self.window().is_fullscreen().unwrap_or(false) {
-            let _ = self.window().set_fullscreen(false);
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        }
-        if self.window().is_maximized().unwrap_or(false) {
-            let _ = self.window().unmaximize();
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        }
+        self.restore_window().await?;
+
+        // Bring a window that would be entirely off-screen back onto the nearest monitor
+        let work_areas: Vec<WindowRect> = self
+            .window()
+            .available_monitors()
+            .unwrap_or_default()
+            .iter()
+            .map(|monitor| {
+                let area = monitor.work_area();
+                WindowRect {
+                    x: area.position.x,
+                    y: area.position.y,
+                    width: area.size.width,
+                    height: area.size.height,
+                }
+            })
+            .collect();
+        let rect = fit_to_work_areas(&rect, &work_areas);
 
         let _ = self
             .window()
@@ -1397,9 +3204,99 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         self.get_window_rect().await
     }
 
+    /// Get native window state flags
+    #[cfg(desktop)]
+    async fn get_window_state(&self) -> Result<WindowState, WebDriverErrorResponse> {
+        let window = self.window();
+        let map_err = |e: tauri::Error| WebDriverErrorResponse::unknown_error(&e.to_string());
+        Ok(WindowState {
+            maximized: window.is_maximized().map_err(map_err)?,
+            minimized: window.is_minimized().map_err(map_err)?,
+            fullscreen: window.is_fullscreen().map_err(map_err)?,
+            focused: window.is_focused().map_err(map_err)?,
+            visible: window.is_visible().map_err(map_err)?,
+            always_on_top: window.is_always_on_top().map_err(map_err)?,
+            decorated: window.is_decorated().map_err(map_err)?,
+        })
+    }
+
+    /// Keep the window above all other windows
+    #[cfg(desktop)]
+    async fn set_always_on_top(&self, enabled: bool) -> Result<(), WebDriverErrorResponse> {
+        self.window()
+            .set_always_on_top(enabled)
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
+    /// Show or hide native window decorations (titlebar and borders)
+    #[cfg(desktop)]
+    async fn set_decorations(&self, enabled: bool) -> Result<(), WebDriverErrorResponse> {
+        self.window()
+            .set_decorations(enabled)
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
+    /// Bring the window to the foreground and give the webview keyboard focus.
+    /// Callers skip this for sessions with `hiddenWindows`, as raising a window shows it.
+    #[cfg(desktop)]
+    async fn focus_window(&self) -> Result<(), WebDriverErrorResponse> {
+        self.window()
+            .set_focus()
+            .and_then(|()| self.webview().set_focus())
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
+    /// Restore window to its normal state (exit fullscreen, unminimize, unmaximize)
+    #[cfg(desktop)]
+    async fn restore_window(&self) -> Result<(), WebDriverErrorResponse> {
+        self.exit_fullscreen_window().await?;
+        self.unminimize_window().await?;
+        if self.window().is_maximized().unwrap_or(false) {
+            let _ = self.window().unmaximize();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+
+    /// Exit fullscreen, waiting for the transition to finish (macOS animates it)
+    #[cfg(desktop)]
+    async fn exit_fullscreen_window(&self) -> Result<(), WebDriverErrorResponse> {
+        let window = self.window();
+        if !window.is_fullscreen().unwrap_or(false) {
+            return Ok(());
+        }
+
+        window
+            .set_fullscreen(false)
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+        while window.is_fullscreen().unwrap_or(false) {
+            if std::time::Instant::now() >= deadline {
+                return Err(WebDriverErrorResponse::unknown_error(
+                    "Timed out waiting for window to exit fullscreen",
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+
+    /// Restore a minimized window
+    #[cfg(desktop)]
+    async fn unminimize_window(&self) -> Result<(), WebDriverErrorResponse> {
+        if self.window().is_minimized().unwrap_or(false) {
+            let _ = self.window().unminimize();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+
     /// Maximize window
     #[cfg(desktop)]
     async fn maximize_window(&self) -> Result<WindowRect, WebDriverErrorResponse> {
+        self.exit_fullscreen_window().await?;
+        self.unminimize_window().await?;
         let _ = self.window().maximize();
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         self.get_window_rect().await
@@ -1415,8 +3312,11 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     /// Set window to fullscreen
     #[cfg(desktop)]
     async fn fullscreen_window(&self) -> Result<WindowRect, WebDriverErrorResponse> {
-        let _ = self.window().set_fullscreen(true);
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        self.unminimize_window().await?;
+        if !self.window().is_fullscreen().unwrap_or(false) {
+            let _ = self.window().set_fullscreen(true);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
         self.get_window_rect().await
     }
 
@@ -1431,6 +3331,44 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         ))
     }
 
+    /// Focus window (no-op on mobile, where the single webview always has focus)
+    #[cfg(mobile)]
+    async fn focus_window(&self) -> Result<(), WebDriverErrorResponse> {
+        Ok(())
+    }
+
+    /// Get window state (mobile unsupported)
+    #[cfg(mobile)]
+    async fn get_window_state(&self) -> Result<WindowState, WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Window state is not supported on mobile platforms",
+        ))
+    }
+
+    /// Set always-on-top (mobile unsupported)
+    #[cfg(mobile)]
+    async fn set_always_on_top(&self, _enabled: bool) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Always-on-top is not supported on mobile platforms",
+        ))
+    }
+
+    /// Set window decorations (mobile unsupported)
+    #[cfg(mobile)]
+    async fn set_decorations(&self, _enabled: bool) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Window decorations are not supported on mobile platforms",
+        ))
+    }
+
+    /// Restore window (mobile unsupported)
+    #[cfg(mobile)]
+    async fn restore_window(&self) -> Result<(), WebDriverErrorResponse> {
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Restoring window is not supported on mobile platforms",
+        ))
+    }
+
     /// Maximize window (mobile unsupported)
     #[cfg(mobile)]
     async fn maximize_window(&self) -> Result<WindowRect, WebDriverErrorResponse> {
@@ -1481,12 +3419,12 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
             FrameId::Element(js_var) => {
                 let script = format!(
                     r"(function() {{
-                        var el = window.{js_var};
+                        var el = {ELEMENT_REFS_JS}.get('{js_var}');
                         if (!el || !el.isConnected) {{
-                            throw new Error('stale element reference');
+                            throw new Error('__wd:stale element reference');
                         }}
                         if (el.tagName !== 'IFRAME' && el.tagName !== 'FRAME') {{
-                            throw new Error('element is not a frame');
+                            throw new Error('__wd:element is not a frame');
                         }}
                         return true;
                     }})()"
@@ -1499,7 +3437,9 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 
     /// Switch to parent frame
     async fn switch_to_parent_frame(&self) -> Result<(), WebDriverErrorResponse> {
-        // No-op - frame context is managed by the session, not the executor
+        // Frame context is managed by the session; the executor is created for the
+        // parent context, so evaluating anything validates that the frame chain resolves
+        self.evaluate_js("true").await?;
         Ok(())
     }
 
@@ -1507,12 +3447,27 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     // Cookies (using Tauri's native cookie APIs)
     // =========================================================================
 
+    /// Cookies of the current document from the native cookie store (`WebView2`'s cookie
+    /// manager, `WKHTTPCookieStore` or `WebKitCookieManager`), including `HttpOnly` ones.
+    /// Custom-scheme pages such as `tauri://localhost` have no URL-scoped cookies, so
+    /// they see the whole store.
+    fn document_cookies(&self) -> Result<Vec<TauriCookie<'static>>, WebDriverErrorResponse> {
+        let cookies = match self.webview().url() {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                self.webview().cookies_for_url(url)
+            }
+            _ => self.webview().cookies(),
+        };
+        cookies.map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
     /// Get all cookies
     async fn get_all_cookies(&self) -> Result<Vec<Cookie>, WebDriverErrorResponse> {
-        self.window()
-            .cookies()
-            .map(|cookies| cookies.iter().map(tauri_cookie_to_webdriver).collect())
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+        Ok(self
+            .document_cookies()?
+            .iter()
+            .map(tauri_cookie_to_webdriver)
+            .collect())
     }
 
     /// Get a specific cookie by name
@@ -1525,7 +3480,7 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
     async fn add_cookie(&self, mut cookie: Cookie) -> Result<(), WebDriverErrorResponse> {
         // Per WebDriver spec: if no domain is specified, use the current page's domain
         if cookie.domain.is_none() {
-            if let Ok(url) = self.window().url() {
+            if let Ok(url) = self.webview().url() {
                 cookie.domain = url.host_str().map(String::from);
             }
         }
@@ -1536,22 +3491,17 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         }
 
         let tauri_cookie = webdriver_cookie_to_tauri(&cookie);
-        self.window()
+        self.webview()
             .set_cookie(tauri_cookie)
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+            .map_err(|e| WebDriverErrorResponse::unable_to_set_cookie(&e.to_string()))
     }
 
     /// Delete a cookie by name
     async fn delete_cookie(&self, name: &str) -> Result<(), WebDriverErrorResponse> {
         // Find the cookie first to get its exact domain/path for deletion
-        let cookies = self
-            .window()
-            .cookies()
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
-
-        for cookie in cookies {
+        for cookie in self.document_cookies()? {
             if cookie.name() == name {
-                self.window()
+                self.webview()
                     .delete_cookie(cookie)
                     .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
                 return Ok(());
@@ -1562,27 +3512,202 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 
     /// Delete all cookies
     async fn delete_all_cookies(&self) -> Result<(), WebDriverErrorResponse> {
-        let cookies = self
-            .window()
-            .cookies()
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
-
-        for cookie in cookies {
-            self.window()
+        for cookie in self.document_cookies()? {
+            self.webview()
                 .delete_cookie(cookie)
                 .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
         }
         Ok(())
     }
 
+    // =========================================================================
+    // Web Storage
+    // =========================================================================
+
+    /// Keys of a storage area in the current browsing context, in storage order
+    async fn get_storage_keys(
+        &self,
+        area: StorageArea,
+    ) -> Result<Vec<String>, WebDriverErrorResponse> {
+        let storage = area.as_js();
+        let script = format!(
+            r"(function() {{
+                var storage = window.{storage};
+                var keys = [];
+                for (var i = 0; i < storage.length; i++) {{
+                    keys.push(storage.key(i));
+                }}
+                return keys;
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        extract_string_list(&result)
+    }
+
+    /// Value stored under `key`, or `None` if the key is absent
+    async fn get_storage_item(
+        &self,
+        area: StorageArea,
+        key: &str,
+    ) -> Result<Option<String>, WebDriverErrorResponse> {
+        let storage = area.as_js();
+        let key = serde_json::to_string(key)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let script = format!("window.{storage}.getItem({key})");
+        let result = self.evaluate_js(&script).await?;
+        Ok(extract_value(&result)?.as_str().map(String::from))
+    }
+
+    /// Store `value` under `key`
+    async fn set_storage_item(
+        &self,
+        area: StorageArea,
+        key: &str,
+        value: &str,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let storage = area.as_js();
+        let key = serde_json::to_string(key)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let value = serde_json::to_string(value)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let script = format!(
+            r"(function() {{
+                window.{storage}.setItem({key}, {value});
+                return true;
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        extract_value(&result)?;
+        Ok(())
+    }
+
+    /// Remove `key` from a storage area
+    async fn remove_storage_item(
+        &self,
+        area: StorageArea,
+        key: &str,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let storage = area.as_js();
+        let key = serde_json::to_string(key)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+        let script = format!(
+            r"(function() {{
+                window.{storage}.removeItem({key});
+                return true;
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        extract_value(&result)?;
+        Ok(())
+    }
+
+    /// Remove every key from a storage area
+    async fn clear_storage(&self, area: StorageArea) -> Result<(), WebDriverErrorResponse> {
+        let storage = area.as_js();
+        let script = format!(
+            r"(function() {{
+                window.{storage}.clear();
+                return true;
+            }})()"
+        );
+        let result = self.evaluate_js(&script).await?;
+        extract_value(&result)?;
+        Ok(())
+    }
+
+    /// Databases of the current origin with their version and object store names
+    async fn get_indexed_databases(&self) -> Result<Value, WebDriverErrorResponse> {
+        let script = r"var done = arguments[arguments.length - 1];
+            if (!window.indexedDB || !indexedDB.databases) {
+                done({ error: 'IndexedDB databases cannot be listed in this webview' });
+                return;
+            }
+            indexedDB.databases().then(function(infos) {
+                return Promise.all(infos.map(function(info) {
+                    return new Promise(function(resolve) {
+                        var entry = { name: info.name, version: info.version, objectStores: [] };
+                        var request = indexedDB.open(info.name);
+                        request.onupgradeneeded = function() {
+                            // Deleted in the meantime; don't create it again
+                            request.transaction.abort();
+                        };
+                        request.onsuccess = function() {
+                            entry.objectStores = Array.from(request.result.objectStoreNames);
+                            request.result.close();
+                            resolve(entry);
+                        };
+                        request.onerror = function() { resolve(entry); };
+                    });
+                }));
+            }).then(function(databases) {
+                done({ databases: databases });
+            }, function(e) {
+                done({ error: String(e && e.message || e) });
+            });";
+        let result = self.execute_async_script(script, &[]).await?;
+        if let Some(error) = result.get("error").and_then(Value::as_str) {
+            return Err(WebDriverErrorResponse::unknown_error(error));
+        }
+        Ok(result.get("databases").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Delete a database of the current origin. Deletion waits until the page closes its
+    /// connections, which well-behaved apps do on `versionchange`.
+    async fn delete_indexed_database(&self, name: &str) -> Result<(), WebDriverErrorResponse> {
+        let script = r"var done = arguments[arguments.length - 1];
+            var request = indexedDB.deleteDatabase(arguments[0]);
+            request.onsuccess = function() { done({}); };
+            request.onerror = function() {
+                done({ error: String(request.error && request.error.message || request.error) });
+            };";
+        let result = self
+            .execute_async_script(script, &[Value::String(name.to_string())])
+            .await?;
+        if let Some(error) = result.get("error").and_then(Value::as_str) {
+            return Err(WebDriverErrorResponse::unknown_error(error));
+        }
+        Ok(())
+    }
+
+    /// Wipe cookies, Web Storage, `IndexedDB` and Cache Storage. The page's own state is
+    /// cleared first so the open document sees it gone, then the native data store is
+    /// cleared for everything the page cannot reach (`HttpOnly` cookies, other origins).
+    async fn clear_site_data(&self) -> Result<(), WebDriverErrorResponse> {
+        let script = r"var done = arguments[arguments.length - 1];
+            try { localStorage.clear(); } catch (e) {}
+            try { sessionStorage.clear(); } catch (e) {}
+            var pending = [];
+            if (window.caches) {
+                pending.push(caches.keys().then(function(names) {
+                    return Promise.all(names.map(function(name) { return caches.delete(name); }));
+                }));
+            }
+            if (window.indexedDB && indexedDB.databases) {
+                pending.push(indexedDB.databases().then(function(infos) {
+                    return Promise.all(infos.map(function(info) {
+                        return new Promise(function(resolve) {
+                            var request = indexedDB.deleteDatabase(info.name);
+                            request.onsuccess = request.onerror = request.onblocked = resolve;
+                        });
+                    }));
+                }));
+            }
+            Promise.all(pending).then(function() { done(true); }, function() { done(true); });";
+        self.execute_async_script(script, &[]).await?;
+
+        self.webview()
+            .clear_all_browsing_data()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
     // =========================================================================
     // Alerts (using per-window alert state)
     // =========================================================================
 
     /// Dismiss the current alert (cancel)
     async fn dismiss_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let manager = self.window().app_handle().state::<AlertStateManager>();
-        let alert_state = manager.get_or_create(self.window().label());
+        let manager = self.webview().app_handle().state::<AlertStateManager>();
+        let alert_state = manager.get_or_create(self.webview().label());
         if alert_state.respond(false, None) {
             Ok(())
         } else {
@@ -1592,8 +3717,8 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 
     /// Accept the current alert (OK)
     async fn accept_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let manager = self.window().app_handle().state::<AlertStateManager>();
-        let alert_state = manager.get_or_create(self.window().label());
+        let manager = self.webview().app_handle().state::<AlertStateManager>();
+        let alert_state = manager.get_or_create(self.webview().label());
         // For prompts, use input text if set, otherwise default text
         let prompt_text = alert_state
             .get_prompt_input()
@@ -1607,8 +3732,8 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 
     /// Get the text of the current alert
     async fn get_alert_text(&self) -> Result<String, WebDriverErrorResponse> {
-        let manager = self.window().app_handle().state::<AlertStateManager>();
-        let alert_state = manager.get_or_create(self.window().label());
+        let manager = self.webview().app_handle().state::<AlertStateManager>();
+        let alert_state = manager.get_or_create(self.webview().label());
         match alert_state.get_message() {
             Some(msg) => Ok(msg),
             None => Err(WebDriverErrorResponse::no_such_alert()),
@@ -1617,8 +3742,8 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 
     /// Send text to the current alert (for prompts)
     async fn send_alert_text(&self, text: &str) -> Result<(), WebDriverErrorResponse> {
-        let manager = self.window().app_handle().state::<AlertStateManager>();
-        let alert_state = manager.get_or_create(self.window().label());
+        let manager = self.webview().app_handle().state::<AlertStateManager>();
+        let alert_state = manager.get_or_create(self.webview().label());
         match alert_state.get_alert_type() {
             None => Err(WebDriverErrorResponse::no_such_alert()),
             Some(AlertType::Prompt) => {
@@ -1635,6 +3760,14 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
         }
     }
 
+    /// Fail with `unexpected alert open` if a user prompt is currently showing
+    async fn check_no_alert(&self) -> Result<(), WebDriverErrorResponse> {
+        match self.get_alert_text().await {
+            Ok(text) => Err(WebDriverErrorResponse::unexpected_alert_open(&text)),
+            Err(_) => Ok(()),
+        }
+    }
+
     // =========================================================================
     // Print
     // =========================================================================
@@ -1647,6 +3780,174 @@ pub trait PlatformExecutor<R: Runtime>: Send + Sync {
 // Helper Functions for Default Implementations
 // =============================================================================
 
+/// Guess the MIME type of an uploaded file from its extension
+fn mime_type_for_path(path: &std::path::Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "zip" => "application/zip",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        _ => "",
+    }
+}
+
+/// JavaScript function preparing an edit of an `<input>` or `<textarea>` value.
+/// Text replaces the selection, or is appended when the type has no selection API,
+/// and is clipped to what `maxlength` leaves room for, as typing would.
+const FIELD_EDIT_JS: &str = r"function(el) {
+    var setValue = Object.getOwnPropertyDescriptor(
+        el.tagName === 'INPUT' ? window.HTMLInputElement.prototype : window.HTMLTextAreaElement.prototype,
+        'value'
+    ).set;
+    var value = el.value;
+    // Types such as email and number report a null selection
+    var hasSelection = typeof el.selectionStart === 'number';
+    var start = hasSelection ? el.selectionStart : value.length;
+    var end = hasSelection ? el.selectionEnd : value.length;
+    var before = value.slice(0, start);
+    var after = value.slice(end);
+    var room = el.maxLength >= 0 ? Math.max(0, el.maxLength - before.length - after.length) : Infinity;
+    return {
+        fit: function(text) {
+            return text.length > room ? text.slice(0, room) : text;
+        },
+        set: function(text) {
+            setValue.call(el, before + text + after);
+            if (hasSelection) {
+                el.setSelectionRange(start + text.length, start + text.length);
+            }
+        }
+    };
+}";
+
+/// Build a script that inserts typeable text into the focused element
+fn insert_text_script(js_var: &str, text: &str) -> String {
+    let data = serde_json::to_string(text).unwrap_or_default();
+    format!(
+        r"(function() {{
+            var el = {ELEMENT_REFS_JS}.get('{js_var}');
+            if (el.tagName === 'INPUT' || el.tagName === 'TEXTAREA') {{
+                var edit = ({FIELD_EDIT_JS})(el);
+                var data = edit.fit({data});
+                if (data === '') {{
+                    return true;
+                }}
+                edit.set(data);
+
+                var inputEvent = new InputEvent('input', {{
+                    bubbles: true,
+                    cancelable: true,
+                    inputType: 'insertText',
+                    data: data
+                }});
+                el.dispatchEvent(inputEvent);
+
+                var changeEvent = new Event('change', {{ bubbles: true }});
+                el.dispatchEvent(changeEvent);
+            }} else if (el.isContentEditable) {{
+                document.execCommand('insertText', false, {data});
+            }}
+            return true;
+        }})()"
+    )
+}
+
+/// Build a script that enters text into the focused element through an IME-style
+/// composition, one character per update, like a CJK input method
+fn compose_text_script(js_var: &str, text: &str) -> String {
+    let data = serde_json::to_string(text).unwrap_or_default();
+    format!(
+        r"(function() {{
+            var el = {ELEMENT_REFS_JS}.get('{js_var}');
+            var isField = el.tagName === 'INPUT' || el.tagName === 'TEXTAREA';
+            var edit = isField ? ({FIELD_EDIT_JS})(el) : null;
+
+            // Editable content is composed into a text node at the caret
+            var node = null;
+            var selection = window.getSelection();
+            if (!isField && el.isContentEditable) {{
+                var range = selection.rangeCount > 0 ? selection.getRangeAt(0) : null;
+                if (!range || !el.contains(range.startContainer)) {{
+                    range = document.createRange();
+                    range.selectNodeContents(el);
+                    range.collapse(false);
+                }}
+                range.deleteContents();
+                node = document.createTextNode('');
+                range.insertNode(node);
+            }}
+
+            // IMEs report keys they consume as 'Process' with keyCode 229
+            var key = function(type, isComposing) {{
+                el.dispatchEvent(new KeyboardEvent(type, {{
+                    key: 'Process',
+                    keyCode: 229,
+                    which: 229,
+                    isComposing: isComposing,
+                    bubbles: true,
+                    cancelable: true
+                }}));
+            }};
+            var input = function(type, data, isComposing) {{
+                el.dispatchEvent(new InputEvent(type, {{
+                    inputType: 'insertCompositionText',
+                    data: data,
+                    isComposing: isComposing,
+                    bubbles: true
+                }}));
+            }};
+
+            var composed = '';
+            key('keydown', false);
+            el.dispatchEvent(new CompositionEvent('compositionstart', {{ data: '', bubbles: true, cancelable: true }}));
+            Array.from({data}).forEach(function(ch, i) {{
+                if (i > 0) key('keydown', true);
+                composed += ch;
+                input('beforeinput', composed, true);
+                el.dispatchEvent(new CompositionEvent('compositionupdate', {{ data: composed, bubbles: true }}));
+                if (edit) {{
+                    edit.set(composed);
+                }} else if (node) {{
+                    node.data = composed;
+                }}
+                input('input', composed, true);
+                key('keyup', true);
+            }});
+
+            // The committed text is clipped to maxlength, like browsers do on commit
+            if (edit) {{
+                composed = edit.fit(composed);
+                edit.set(composed);
+            }}
+            el.dispatchEvent(new CompositionEvent('compositionend', {{ data: composed, bubbles: true }}));
+            input('input', composed, false);
+
+            if (isField) {{
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            }} else if (node) {{
+                var caret = document.createRange();
+                caret.setStartAfter(node);
+                caret.collapse(true);
+                selection.removeAllRanges();
+                selection.addRange(caret);
+            }}
+            return true;
+        }})()"
+    )
+}
+
 /// Extract string value from JavaScript result
 fn extract_string_value(result: &Value) -> Result<String, WebDriverErrorResponse> {
     if let Some(success) = result.get("success").and_then(Value::as_bool) {
@@ -1658,7 +3959,7 @@ fn extract_string_value(result: &Value) -> Result<String, WebDriverErrorResponse
                 return Ok(value.to_string());
             }
         } else if let Some(error) = result.get("error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
+            return Err(WebDriverErrorResponse::from_js_error(error, None));
         }
     }
     Ok(String::new())
@@ -1672,24 +3973,24 @@ fn extract_bool_value(result: &Value) -> Result<bool, WebDriverErrorResponse> {
                 return Ok(value);
             }
         } else if let Some(error) = result.get("error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
+            return Err(WebDriverErrorResponse::from_js_error(error, None));
         }
     }
     Ok(false)
 }
 
-/// Extract usize value from JavaScript result
-fn extract_usize_value(result: &Value) -> Result<usize, WebDriverErrorResponse> {
-    if let Some(success) = result.get("success").and_then(Value::as_bool) {
-        if success {
-            if let Some(count) = result.get("value").and_then(Value::as_u64) {
-                return Ok(usize::try_from(count).unwrap_or(0));
-            }
-        } else if let Some(error) = result.get("error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
-        }
-    }
-    Ok(0)
+/// Extract a list of strings from JavaScript result
+fn extract_string_list(result: &Value) -> Result<Vec<String>, WebDriverErrorResponse> {
+    Ok(extract_value(result)?
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default())
 }
 
 /// Extract raw Value from JavaScript result
@@ -1698,7 +3999,7 @@ fn extract_value(result: &Value) -> Result<Value, WebDriverErrorResponse> {
         if success {
             return Ok(result.get("value").cloned().unwrap_or(Value::Null));
         } else if let Some(error) = result.get("error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
+            return Err(WebDriverErrorResponse::from_js_error(error, None));
         }
     }
     Ok(Value::Null)
@@ -1711,7 +4012,7 @@ fn extract_script_result(result: &Value) -> Result<Value, WebDriverErrorResponse
         if success {
             result.get("value").cloned().unwrap_or(Value::Null)
         } else if let Some(error) = result.get("error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
+            return Err(WebDriverErrorResponse::from_js_error(error, None));
         } else {
             Value::Null
         }
@@ -1719,12 +4020,17 @@ fn extract_script_result(result: &Value) -> Result<Value, WebDriverErrorResponse
         Value::Null
     };
 
-    // Now check the script execution result
+    extract_script_outcome(&inner)
+}
+
+/// Extract the value from the `{ __wd_success, __wd_value | __wd_error }` object the
+/// script wrappers build
+pub fn extract_script_outcome(inner: &Value) -> Result<Value, WebDriverErrorResponse> {
     if let Some(success) = inner.get("__wd_success").and_then(Value::as_bool) {
         if success {
             return Ok(inner.get("__wd_value").cloned().unwrap_or(Value::Null));
         } else if let Some(error) = inner.get("__wd_error").and_then(Value::as_str) {
-            return Err(WebDriverErrorResponse::javascript_error(error, None));
+            return Err(WebDriverErrorResponse::from_js_error(error, None));
         }
     }
 
@@ -1739,72 +4045,165 @@ fn extract_script_result(result: &Value) -> Result<Value, WebDriverErrorResponse
     Ok(Value::Null)
 }
 
-/// Wrap a JavaScript script to execute within a specific frame context.
-/// If `frame_context` is empty (top-level), returns the script unchanged.
-/// Otherwise, wraps the script to navigate to the correct frame before execution.
-pub fn wrap_script_for_frame_context(script: &str, frame_context: &[FrameId]) -> String {
+/// Build a JavaScript expression evaluating to a promise of the
+/// `{ __wd_success, __wd_value | __wd_error }` outcome of `script`, for platforms that run
+/// it in an isolated world. Async scripts settle when they call the callback appended to
+/// their arguments, sync ones when they return or the returned promise settles.
+pub fn new_realm_script(
+    script: &str,
+    args: &[Value],
+    is_async: bool,
+) -> Result<String, WebDriverErrorResponse> {
+    let args_json = serde_json::to_string(args)
+        .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+    let run = if is_async {
+        "args.push(resolve); fn.apply(null, args);"
+    } else {
+        "resolve(fn.apply(null, args));"
+    };
+    Ok(format!(
+        r"new Promise(function(resolve) {{
+            var args = {args_json}.map(function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, window); }});
+            var fn = function() {{ {script}
+            }};
+            {run}
+        }}).then(function(result) {{
+            return {{ __wd_success: true, __wd_value: ({SERIALIZE_RESULT_JS})(result, window) }};
+        }}).catch(function(e) {{
+            return {{ __wd_success: false, __wd_error: (e && e.message) || String(e) }};
+        }})"
+    ))
+}
+
+/// Symbol of the per-document map from session to the frame window it selected
+const FRAME_WINDOWS_SYMBOL_JS: &str = "Symbol.for('tauri-plugin-webdriver:frame-windows')";
+
+/// Statement thrown when the selected frame's realm is out of reach of page script
+const CROSS_ORIGIN_FRAME_JS: &str = "throw new Error('__wd:unsupported operation: cross-origin frames can be selected but not scripted on this platform')";
+
+/// Build a JavaScript expression that resolves to the window of the selected frame.
+/// If `frame_context` is empty (top-level), this is simply `window`.
+/// Throws `no such frame` / `stale element reference` if the frame chain is broken.
+/// Only the documents of the frames above the selected one are read, so a cross-origin
+/// frame resolves to its `WindowProxy`; stepping through one throws `unsupported
+/// operation`.
+pub fn frame_window_script(frame_context: &FrameContext) -> String {
     use std::fmt::Write;
 
     if frame_context.is_empty() {
-        return script.to_string();
+        return "window".to_string();
     }
 
-    // Build JavaScript to navigate to the target frame
+    // Build JavaScript to navigate to the target frame. A window cached for the session
+    // is reused while the selection is unchanged and its frame element still shows it;
+    // a frame that was removed or whose ancestor went away fails that check.
     let mut frame_nav = String::new();
     frame_nav.push_str("(function() {\n");
+    if let Some(owner) = &frame_context.owner {
+        let generation = frame_context.generation;
+        let _ = writeln!(
+            frame_nav,
+            "  var cache = window[{FRAME_WINDOWS_SYMBOL_JS}] || Object.defineProperty(window, {FRAME_WINDOWS_SYMBOL_JS}, {{ value: new Map() }})[{FRAME_WINDOWS_SYMBOL_JS}];"
+        );
+        let _ = writeln!(frame_nav, "  var cached = cache.get('{owner}');");
+        let _ = writeln!(
+            frame_nav,
+            "  if (cached && cached.generation === {generation} && cached.frame.isConnected && cached.frame.contentWindow === cached.win) return cached.win;"
+        );
+    }
     frame_nav.push_str("  var ctx = window;\n");
-    frame_nav.push_str("  var doc = document;\n");
+    let _ = writeln!(
+        frame_nav,
+        "  var enter = function(win) {{ try {{ return win.document; }} catch (e) {{ {CROSS_ORIGIN_FRAME_JS}; }} }};"
+    );
 
     for (i, frame_id) in frame_context.iter().enumerate() {
+        let _ = writeln!(frame_nav, "  var doc{i} = enter(ctx);");
         match frame_id {
             FrameId::Index(index) => {
                 let _ = writeln!(
                     frame_nav,
-                    "  var frames{i} = doc.querySelectorAll('iframe, frame');"
+                    "  var frames{i} = doc{i}.querySelectorAll('iframe, frame');"
                 );
                 let _ = writeln!(
                     frame_nav,
-                    "  if ({index} >= frames{i}.length) throw new Error('no such frame');"
+                    "  if ({index} >= frames{i}.length) throw new Error('__wd:no such frame');"
                 );
                 let _ = writeln!(frame_nav, "  var frame{i} = frames{i}[{index}];");
-                let _ = writeln!(
-                    frame_nav,
-                    "  if (!frame{i}.contentWindow) throw new Error('no such frame');"
-                );
-                let _ = writeln!(frame_nav, "  ctx = frame{i}.contentWindow;");
-                let _ = writeln!(frame_nav, "  doc = frame{i}.contentDocument;");
             }
             FrameId::Element(js_var) => {
-                let _ = writeln!(frame_nav, "  var frame{i} = window.{js_var};");
+                // Frame elements are stored on the window of the frame they were found in
                 let _ = writeln!(
                     frame_nav,
-                    "  if (!frame{i} || !doc.contains(frame{i})) throw new Error('stale element reference');"
+                    "  var frame{i} = (ctx[{ELEMENT_REFS_SYMBOL_JS}] || new Map()).get('{js_var}');"
                 );
                 let _ = writeln!(
                     frame_nav,
-                    "  if (frame{i}.tagName !== 'IFRAME' && frame{i}.tagName !== 'FRAME') throw new Error('element is not a frame');"
+                    "  if (!frame{i} || !doc{i}.contains(frame{i})) throw new Error('__wd:stale element reference');"
                 );
                 let _ = writeln!(
                     frame_nav,
-                    "  if (!frame{i}.contentWindow) throw new Error('no such frame');"
+                    "  if (frame{i}.tagName !== 'IFRAME' && frame{i}.tagName !== 'FRAME') throw new Error('__wd:element is not a frame');"
                 );
-                let _ = writeln!(frame_nav, "  ctx = frame{i}.contentWindow;");
-                let _ = writeln!(frame_nav, "  doc = frame{i}.contentDocument;");
             }
         }
+        let _ = writeln!(
+            frame_nav,
+            "  if (!frame{i}.contentWindow) throw new Error('__wd:no such frame');"
+        );
+        let _ = writeln!(frame_nav, "  ctx = frame{i}.contentWindow;");
     }
 
-    // Execute the original script in the frame context
-    // We use Function constructor to evaluate in the frame's context
+    if let Some(owner) = &frame_context.owner {
+        let last = frame_context.len() - 1;
+        let generation = frame_context.generation;
+        let _ = writeln!(
+            frame_nav,
+            "  cache.set('{owner}', {{ generation: {generation}, frame: frame{last}, win: ctx }});"
+        );
+    }
+    frame_nav.push_str("  return ctx;\n");
+    frame_nav.push_str("})()");
+
+    frame_nav
+}
+
+/// Wrap a JavaScript script to execute within a specific frame context.
+/// If `frame_context` is empty (top-level), returns the script unchanged.
+/// Otherwise, wraps the script to navigate to the correct frame before execution.
+/// Cross-origin frames fail with `unsupported operation`.
+pub fn wrap_script_for_frame_context(script: &str, frame_context: &FrameContext) -> String {
+    if frame_context.is_empty() {
+        return script.to_string();
+    }
+
+    // Evaluate the original script in the frame's global scope
     let escaped_script = script
         .replace('\\', "\\\\")
         .replace('`', "\\`")
         .replace("${", "\\${");
 
-    let _ = writeln!(frame_nav, "  return ctx.eval(`{escaped_script}`);");
-    frame_nav.push_str("})()");
+    format!(
+        "(function() {{ var ctx = {}; var evaluate; try {{ evaluate = ctx.eval; }} catch (e) {{ {CROSS_ORIGIN_FRAME_JS}; }} return evaluate.call(ctx, `{escaped_script}`); }})()",
+        frame_window_script(frame_context)
+    )
+}
 
-    frame_nav
+/// Build a JavaScript function expression whose body is `script`, created in the
+/// realm of the selected frame so that `window`/`document` refer to the frame.
+/// Used by async script wrappers, which are evaluated at top level or, on macOS, in the
+/// nearest frame reached natively. Cross-origin frames fail with `unsupported operation`.
+#[cfg(any(target_os = "android", target_os = "ios", target_os = "macos"))]
+pub fn frame_function_script(script: &str, frame_context: &FrameContext) -> String {
+    if frame_context.is_empty() {
+        return format!("(function() {{ {script} }})");
+    }
+
+    let script_literal = serde_json::to_string(script).unwrap_or_else(|_| "''".to_string());
+    format!(
+        "(function() {{ var ctx = {}; var create; try {{ create = ctx.Function; }} catch (e) {{ {CROSS_ORIGIN_FRAME_JS}; }} return create({script_literal}); }})()",
+        frame_window_script(frame_context)
+    )
 }
 
 // =============================================================================
@@ -1823,7 +4222,8 @@ fn tauri_cookie_to_webdriver(cookie: &TauriCookie<'static>) -> Cookie {
         secure: cookie.secure().unwrap_or(false),
         http_only: cookie.http_only().unwrap_or(false),
         expiry: cookie.expires().and_then(|exp| match exp {
-            Expiration::DateTime(dt) => Some(dt.unix_timestamp().cast_unsigned()),
+            // Stores can report expiry before the epoch, which WebDriver can't represent
+            Expiration::DateTime(dt) => u64::try_from(dt.unix_timestamp()).ok(),
             Expiration::Session => None,
         }),
         same_site: cookie.same_site().map(|ss| match ss {
@@ -1857,7 +4257,8 @@ fn webdriver_cookie_to_tauri(cookie: &Cookie) -> TauriCookie<'static> {
     }
 
     if let Some(expiry) = cookie.expiry {
-        if let Ok(dt) = OffsetDateTime::from_unix_timestamp(expiry.cast_signed()) {
+        let expiry = i64::try_from(expiry).unwrap_or(i64::MAX);
+        if let Ok(dt) = OffsetDateTime::from_unix_timestamp(expiry) {
             builder = builder.expires(Expiration::DateTime(dt));
         }
     }
@@ -1,14 +1,15 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{Manager, Runtime, WebviewWindow};
+use tauri::{Manager, Runtime, Webview};
 
 use crate::mobile::{
-    AlertResult, EvaluateJsArgs, JsResult, ScreenshotArgs, SendAlertTextArgs, TouchArgs, Webdriver,
+    AlertResult, EvaluateJsArgs, JsResult, ScreenshotArgs, SendAlertTextArgs, Webdriver,
 };
 use crate::platform::{
-    wrap_script_for_frame_context, FrameId, PlatformExecutor, PointerEventType, PrintOptions,
-    WindowRect,
+    extract_script_outcome, frame_function_script, frame_window_script, new_realm_script,
+    wrap_script_for_frame_context, FrameContext, PlatformExecutor, PointerEventType, PointerInput,
+    PrintOptions, ScreenshotClip, WindowRect, DESERIALIZE_ARG_JS, SERIALIZE_RESULT_JS,
 };
 use crate::server::response::WebDriverErrorResponse;
 use crate::webdriver::Timeouts;
@@ -16,19 +17,53 @@ use crate::webdriver::Timeouts;
 /// iOS WKWebView executor using Tauri's mobile plugin bridge
 #[derive(Clone)]
 pub struct IOSExecutor<R: Runtime> {
-    window: WebviewWindow<R>,
+    webview: Webview<R>,
     timeouts: Timeouts,
-    frame_context: Vec<FrameId>,
+    frame_context: FrameContext,
 }
 
 impl<R: Runtime> IOSExecutor<R> {
-    pub fn new(window: WebviewWindow<R>, timeouts: Timeouts, frame_context: Vec<FrameId>) -> Self {
+    pub fn new(webview: Webview<R>, timeouts: Timeouts, frame_context: FrameContext) -> Self {
         Self {
-            window,
+            webview,
             timeouts,
             frame_context,
         }
     }
+
+    /// Take a screenshot of the webview, cropped natively to `clip` if given
+    async fn snapshot(
+        &self,
+        clip: Option<ScreenshotClip>,
+    ) -> Result<String, WebDriverErrorResponse> {
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
+
+        let args = ScreenshotArgs {
+            timeout_ms: self.timeouts.script_ms,
+            clip,
+            full_page: false,
+        };
+
+        let result: JsResult = webdriver
+            .0
+            .run_mobile_plugin_async("takeScreenshot", args)
+            .await
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        if result.success {
+            if let Some(Value::String(base64)) = result.value {
+                Ok(base64)
+            } else {
+                Err(WebDriverErrorResponse::unknown_error(
+                    "Screenshot returned invalid data",
+                ))
+            }
+        } else {
+            Err(WebDriverErrorResponse::unknown_error(
+                result.error.as_deref().unwrap_or("Screenshot failed"),
+            ))
+        }
+    }
 }
 
 // =============================================================================
@@ -40,18 +75,21 @@ impl<R: Runtime> IOSExecutor<R> {
 struct AsyncScriptArgs {
     script: String,
     timeout_ms: u64,
+    /// Isolated content world to run in instead of the page's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    world_name: Option<String>,
 }
 
 #[async_trait]
 impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
-    fn window(&self) -> &WebviewWindow<R> {
-        &self.window
+    fn webview(&self) -> &Webview<R> {
+        &self.webview
     }
 
     async fn evaluate_js(&self, script: &str) -> Result<Value, WebDriverErrorResponse> {
         let wrapped_script = wrap_script_for_frame_context(script, &self.frame_context);
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let args = EvaluateJsArgs {
             script: wrapped_script,
@@ -77,7 +115,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
             if error_msg.to_lowercase().contains("timeout") {
                 Err(WebDriverErrorResponse::script_timeout())
             } else {
-                Err(WebDriverErrorResponse::javascript_error(error_msg, None))
+                Err(WebDriverErrorResponse::from_js_error(error_msg, None))
             }
         }
     }
@@ -92,35 +130,28 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
 
         // Build wrapper that includes argument deserialization
         // Swift wraps this in a Promise and provides __done via callAsyncJavaScript
+        let frame_window = frame_window_script(&self.frame_context);
+        let function = frame_function_script(script, &self.frame_context);
         let wrapper = format!(
-            r"var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-            function deserializeArg(arg) {{
-                if (arg === null || arg === undefined) return arg;
-                if (Array.isArray(arg)) return arg.map(deserializeArg);
-                if (typeof arg === 'object') {{
-                    if (arg[ELEMENT_KEY]) {{
-                        var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                        if (!el) throw new Error('stale element reference');
-                        return el;
-                    }}
-                    var result = {{}};
-                    for (var key in arg) {{
-                        if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                    }}
-                    return result;
-                }}
-                return arg;
-            }}
+            r"var __ctx = {frame_window};
+            var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, __ctx); }};
             var __args = {args_json}.map(deserializeArg);
-            __args.push(__done);
-            (function() {{ {script} }}).apply(null, __args);"
+            __args.push(function(result) {{
+                try {{
+                    __done(({SERIALIZE_RESULT_JS})(result, __ctx));
+                }} catch (e) {{
+                    __done(null, e.message || String(e));
+                }}
+            }});
+            {function}.apply(null, __args);"
         );
 
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let plugin_args = AsyncScriptArgs {
             script: wrapper,
             timeout_ms: self.timeouts.script_ms,
+            world_name: None,
         };
 
         let result: JsResult = webdriver
@@ -137,62 +168,82 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
             if error_msg.to_lowercase().contains("timeout") {
                 Err(WebDriverErrorResponse::script_timeout())
             } else {
-                Err(WebDriverErrorResponse::javascript_error(error_msg, None))
+                Err(WebDriverErrorResponse::from_js_error(error_msg, None))
             }
         }
     }
 
-    async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+    async fn execute_script_in_new_realm(
+        &self,
+        script: &str,
+        args: &[Value],
+        is_async: bool,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        // Scripts of other worlds are run in the main frame only
+        if !self.frame_context.is_empty() {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "New realms are only supported in top-level browsing contexts on this platform",
+            ));
+        }
+        let expression = new_realm_script(script, args, is_async)?;
 
-        let args = ScreenshotArgs {
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
+        let plugin_args = AsyncScriptArgs {
+            script: format!(
+                "Promise.resolve({expression}).then(function(outcome) {{ __done(outcome); }});"
+            ),
             timeout_ms: self.timeouts.script_ms,
+            world_name: Some(format!("webdriver-{}", uuid::Uuid::new_v4())),
         };
 
         let result: JsResult = webdriver
             .0
-            .run_mobile_plugin_async("takeScreenshot", args)
+            .run_mobile_plugin_async("executeAsyncScript", plugin_args)
             .await
             .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
 
         if result.success {
-            if let Some(Value::String(base64)) = result.value {
-                Ok(base64)
+            extract_script_outcome(&result.value.unwrap_or(Value::Null))
+        } else {
+            let error_msg = result.error.as_deref().unwrap_or("Unknown error");
+            if error_msg.to_lowercase().contains("timeout") {
+                Err(WebDriverErrorResponse::script_timeout())
             } else {
-                Err(WebDriverErrorResponse::unknown_error(
-                    "Screenshot returned invalid data",
-                ))
+                Err(WebDriverErrorResponse::from_js_error(error_msg, None))
             }
-        } else {
-            Err(WebDriverErrorResponse::unknown_error(
-                result.error.as_deref().unwrap_or("Screenshot failed"),
-            ))
         }
     }
 
+    async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        self.snapshot(None).await
+    }
+
     async fn take_element_screenshot(
         &self,
         js_var: &str,
     ) -> Result<String, WebDriverErrorResponse> {
-        // Scroll element into view first
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
-                return true;
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
+        let clip = self.get_element_screenshot_clip(js_var).await?;
+        self.snapshot(Some(clip)).await
+    }
 
-        // Take full screenshot (element clipping can be added later)
-        self.take_screenshot().await
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        // A snapshot rect beyond the visible bounds makes WebKit render the rest of the page
+        let clip = self.get_full_page_clip().await?;
+        self.snapshot(Some(clip)).await
     }
 
     async fn print_page(&self, options: PrintOptions) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        if options
+            .page_ranges
+            .as_ref()
+            .is_some_and(|ranges| !ranges.is_empty())
+        {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "Printing page ranges is not supported on iOS",
+            ));
+        }
+
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let result: JsResult = webdriver
             .0
@@ -215,40 +266,31 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
         }
     }
 
-    // Override pointer dispatch to use touch events on iOS
+    // All pointer input is touch input on iOS
     async fn dispatch_pointer_event(
         &self,
         event_type: PointerEventType,
         x: i32,
         y: i32,
         _button: u32,
+        pointer: &PointerInput,
     ) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
-
-        let touch_type = match event_type {
-            PointerEventType::Down => "down",
-            PointerEventType::Up => "up",
-            PointerEventType::Move => "move",
-        };
-
-        let args = TouchArgs {
-            r#type: touch_type.to_string(),
-            x,
-            y,
-        };
-
-        let _result: Value = webdriver
-            .0
-            .run_mobile_plugin_async("dispatchTouch", args)
-            .await
-            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        self.dispatch_touch_event(event_type, x, y, pointer).await
+    }
 
+    // Touch input has no hover state
+    async fn update_hover(
+        &self,
+        _source_id: &str,
+        _x: i32,
+        _y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
         Ok(())
     }
 
     // Alert handling via plugin
     async fn get_alert_text(&self) -> Result<String, WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let result: AlertResult = webdriver
             .0
@@ -268,7 +310,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
     }
 
     async fn accept_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -286,7 +328,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
     }
 
     async fn dismiss_alert(&self) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -304,7 +346,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
     }
 
     async fn send_alert_text(&self, text: &str) -> Result<(), WebDriverErrorResponse> {
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         let _result: Value = webdriver
             .0
@@ -332,7 +374,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
     }
 
     // Cookies: Use default implementation from PlatformExecutor trait
-    // (Tauri's window().cookies() APIs work on iOS)
+    // (Tauri's webview().cookies() APIs work on iOS)
 
     // =========================================================================
     // Window Management
@@ -340,7 +382,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for IOSExecutor<R> {
 
     async fn get_window_rect(&self) -> Result<WindowRect, WebDriverErrorResponse> {
         // Get viewport size from Swift plugin
-        let webdriver = self.window.app_handle().state::<Webdriver<R>>();
+        let webdriver = self.webview.app_handle().state::<Webdriver<R>>();
 
         webdriver
             .0
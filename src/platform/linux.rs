@@ -1,55 +1,454 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
 use glib::MainContext;
+use gtk::gdk;
+use gtk::gdk::prelude::{SeatExt, WindowExtManual};
+use gtk::glib::translate::{ToGlibPtr, ToGlibPtrMut};
+use gtk::prelude::WidgetExt;
 use javascriptcore::ValueExt;
 use serde_json::Value;
-use tauri::{Manager, Runtime, WebviewWindow};
+use tauri::{Manager, Runtime, Webview};
 use tokio::sync::oneshot;
 use webkit2gtk::{
-    PrintOperationExt, ScriptDialogType, SnapshotOptions, SnapshotRegion, WebViewExt,
+    PrintOperationExt, ScriptDialogType, SettingsExt, SnapshotOptions, SnapshotRegion,
+    UserContentInjectedFrames, UserContentManagerExt, UserScript, UserScriptInjectionTime,
+    WebViewExt,
 };
 
-use crate::platform::alert_state::{AlertStateManager, AlertType, PendingAlert};
-use crate::platform::{wrap_script_for_frame_context, FrameId, PlatformExecutor, PrintOptions};
+use crate::platform::async_script::{AsyncScriptState, HANDLER_NAME};
+use crate::platform::{
+    extract_script_outcome, new_realm_script, wrap_script_for_frame_context, FrameContext,
+    ModifierState, PlatformExecutor, PointerEventType, PointerInput, PrintOptions, ScreenshotClip,
+    DESERIALIZE_ARG_JS, SERIALIZE_RESULT_JS,
+};
 use crate::server::response::WebDriverErrorResponse;
+use crate::webdriver::alert::{AlertStateManager, AlertType, PendingAlert};
+use crate::webdriver::keys::normalized_key;
+use crate::webdriver::print::parse_page_range;
 use crate::webdriver::Timeouts;
 
+thread_local! {
+    /// Preload scripts by id. `UserScript` isn't `Send`, so this is only touched from
+    /// `with_webview` closures on the GTK main thread.
+    static PRELOAD_SCRIPTS: RefCell<HashMap<String, UserScript>> = RefCell::new(HashMap::new());
+}
+
 /// Linux `WebKitGTK` executor
 #[derive(Clone)]
 pub struct LinuxExecutor<R: Runtime> {
-    window: WebviewWindow<R>,
+    webview: Webview<R>,
     timeouts: Timeouts,
-    frame_context: Vec<FrameId>,
+    frame_context: FrameContext,
 }
 
 impl<R: Runtime> LinuxExecutor<R> {
-    pub fn new(window: WebviewWindow<R>, timeouts: Timeouts, frame_context: Vec<FrameId>) -> Self {
+    pub fn new(webview: Webview<R>, timeouts: Timeouts, frame_context: FrameContext) -> Self {
         Self {
-            window,
+            webview,
             timeouts,
             frame_context,
         }
     }
+
+    /// Take a snapshot of `region` of the webview content, optionally cropped to `clip`
+    async fn snapshot(
+        &self,
+        region: SnapshotRegion,
+        clip: Option<ScreenshotClip>,
+    ) -> Result<String, WebDriverErrorResponse> {
+        // Use WebKitGTK's native snapshot API
+        let (tx, rx) = oneshot::channel();
+
+        let result = self.webview.with_webview(move |webview| {
+            let webview = webview.inner().clone();
+            let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+
+            // Use glib main context to spawn the async future
+            let ctx = MainContext::default();
+            ctx.spawn_local(async move {
+                let result = webview.snapshot_future(region, SnapshotOptions::NONE).await;
+
+                let response: Result<String, String> = result
+                    .map_err(|e| e.to_string())
+                    .and_then(|surface| {
+                        gtk::cairo::ImageSurface::try_from(surface)
+                            .map_err(|e| format!("Failed to downcast to ImageSurface: {e:?}"))
+                    })
+                    .and_then(|image_surface| match clip {
+                        Some(clip) => crop_surface(&image_surface, clip),
+                        None => Ok(image_surface),
+                    })
+                    .and_then(|image_surface| {
+                        let mut png_data: Vec<u8> = Vec::new();
+                        image_surface
+                            .write_to_png(&mut png_data)
+                            .map(|()| BASE64_STANDARD.encode(&png_data))
+                            .map_err(|e| format!("Failed to write PNG: {e}"))
+                    });
+
+                if let Ok(mut guard) = tx.lock() {
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(response);
+                    }
+                }
+            });
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(base64))) => {
+                if base64.is_empty() {
+                    Err(WebDriverErrorResponse::unknown_error(
+                        "Screenshot returned empty data",
+                    ))
+                } else {
+                    Ok(base64)
+                }
+            }
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&error)),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    /// Deliver a native input event to the webview widget on the GTK main thread
+    async fn send_native_input(&self, input: NativeInput) -> Result<(), WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
+
+        let result = self.webview.with_webview(move |webview| {
+            let _ = tx.send(inject_native_input(&webview.inner(), &input));
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        match rx.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(error)) => Err(WebDriverErrorResponse::unknown_error(&error)),
+            Err(_) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+        }
+    }
+}
+
+/// Convert a `JavaScriptCore` value to JSON, falling back to its string form
+fn js_value_to_json(js_value: &javascriptcore::Value) -> Value {
+    match js_value.to_json(0) {
+        Some(json_str) => serde_json::from_str::<Value>(json_str.as_str())
+            .unwrap_or_else(|_| Value::String(json_str.to_string())),
+        None => Value::Null,
+    }
+}
+
+/// Crop a snapshot to `clip`, scaling CSS pixels to the snapshot's device pixels
+#[allow(clippy::cast_possible_truncation)]
+fn crop_surface(
+    surface: &gtk::cairo::ImageSurface,
+    clip: ScreenshotClip,
+) -> Result<gtk::cairo::ImageSurface, String> {
+    let scale = if clip.viewport_width > 0.0 {
+        f64::from(surface.width()) / clip.viewport_width
+    } else {
+        1.0
+    };
+    let width = (clip.width * scale).round().max(1.0) as i32;
+    let height = (clip.height * scale).round().max(1.0) as i32;
+
+    let cropped = gtk::cairo::ImageSurface::create(gtk::cairo::Format::ARgb32, width, height)
+        .map_err(|e| format!("Failed to create surface: {e}"))?;
+    let context = gtk::cairo::Context::new(&cropped)
+        .map_err(|e| format!("Failed to create cairo context: {e}"))?;
+    context
+        .set_source_surface(surface, -clip.x * scale, -clip.y * scale)
+        .and_then(|()| context.paint())
+        .map_err(|e| format!("Failed to crop snapshot: {e}"))?;
+    drop(context);
+    Ok(cropped)
+}
+
+// =============================================================================
+// Native Input
+// =============================================================================
+
+/// Pixels `WebKitGTK` scrolls per unit of smooth scroll delta
+const PIXELS_PER_SCROLL_STEP: f64 = 40.0;
+
+/// Input event to synthesize at the GDK level
+enum NativeInput {
+    Pointer {
+        event_type: PointerEventType,
+        x: i32,
+        y: i32,
+        button: u32,
+        state: gdk::ModifierType,
+    },
+    Key {
+        keyval: u32,
+        is_down: bool,
+        state: gdk::ModifierType,
+    },
+    Scroll {
+        x: i32,
+        y: i32,
+        delta_x: i32,
+        delta_y: i32,
+    },
+}
+
+/// GDK modifier mask for held `WebDriver` modifier keys
+fn gdk_modifiers(modifiers: ModifierState) -> gdk::ModifierType {
+    let mut state = gdk::ModifierType::empty();
+    if modifiers.shift {
+        state |= gdk::ModifierType::SHIFT_MASK;
+    }
+    if modifiers.ctrl {
+        state |= gdk::ModifierType::CONTROL_MASK;
+    }
+    if modifiers.alt {
+        state |= gdk::ModifierType::MOD1_MASK;
+    }
+    if modifiers.meta {
+        state |= gdk::ModifierType::SUPER_MASK | gdk::ModifierType::META_MASK;
+    }
+    state
+}
+
+/// GDK button mask for held `WebDriver` buttons, so moves in between become drags
+fn gdk_buttons(buttons: u32) -> gdk::ModifierType {
+    // WebDriver buttons are 0-based, GDK's button masks start at button 1
+    let shift = gdk::ModifierType::BUTTON1_MASK.bits().trailing_zeros();
+    gdk::ModifierType::from_bits_truncate((buttons & 0b1_1111) << shift)
+}
+
+/// X keysym for a `WebDriver` key, either a normalized special key or a character
+fn gdk_keyval(key: &str) -> Option<u32> {
+    let mut chars = key.chars();
+    let ch = chars.next()?;
+    let dom_key = normalized_key(ch).unwrap_or(key);
+    let name = match dom_key {
+        "Backspace" => "BackSpace",
+        "Enter" => "Return",
+        " " => "space",
+        "ArrowLeft" => "Left",
+        "ArrowUp" => "Up",
+        "ArrowRight" => "Right",
+        "ArrowDown" => "Down",
+        "PageUp" => "Page_Up",
+        "PageDown" => "Page_Down",
+        "Shift" => "Shift_L",
+        "Control" => "Control_L",
+        "Alt" => "Alt_L",
+        "Meta" => "Super_L",
+        "ZenkakuHankaku" => "Zenkaku_Hankaku",
+        "Tab" | "Escape" | "Delete" | "Insert" | "Home" | "End" | "Pause" | "Help" | "Clear"
+        | "Cancel" | "F1" | "F2" | "F3" | "F4" | "F5" | "F6" | "F7" | "F8" | "F9" | "F10"
+        | "F11" | "F12" => dom_key,
+        _ => {
+            let mut dom_chars = dom_key.chars();
+            return match (dom_chars.next(), dom_chars.next()) {
+                (Some(c), None) => Some(*gdk::keys::Key::from_unicode(c)),
+                _ => None,
+            };
+        }
+    };
+    Some(*gdk::keys::Key::from_name(name))
+}
+
+/// Fill the type-specific fields of a freshly created GDK event. Viewport points are
+/// scaled by the zoom level and shifted by `offset` into the GDK window.
+#[allow(clippy::cast_possible_truncation)]
+fn fill_native_event(
+    event: &mut gdk::Event,
+    window: &gdk::Window,
+    input: &NativeInput,
+    (offset_x, offset_y): (i32, i32),
+    zoom: f64,
+) {
+    let to_window = |x: i32, y: i32| {
+        (
+            f64::from(offset_x) + f64::from(x) * zoom,
+            f64::from(offset_y) + f64::from(y) * zoom,
+        )
+    };
+    let to_root = |x: f64, y: f64| {
+        let (root_x, root_y) = window.root_coords(x as i32, y as i32);
+        (f64::from(root_x), f64::from(root_y))
+    };
+    let time = gtk::current_event_time();
+
+    // SAFETY: the event was created with the type whose union member is written, and
+    // it takes over the window reference, which gdk_event_free releases
+    unsafe {
+        let raw: *mut gdk::ffi::GdkEvent = event.to_glib_none_mut().0;
+        match input {
+            NativeInput::Pointer {
+                event_type,
+                x,
+                y,
+                button,
+                state,
+            } => {
+                let (window_x, window_y) = to_window(*x, *y);
+                let (root_x, root_y) = to_root(window_x, window_y);
+                if let PointerEventType::Move = event_type {
+                    let motion = &mut (*raw).motion;
+                    motion.window = window.to_glib_full();
+                    motion.time = time;
+                    motion.x = window_x;
+                    motion.y = window_y;
+                    motion.x_root = root_x;
+                    motion.y_root = root_y;
+                    motion.state = state.bits();
+                } else {
+                    // WebDriver buttons are 0-based, GDK buttons 1-based
+                    let gdk_button = button + 1;
+                    let mut state = *state;
+                    if let PointerEventType::Up = event_type {
+                        // Releases report the button that was held
+                        state |= gdk::ModifierType::from_bits_truncate(
+                            gdk::ModifierType::BUTTON1_MASK.bits() << button,
+                        );
+                    }
+                    let event_button = &mut (*raw).button;
+                    event_button.window = window.to_glib_full();
+                    event_button.time = time;
+                    event_button.x = window_x;
+                    event_button.y = window_y;
+                    event_button.x_root = root_x;
+                    event_button.y_root = root_y;
+                    event_button.button = gdk_button;
+                    event_button.state = state.bits();
+                }
+            }
+            NativeInput::Key { keyval, state, .. } => {
+                let keycode = gdk::Keymap::for_display(&window.display())
+                    .and_then(|keymap| {
+                        keymap
+                            .entries_for_keyval(*keyval)
+                            .first()
+                            .map(gdk::KeymapKey::keycode)
+                    })
+                    .unwrap_or(0);
+                let key = &mut (*raw).key;
+                key.window = window.to_glib_full();
+                key.time = time;
+                key.state = state.bits();
+                key.keyval = *keyval;
+                key.hardware_keycode = u16::try_from(keycode).unwrap_or(0);
+            }
+            NativeInput::Scroll {
+                x,
+                y,
+                delta_x,
+                delta_y,
+            } => {
+                let (window_x, window_y) = to_window(*x, *y);
+                let (root_x, root_y) = to_root(window_x, window_y);
+                let scroll = &mut (*raw).scroll;
+                scroll.window = window.to_glib_full();
+                scroll.time = time;
+                scroll.x = window_x;
+                scroll.y = window_y;
+                scroll.x_root = root_x;
+                scroll.y_root = root_y;
+                scroll.direction = gdk::ffi::GDK_SCROLL_SMOOTH;
+                scroll.delta_x = f64::from(*delta_x) / PIXELS_PER_SCROLL_STEP;
+                scroll.delta_y = f64::from(*delta_y) / PIXELS_PER_SCROLL_STEP;
+            }
+        }
+    }
+}
+
+/// Synthesize a GDK event and run it through GTK's event dispatch, as if it came from
+/// the display server. `WebKit` handles it like real input (trusted events, focus, native
+/// scrolling), on X11 and Wayland alike and without an input device.
+fn inject_native_input(webview: &webkit2gtk::WebView, input: &NativeInput) -> Result<(), String> {
+    let window = webview.window().ok_or("Webview is not realized")?;
+    let seat = window
+        .display()
+        .default_seat()
+        .ok_or("No input seat available")?;
+
+    // Widgets without their own GDK window are positioned inside their parent's
+    let (offset_x, offset_y) = if webview.has_window() {
+        (0, 0)
+    } else {
+        let allocation = webview.allocation();
+        (allocation.x(), allocation.y())
+    };
+    let (mut event, device) = match input {
+        NativeInput::Pointer { event_type, .. } => {
+            let gdk_type = match event_type {
+                PointerEventType::Down => gdk::EventType::ButtonPress,
+                PointerEventType::Up => gdk::EventType::ButtonRelease,
+                PointerEventType::Move => gdk::EventType::MotionNotify,
+            };
+            (gdk::Event::new(gdk_type), seat.pointer())
+        }
+        NativeInput::Key { is_down, .. } => {
+            let gdk_type = if *is_down {
+                gdk::EventType::KeyPress
+            } else {
+                gdk::EventType::KeyRelease
+            };
+            (gdk::Event::new(gdk_type), seat.keyboard())
+        }
+        NativeInput::Scroll { .. } => (gdk::Event::new(gdk::EventType::Scroll), seat.pointer()),
+    };
+
+    fill_native_event(
+        &mut event,
+        &window,
+        input,
+        (offset_x, offset_y),
+        webview.zoom_level(),
+    );
+    event.set_device(device.as_ref());
+
+    gtk::main_do_event(&mut event);
+    Ok(())
 }
 
 /// Register `WebKitGTK` handlers at webview creation time.
 /// This is called from the plugin's `on_webview_ready` hook to ensure
-/// the script dialog handler is registered before any navigation completes.
+/// the script dialog and message handlers are registered before any navigation completes.
 pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
-    use crate::platform::alert_state::AlertResponse;
+    use crate::webdriver::alert::AlertResponse;
     use webkit2gtk::WebViewExt as _;
 
-    // Get per-window alert state from the manager
+    // Fresh per-window alert state, so a recreated window doesn't inherit a stale prompt
     let manager = webview.app_handle().state::<AlertStateManager>();
-    let alert_state = manager.get_or_create(webview.label());
+    let alert_state = manager.reset(webview.label());
+    let app = webview.app_handle().clone();
 
     let _ = webview.with_webview(move |webview| {
         let webview = webview.inner().clone();
         let alert_state = alert_state.clone();
 
+        // Native message handler used by execute_async_script
+        if let Some(manager) = webview.user_content_manager() {
+            manager.connect_script_message_received(Some(HANDLER_NAME), move |_, result| {
+                let Some(message) = result.js_value().map(|value| value.to_str()) else {
+                    return;
+                };
+                if let Ok(message) = serde_json::from_str::<Value>(message.as_str()) {
+                    app.state::<AsyncScriptState>().complete_message(&message);
+                }
+            });
+            if !manager.register_script_message_handler(HANDLER_NAME) {
+                tracing::error!("Failed to register script message handler");
+            }
+        }
+
         // Connect to the script-dialog signal to intercept JS dialogs
         webview.connect_script_dialog(move |_webview, dialog| {
             let dialog_type = dialog.dialog_type();
@@ -79,7 +478,7 @@ pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
 
             // Create channel for WebDriver response
             let (tx, rx) = std::sync::mpsc::channel::<AlertResponse>();
-            alert_state.set_pending(PendingAlert {
+            let alert_id = alert_state.set_pending(PendingAlert {
                 message: message.clone(),
                 default_text: default_text.clone(),
                 alert_type,
@@ -87,31 +486,20 @@ pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
             });
 
             // Wait for WebDriver response with timeout
-            let timeout = std::time::Duration::from_secs(30);
-            let response = rx.recv_timeout(timeout);
-
-            match response {
-                Ok(AlertResponse {
-                    accepted,
-                    prompt_text,
-                }) => {
-                    if alert_type == AlertType::Confirm {
-                        dialog.confirm_set_confirmed(accepted);
-                    } else if alert_type == AlertType::Prompt && accepted {
-                        // Only set text if accepted - when dismissed, not calling
-                        // prompt_set_text() causes JavaScript to receive null
-                        let text = prompt_text.or(default_text).unwrap_or_default();
-                        dialog.prompt_set_text(&text);
-                    }
-                    // For Alert type, nothing special to set
-                }
-                Err(_) => {
-                    // Timeout - auto-accept
-                    if alert_type == AlertType::Confirm {
-                        dialog.confirm_set_confirmed(true);
-                    }
-                }
+            let AlertResponse {
+                accepted,
+                prompt_text,
+            } = alert_state.wait_for_response(alert_id, &rx);
+
+            if alert_type == AlertType::Confirm {
+                dialog.confirm_set_confirmed(accepted);
+            } else if alert_type == AlertType::Prompt && accepted {
+                // Only set text if accepted - when dismissed, not calling
+                // prompt_set_text() causes JavaScript to receive null
+                let text = prompt_text.or(default_text).unwrap_or_default();
+                dialog.prompt_set_text(&text);
             }
+            // For Alert type, nothing special to set
 
             // Return true to indicate we handled the dialog
             true
@@ -124,11 +512,11 @@ pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
 #[async_trait]
 impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
     // =========================================================================
-    // Window Access
+    // Webview Access
     // =========================================================================
 
-    fn window(&self) -> &WebviewWindow<R> {
-        &self.window
+    fn webview(&self) -> &Webview<R> {
+        &self.webview
     }
 
     // =========================================================================
@@ -139,7 +527,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
         let (tx, rx) = oneshot::channel();
         let script_owned = wrap_script_for_frame_context(script, &self.frame_context);
 
-        let result = self.window.with_webview(move |webview| {
+        let result = self.webview.with_webview(move |webview| {
             let webview = webview.inner().clone();
             let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
 
@@ -149,19 +537,8 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
                 let result = webview
                     .evaluate_javascript_future(&script_owned, None, None)
                     .await;
-                let response: Result<Value, String> = match result {
-                    Ok(js_value) => {
-                        if let Some(json_str) = js_value.to_json(0) {
-                            match serde_json::from_str::<Value>(json_str.as_str()) {
-                                Ok(value) => Ok(value),
-                                Err(_) => Ok(Value::String(json_str.to_string())),
-                            }
-                        } else {
-                            Ok(Value::Null)
-                        }
-                    }
-                    Err(e) => Err(e.to_string()),
-                };
+                let response = result.map(|js_value| js_value_to_json(&js_value));
+                let response = response.map_err(|e| e.to_string());
 
                 if let Ok(mut guard) = tx.lock() {
                     if let Some(tx) = guard.take() {
@@ -172,10 +549,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
         });
 
         if let Err(e) = result {
-            return Err(WebDriverErrorResponse::javascript_error(
-                &e.to_string(),
-                None,
-            ));
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
@@ -184,7 +558,7 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
                 "success": true,
                 "value": value
             })),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
         }
@@ -195,41 +569,44 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
     // =========================================================================
 
     async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
-        // Use WebKitGTK's native snapshot API
-        let (tx, rx) = oneshot::channel();
+        self.snapshot(SnapshotRegion::Visible, None).await
+    }
 
-        let result = self.window.with_webview(move |webview| {
-            let webview = webview.inner().clone();
-            let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+    async fn take_element_screenshot(
+        &self,
+        js_var: &str,
+    ) -> Result<String, WebDriverErrorResponse> {
+        let clip = self.get_element_screenshot_clip(js_var).await?;
+        self.snapshot(SnapshotRegion::Visible, Some(clip)).await
+    }
 
-            // Use glib main context to spawn the async future
-            let ctx = MainContext::default();
-            ctx.spawn_local(async move {
-                // Take snapshot of visible content
-                let result = webview
-                    .snapshot_future(SnapshotRegion::Visible, SnapshotOptions::NONE)
-                    .await;
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        self.snapshot(SnapshotRegion::FullDocument, None).await
+    }
 
-                let response: Result<String, String> = match result {
-                    Ok(surface) => {
-                        let mut png_data: Vec<u8> = Vec::new();
-                        match gtk::cairo::ImageSurface::try_from(surface) {
-                            Ok(image_surface) => match image_surface.write_to_png(&mut png_data) {
-                                Ok(()) => Ok(BASE64_STANDARD.encode(&png_data)),
-                                Err(e) => Err(format!("Failed to write PNG: {e}")),
-                            },
-                            Err(e) => Err(format!("Failed to downcast to ImageSurface: {e:?}")),
-                        }
-                    }
-                    Err(e) => Err(e.to_string()),
-                };
+    async fn take_window_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
 
-                if let Ok(mut guard) = tx.lock() {
-                    if let Some(tx) = guard.take() {
-                        let _ = tx.send(response);
-                    }
-                }
-            });
+        // The toplevel GDK window covers client-side decorations (GTK header bars,
+        // custom titlebars) and menus, unlike the WebKit snapshot API
+        let result = self.webview.with_webview(move |webview| {
+            let response = webview
+                .inner()
+                .toplevel()
+                .and_then(|toplevel| toplevel.window())
+                .ok_or_else(|| "Window is not realized".to_string())
+                .and_then(|gdk_window| {
+                    gdk_window
+                        .pixbuf(0, 0, gdk_window.width(), gdk_window.height())
+                        .ok_or_else(|| "Failed to capture window contents".to_string())
+                })
+                .and_then(|pixbuf| {
+                    pixbuf
+                        .save_to_bufferv("png", &[])
+                        .map(|png_data| BASE64_STANDARD.encode(&png_data))
+                        .map_err(|e| format!("Failed to write PNG: {e}"))
+                });
+            let _ = tx.send(response);
         });
 
         if let Err(e) = result {
@@ -238,44 +615,73 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
         match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(Ok(base64))) => {
-                if base64.is_empty() {
-                    Err(WebDriverErrorResponse::unknown_error(
-                        "Screenshot returned empty data",
-                    ))
-                } else {
-                    Ok(base64)
-                }
-            }
+            Ok(Ok(Ok(base64))) => Ok(base64),
             Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&error)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
         }
     }
 
-    async fn take_element_screenshot(
+    // =========================================================================
+    // Native Input
+    // =========================================================================
+
+    async fn dispatch_native_pointer_event(
         &self,
-        js_var: &str,
-    ) -> Result<String, WebDriverErrorResponse> {
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
-                return true;
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
+        event_type: PointerEventType,
+        x: i32,
+        y: i32,
+        button: u32,
+        pointer: &PointerInput,
+    ) -> Result<(), WebDriverErrorResponse> {
+        self.send_native_input(NativeInput::Pointer {
+            event_type,
+            x,
+            y,
+            button,
+            state: gdk_modifiers(pointer.modifiers) | gdk_buttons(pointer.buttons),
+        })
+        .await
+    }
+
+    async fn dispatch_native_key_event(
+        &self,
+        key: &str,
+        is_down: bool,
+        modifiers: &ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let keyval = gdk_keyval(key).ok_or_else(|| {
+            WebDriverErrorResponse::invalid_argument(&format!("Unsupported key: {key:?}"))
+        })?;
+        self.send_native_input(NativeInput::Key {
+            keyval,
+            is_down,
+            state: gdk_modifiers(*modifiers),
+        })
+        .await
+    }
 
-        self.take_screenshot().await
+    async fn dispatch_native_scroll_event(
+        &self,
+        x: i32,
+        y: i32,
+        delta_x: i32,
+        delta_y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        self.send_native_input(NativeInput::Scroll {
+            x,
+            y,
+            delta_x,
+            delta_y,
+        })
+        .await
     }
 
     // =========================================================================
     // Print
     // =========================================================================
 
+    #[allow(clippy::too_many_lines)]
     async fn print_page(&self, options: PrintOptions) -> Result<String, WebDriverErrorResponse> {
         let (tx, rx) = oneshot::channel::<Result<(), String>>();
 
@@ -294,10 +700,34 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
         let margin_bottom = options.margin_bottom;
         let margin_left = options.margin_left;
         let margin_right = options.margin_right;
+        let scale = options.scale.unwrap_or(1.0);
+        let background = options.background.unwrap_or(false);
+        // GTK ranges are 0-based and closed; WebKit clamps the open end to the last page
+        let page_ranges: Option<Vec<gtk::PageRange>> = options.page_ranges.map(|ranges| {
+            ranges
+                .iter()
+                .filter_map(|range| parse_page_range(range).ok())
+                .map(|range| {
+                    let start = i32::try_from(range.start).unwrap_or(i32::MAX) - 1;
+                    let end = range
+                        .end
+                        .map_or(i32::MAX, |end| i32::try_from(end).unwrap_or(i32::MAX) - 1);
+                    gtk::PageRange::new(start, end)
+                })
+                .collect()
+        });
 
-        let result = self.window.with_webview(move |webview| {
+        let result = self.webview.with_webview(move |webview| {
             let webview = webview.inner().clone();
 
+            // Backgrounds are a WebKit setting rather than a print setting, so the
+            // app's own value is restored once the operation is done
+            let restore_background = WebViewExt::settings(&webview).map(|webkit_settings| {
+                let previous = webkit_settings.is_print_backgrounds();
+                webkit_settings.set_print_backgrounds(background);
+                (webkit_settings, previous)
+            });
+
             // Create print operation
             let print_op = webkit2gtk::PrintOperation::new(&webview);
 
@@ -339,12 +769,21 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
                 Some(&format!("file://{}", pdf_path_clone.display())),
             );
             settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+            // GTK scale is a percentage
+            settings.set_scale(scale * 100.0);
+            if let Some(ref ranges) = page_ranges {
+                settings.set_print_pages(gtk::PrintPages::Ranges);
+                settings.set_page_ranges(ranges);
+            }
 
             print_op.set_print_settings(&settings);
 
             // Connect to finished signal
             let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
             print_op.connect_finished(move |_op| {
+                if let Some((webkit_settings, previous)) = &restore_background {
+                    webkit_settings.set_print_backgrounds(*previous);
+                }
                 if let Ok(mut guard) = tx.lock() {
                     if let Some(tx) = guard.take() {
                         let _ = tx.send(Ok(()));
@@ -395,95 +834,153 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for LinuxExecutor<R> {
         let args_json = serde_json::to_string(args)
             .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
 
-        // Build wrapper that includes argument deserialization
-        // call_async_javascript_function handles Promises natively - we wrap the script in a Promise
-        // and provide __done via closure
+        let async_id = uuid::Uuid::new_v4().to_string();
+
+        // Get async state and register this operation
+        // (the native message handler is registered in `register_webview_handlers`)
+        let app = self.webview.app_handle().clone();
+        let async_state = app.state::<AsyncScriptState>();
+
+        let mut pending = async_state.register(async_id.clone());
+
+        // Build wrapper script posting to the native message handler. The wrapper runs
+        // in the selected frame via `evaluate_js` and returns immediately, so it doesn't
+        // depend on WebKitGTK awaiting a returned Promise.
         let wrapper = format!(
-            r"return new Promise((resolve, reject) => {{
-                var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-                function deserializeArg(arg) {{
-                    if (arg === null || arg === undefined) return arg;
-                    if (Array.isArray(arg)) return arg.map(deserializeArg);
-                    if (typeof arg === 'object') {{
-                        if (arg[ELEMENT_KEY]) {{
-                            var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                            if (!el) throw new Error('stale element reference');
-                            return el;
-                        }}
-                        var result = {{}};
-                        for (var key in arg) {{
-                            if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                        }}
-                        return result;
-                    }}
-                    return arg;
-                }}
-                var __done = function(result, error) {{
-                    if (error) {{
-                        reject(new Error(typeof error === 'string' ? error : String(error)));
-                    }} else {{
-                        resolve(result);
+            r"(function() {{
+                var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, window); }};
+                var __post = function(result, error) {{
+                    window.webkit.messageHandlers.{HANDLER_NAME}.postMessage(JSON.stringify({{
+                        handler: '{HANDLER_NAME}',
+                        id: '{async_id}',
+                        result: result,
+                        error: error
+                    }}));
+                }};
+                var __done = function(r) {{
+                    try {{
+                        __post(({SERIALIZE_RESULT_JS})(r, window), null);
+                    }} catch (e) {{
+                        __post(null, e.message || String(e));
                     }}
                 }};
-                var __args = {args_json}.map(deserializeArg);
-                __args.push(__done);
                 try {{
+                    var __args = {args_json}.map(deserializeArg);
+                    __args.push(__done);
                     (function() {{ {script} }}).apply(null, __args);
                 }} catch (e) {{
-                    reject(e);
+                    __post(null, e.message || String(e));
                 }}
-            }});"
+            }})()"
         );
 
+        // Execute the wrapper (returns immediately)
+        self.evaluate_js(&wrapper).await?;
+
+        // Wait for result with timeout
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, &mut pending.receiver).await {
+            Ok(Ok(Ok(value))) => Ok(value),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    async fn execute_script_in_new_realm(
+        &self,
+        script: &str,
+        args: &[Value],
+        is_async: bool,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        // WebKitGTK only runs scripts of other worlds in the main frame
+        if !self.frame_context.is_empty() {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "New realms are only supported in top-level browsing contexts on this platform",
+            ));
+        }
+        let body = format!("return {};", new_realm_script(script, args, is_async)?);
+        let world_name = format!("webdriver-{}", uuid::Uuid::new_v4());
         let (tx, rx) = oneshot::channel();
 
-        let result = self.window.with_webview(move |webview| {
+        let result = self.webview.with_webview(move |webview| {
             let webview = webview.inner().clone();
-            let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
-
-            // Use glib main context to spawn the async future
-            let ctx = MainContext::default();
-            ctx.spawn_local(async move {
-                // call_async_javascript_function_future handles Promises natively
+            MainContext::default().spawn_local(async move {
                 let result = webview
-                    .call_async_javascript_function_future(&wrapper, None, None, None)
+                    .call_async_javascript_function_future(&body, None, Some(&world_name), None)
                     .await;
-
-                let response: Result<Value, String> = match result {
-                    Ok(js_value) => {
-                        if let Some(json_str) = js_value.to_json(0) {
-                            match serde_json::from_str::<Value>(json_str.as_str()) {
-                                Ok(value) => Ok(value),
-                                Err(_) => Ok(Value::String(json_str.to_string())),
-                            }
-                        } else {
-                            Ok(Value::Null)
-                        }
-                    }
-                    Err(e) => Err(e.to_string()),
-                };
-
-                if let Ok(mut guard) = tx.lock() {
-                    if let Some(tx) = guard.take() {
-                        let _ = tx.send(response);
-                    }
-                }
+                let _ = tx.send(
+                    result
+                        .map(|js_value| js_value_to_json(&js_value))
+                        .map_err(|e| e.to_string()),
+                );
             });
         });
-
         if let Err(e) = result {
-            return Err(WebDriverErrorResponse::javascript_error(
-                &e.to_string(),
-                None,
-            ));
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
         match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(Ok(value))) => Ok(value),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
+            Ok(Ok(Ok(outcome))) => extract_script_outcome(&outcome),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
         }
     }
+
+    // =========================================================================
+    // Preload Scripts
+    // =========================================================================
+
+    async fn add_preload_script(&self, script: &str) -> Result<String, WebDriverErrorResponse> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let key = id.clone();
+        let source = script.to_string();
+        let (tx, rx) = oneshot::channel();
+
+        let result = self.webview.with_webview(move |webview| {
+            let added = webview.inner().user_content_manager().map(|manager| {
+                let script = UserScript::new(
+                    &source,
+                    UserContentInjectedFrames::AllFrames,
+                    UserScriptInjectionTime::Start,
+                    &[],
+                    &[],
+                );
+                manager.add_script(&script);
+                PRELOAD_SCRIPTS.with(|scripts| scripts.borrow_mut().insert(key, script));
+            });
+            let _ = tx.send(added.is_some());
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        match rx.await {
+            Ok(true) => Ok(id),
+            Ok(false) => Err(WebDriverErrorResponse::unknown_error(
+                "Webview has no user content manager",
+            )),
+            Err(_) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+        }
+    }
+
+    async fn remove_preload_script(&self, id: &str) -> Result<(), WebDriverErrorResponse> {
+        let key = id.to_string();
+
+        self.webview
+            .with_webview(move |webview| {
+                let Some(script) =
+                    PRELOAD_SCRIPTS.with(|scripts| scripts.borrow_mut().remove(&key))
+                else {
+                    return;
+                };
+                if let Some(manager) = webview.inner().user_content_manager() {
+                    manager.remove_script(&script);
+                }
+            })
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
 }
@@ -1,102 +1,208 @@
-use std::sync::Arc;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::{Arc, LazyLock, Mutex};
 
 use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
 use block2::{DynBlock, RcBlock};
-use objc2::rc::Retained;
-use objc2::runtime::{AnyObject, ProtocolObject};
-use objc2::{define_class, msg_send, DefinedClass, MainThreadMarker, MainThreadOnly};
-use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
-use objc2_foundation::{NSData, NSDictionary, NSError, NSObject, NSObjectProtocol, NSString};
+use objc2::encode::{Encoding, RefEncode};
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject, ProtocolObject};
+use objc2::{
+    define_class, msg_send, sel, AllocAnyThread, DefinedClass, MainThreadMarker, MainThreadOnly,
+};
+use objc2_app_kit::{
+    NSBitmapImageFileType, NSBitmapImageRep, NSImage, NSPaperOrientation, NSPrintAllPages,
+    NSPrintFirstPage, NSPrintInfo, NSPrintJobSavingURL, NSPrintLastPage, NSPrintOperation,
+    NSPrintSaveJob, NSPrintingPaginationMode,
+};
+use objc2_foundation::{
+    NSArray, NSData, NSDictionary, NSError, NSNumber, NSObject, NSObjectProtocol, NSPoint, NSRect,
+    NSSize, NSString, NSURL,
+};
 use objc2_web_kit::{
-    WKContentWorld, WKFrameInfo, WKPDFConfiguration, WKSnapshotConfiguration, WKUIDelegate,
-    WKWebView,
+    WKContentWorld, WKFrameInfo, WKPreferences, WKScriptMessage, WKScriptMessageHandler,
+    WKSnapshotConfiguration, WKUIDelegate, WKUserContentController, WKUserScript,
+    WKUserScriptInjectionTime, WKWebView,
 };
 use serde_json::Value;
-use tauri::{Manager, Runtime, WebviewWindow};
+use tauri::{Manager, Runtime, Webview};
 use tokio::sync::oneshot;
 
-use crate::platform::alert_state::{AlertState, AlertStateManager, AlertType, PendingAlert};
-use crate::platform::{wrap_script_for_frame_context, FrameId, PlatformExecutor, PrintOptions};
+use crate::platform::{
+    extract_script_outcome, frame_function_script, new_realm_script, wrap_script_for_frame_context,
+    FrameContext, FrameId, ModifierState, PlatformExecutor, PointerEventType, PointerInput,
+    PrintOptions, ScreenshotClip, DESERIALIZE_ARG_JS, ELEMENT_REFS_JS, SERIALIZE_RESULT_JS,
+};
 use crate::server::response::WebDriverErrorResponse;
+use crate::webdriver::alert::{AlertState, AlertStateManager, AlertType, PendingAlert};
+use crate::webdriver::keys::normalized_key;
+use crate::webdriver::print::parse_page_range;
 use crate::webdriver::Timeouts;
 
 /// Key for associating the UI delegate with the webview
 static DELEGATE_KEY: u8 = 0;
 
+/// Script message handler used to identify frames for frame-scoped execution
+const FRAME_HANDLER_NAME: &str = "webdriver_frame";
+
+/// Injected into every frame: replies to a probe from the parent frame through the
+/// native message handler, which hands us the frame's `WKFrameInfo`
+const FRAME_PROBE_SCRIPT: &str = r"window.addEventListener('message', function(event) {
+    var data = event.data;
+    if (data && data.__webdriverFrameProbe && event.source === window.parent) {
+        window.webkit.messageHandlers.webdriver_frame.postMessage(data.__webdriverFrameProbe);
+    }
+});";
+
+/// How long to wait for a frame to answer a probe. Frames that never loaded the probe
+/// script don't answer at all.
+const FRAME_PROBE_TIMEOUT_MS: u64 = 500;
+
+/// Probes waiting for a frame to reply, keyed by nonce
+static FRAME_PROBES: LazyLock<Mutex<HashMap<String, oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    /// Frames that replied to a probe, keyed by nonce. Only touched on the main thread.
+    static FRAME_INFOS: RefCell<HashMap<String, Retained<WKFrameInfo>>> =
+        RefCell::new(HashMap::new());
+
+    /// Preload scripts by id. Only touched on the main thread.
+    static PRELOAD_SCRIPTS: RefCell<HashMap<String, Retained<WKUserScript>>> =
+        RefCell::new(HashMap::new());
+
+    /// Delegates of print operations in progress, which don't retain them. Only touched
+    /// on the main thread.
+    static PRINT_DELEGATES: RefCell<HashMap<String, Retained<WebDriverPrintDelegate>>> =
+        RefCell::new(HashMap::new());
+}
+
 /// macOS `WebView` executor using `WKWebView` native APIs
 #[derive(Clone)]
 pub struct MacOSExecutor<R: Runtime> {
-    window: WebviewWindow<R>,
+    webview: Webview<R>,
     timeouts: Timeouts,
-    frame_context: Vec<FrameId>,
+    frame_context: FrameContext,
 }
 
 impl<R: Runtime> MacOSExecutor<R> {
-    pub fn new(window: WebviewWindow<R>, timeouts: Timeouts, frame_context: Vec<FrameId>) -> Self {
+    pub fn new(webview: Webview<R>, timeouts: Timeouts, frame_context: FrameContext) -> Self {
         Self {
-            window,
+            webview,
             timeouts,
             frame_context,
         }
     }
-}
-
-/// Register `WKWebView` handlers at webview creation time.
-/// This is called from the plugin's `on_webview_ready` hook to ensure
-/// the UI delegate is registered before any navigation completes.
-pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
-    use objc2::ffi::{objc_setAssociatedObject, OBJC_ASSOCIATION_RETAIN_NONATOMIC};
 
-    // Get per-window alert state from the manager
-    let manager = webview.app_handle().state::<AlertStateManager>();
-    let alert_state = manager.get_or_create(webview.label());
-
-    let _ = webview.with_webview(move |webview| unsafe {
-        let wk_webview: &WKWebView = &*webview.inner().cast();
-
-        let delegate = WebDriverUIDelegate::new(alert_state);
-        let delegate_protocol: Retained<ProtocolObject<dyn WKUIDelegate>> =
-            ProtocolObject::from_retained(delegate);
-
-        let _: () = msg_send![wk_webview, setUIDelegate: &*delegate_protocol];
+    /// Resolve the selected frame, pushing the nonce of each level's `WKFrameInfo` onto
+    /// `chain` (empty for the main frame). Each level is probed from its parent via
+    /// `postMessage`, which works for cross-origin frames that can't be reached through
+    /// `contentWindow`. A same-origin frame that doesn't answer, such as an `about:blank`,
+    /// `srcdoc` or script-built one, ends the chain early: it and the levels below it are
+    /// left to `frame_tail`, which is entered through `contentWindow`.
+    async fn resolve_frame(&self, chain: &mut Vec<String>) -> Result<(), WebDriverErrorResponse> {
+        for frame_id in self.frame_context.iter() {
+            let owner = match frame_id {
+                FrameId::Index(index) => {
+                    format!("document.querySelectorAll('iframe, frame')[{index}]")
+                }
+                FrameId::Element(js_var) => format!("{ELEMENT_REFS_JS}.get('{js_var}')"),
+            };
 
-        // Associate delegate with webview - released when webview is deallocated
-        objc_setAssociatedObject(
-            std::ptr::from_ref::<WKWebView>(wk_webview)
-                .cast_mut()
-                .cast(),
-            std::ptr::addr_of!(DELEGATE_KEY).cast(),
-            Retained::into_raw(delegate_protocol).cast(),
-            OBJC_ASSOCIATION_RETAIN_NONATOMIC,
-        );
+            let nonce = uuid::Uuid::new_v4().simple().to_string();
+            let (tx, rx) = oneshot::channel();
+            if let Ok(mut probes) = FRAME_PROBES.lock() {
+                probes.insert(nonce.clone(), tx);
+            }
 
-        tracing::debug!("Registered UI delegate for webview");
-    });
-}
+            let script = format!(
+                r"(function() {{
+                    var frame = {owner};
+                    if (!frame || !frame.isConnected) throw new Error('__wd:no such frame');
+                    if (frame.tagName !== 'IFRAME' && frame.tagName !== 'FRAME') {{
+                        throw new Error('__wd:element is not a frame');
+                    }}
+                    if (!frame.contentWindow) throw new Error('__wd:no such frame');
+                    frame.contentWindow.postMessage({{ __webdriverFrameProbe: '{nonce}' }}, '*');
+                    return true;
+                }})()"
+            );
+            let parent = chain.last().map(String::as_str);
+            let probed = self.evaluate_in_frame(parent, &script).await;
+
+            let replied = match probed {
+                Ok(_) => {
+                    let timeout = std::time::Duration::from_millis(
+                        FRAME_PROBE_TIMEOUT_MS.min(self.timeouts.script_ms),
+                    );
+                    matches!(tokio::time::timeout(timeout, rx).await, Ok(Ok(())))
+                }
+                Err(e) => {
+                    forget_frame_probe(&nonce);
+                    return Err(e);
+                }
+            };
+            if !replied {
+                forget_frame_probe(&nonce);
+                let check = format!(
+                    r"(function() {{
+                        try {{
+                            return !!{owner}.contentWindow.document;
+                        }} catch (e) {{
+                            return false;
+                        }}
+                    }})()"
+                );
+                let same_origin = self.evaluate_in_frame(parent, &check).await?;
+                if same_origin.get("value") == Some(&Value::Bool(true)) {
+                    return Ok(());
+                }
+                return Err(WebDriverErrorResponse::no_such_frame());
+            }
 
-#[async_trait]
-impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
-    // =========================================================================
-    // Window Access
-    // =========================================================================
+            chain.push(nonce);
+        }
 
-    fn window(&self) -> &WebviewWindow<R> {
-        &self.window
+        Ok(())
     }
 
-    // =========================================================================
-    // Core JavaScript Execution
-    // =========================================================================
+    /// Levels of the selected frame below the ones `resolve_frame` put in `chain`, which
+    /// are entered through `contentWindow`. Only the whole chain keeps the session's
+    /// cached frame window.
+    fn frame_tail(&self, chain: &[String]) -> FrameContext {
+        if chain.is_empty() {
+            self.frame_context.clone()
+        } else {
+            FrameContext::from(self.frame_context[chain.len()..].to_vec())
+        }
+    }
 
-    async fn evaluate_js(&self, script: &str) -> Result<Value, WebDriverErrorResponse> {
+    /// Evaluate a script in a resolved frame (`None` for the main frame)
+    async fn evaluate_in_frame(
+        &self,
+        frame: Option<&str>,
+        script: &str,
+    ) -> Result<Value, WebDriverErrorResponse> {
         let (tx, rx) = oneshot::channel();
-        let script_owned = wrap_script_for_frame_context(script, &self.frame_context);
+        let script_owned = script.to_string();
+        let frame = frame.map(String::from);
 
-        let result = self.window.with_webview(move |webview| unsafe {
+        let result = self.webview.with_webview(move |webview| unsafe {
             let wk_webview: &WKWebView = &*webview.inner().cast();
             let ns_script = NSString::from_str(&script_owned);
+            let mtm = MainThreadMarker::new_unchecked();
+            let content_world = WKContentWorld::pageWorld(mtm);
+
+            let frame_info = frame
+                .as_ref()
+                .and_then(|nonce| FRAME_INFOS.with(|infos| infos.borrow().get(nonce).cloned()));
+            if frame.is_some() && frame_info.is_none() {
+                let _ = tx.send(Err("__wd:no such frame".to_string()));
+                return;
+            }
 
             let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
             let block = RcBlock::new(move |result: *mut AnyObject, error: *mut NSError| {
@@ -118,14 +224,16 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
                 }
             });
 
-            wk_webview.evaluateJavaScript_completionHandler(&ns_script, Some(&block));
+            wk_webview.evaluateJavaScript_inFrame_inContentWorld_completionHandler(
+                &ns_script,
+                frame_info.as_deref(),
+                &content_world,
+                Some(&block),
+            );
         });
 
         if let Err(e) = result {
-            return Err(WebDriverErrorResponse::javascript_error(
-                &e.to_string(),
-                None,
-            ));
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
@@ -134,76 +242,50 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
                 "success": true,
                 "value": value
             })),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
         }
     }
 
-    // =========================================================================
-    // Async Script Execution (using callAsyncJavaScript)
-    // =========================================================================
-
-    async fn execute_async_script(
+    /// Run `body` as an async function in the selected frame and await the promise it
+    /// returns. Runs in the page's world, or in the named isolated world.
+    async fn call_async_js(
         &self,
-        script: &str,
-        args: &[Value],
+        body: String,
+        world_name: Option<String>,
     ) -> Result<Value, WebDriverErrorResponse> {
-        let args_json = serde_json::to_string(args)
-            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
-
-        // Build wrapper that includes argument deserialization
-        // callAsyncJavaScript handles Promises natively - we wrap the script in a Promise
-        // and provide __done via closure
-        let wrapper = format!(
-            r"return new Promise((resolve, reject) => {{
-                var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-                function deserializeArg(arg) {{
-                    if (arg === null || arg === undefined) return arg;
-                    if (Array.isArray(arg)) return arg.map(deserializeArg);
-                    if (typeof arg === 'object') {{
-                        if (arg[ELEMENT_KEY]) {{
-                            var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                            if (!el) throw new Error('stale element reference');
-                            return el;
-                        }}
-                        var result = {{}};
-                        for (var key in arg) {{
-                            if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                        }}
-                        return result;
-                    }}
-                    return arg;
-                }}
-                var __done = function(result, error) {{
-                    if (error) {{
-                        reject(new Error(typeof error === 'string' ? error : String(error)));
-                    }} else {{
-                        resolve(result);
-                    }}
-                }};
-                var __args = {args_json}.map(deserializeArg);
-                __args.push(__done);
-                try {{
-                    (function() {{ {script} }}).apply(null, __args);
-                }} catch (e) {{
-                    reject(e);
-                }}
-            }});"
-        );
+        let mut chain = Vec::new();
+        if let Err(e) = self.resolve_frame(&mut chain).await {
+            self.release_frames(chain);
+            return Err(e);
+        }
+        let frame = chain.last().cloned();
+        let tail = self.frame_tail(&chain);
+        let body = if tail.is_empty() {
+            body
+        } else {
+            format!("return {}.call(null);", frame_function_script(&body, &tail))
+        };
 
         let (tx, rx) = oneshot::channel();
 
-        let result = self.window.with_webview(move |webview| unsafe {
+        let result = self.webview.with_webview(move |webview| unsafe {
             let wk_webview: &WKWebView = &*webview.inner().cast();
-            let ns_script = NSString::from_str(&wrapper);
+            let ns_script = NSString::from_str(&body);
             let mtm = MainThreadMarker::new_unchecked();
 
             // Empty dictionary for arguments (we pass args via JSON in the script)
             let empty_dict: Retained<NSDictionary<NSString, AnyObject>> = NSDictionary::new();
 
-            // Get the page content world
-            let content_world = WKContentWorld::pageWorld(mtm);
+            let content_world = match world_name {
+                Some(name) => WKContentWorld::worldWithName(&NSString::from_str(&name), mtm),
+                None => WKContentWorld::pageWorld(mtm),
+            };
+
+            let frame_info = frame
+                .as_ref()
+                .and_then(|nonce| FRAME_INFOS.with(|infos| infos.borrow().get(nonce).cloned()));
 
             let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
             let block = RcBlock::new(move |result: *mut AnyObject, error: *mut NSError| {
@@ -228,39 +310,61 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
             wk_webview.callAsyncJavaScript_arguments_inFrame_inContentWorld_completionHandler(
                 &ns_script,
                 Some(&empty_dict),
-                None,
+                frame_info.as_deref(),
                 &content_world,
                 Some(&block),
             );
         });
 
         if let Err(e) = result {
-            return Err(WebDriverErrorResponse::javascript_error(
-                &e.to_string(),
-                None,
-            ));
+            self.release_frames(chain);
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
-        match tokio::time::timeout(timeout, rx).await {
+        let result = match tokio::time::timeout(timeout, rx).await {
             Ok(Ok(Ok(value))) => Ok(value),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
-        }
+        };
+        self.release_frames(chain);
+        result
     }
 
-    // =========================================================================
-    // Screenshots
-    // =========================================================================
+    /// Drop the frame infos collected while resolving a frame chain
+    fn release_frames(&self, chain: Vec<String>) {
+        if chain.is_empty() {
+            return;
+        }
+        let _ = self.webview.run_on_main_thread(move || {
+            FRAME_INFOS.with(|infos| {
+                let mut infos = infos.borrow_mut();
+                for nonce in &chain {
+                    infos.remove(nonce);
+                }
+            });
+        });
+    }
 
-    async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+    /// Take a snapshot of the visible webview content, optionally limited to `clip`
+    async fn snapshot(
+        &self,
+        clip: Option<ScreenshotClip>,
+    ) -> Result<String, WebDriverErrorResponse> {
         let (tx, rx) = oneshot::channel();
 
-        let result = self.window.with_webview(move |webview| unsafe {
+        let result = self.webview.with_webview(move |webview| unsafe {
             let wk_webview: &WKWebView = &*webview.inner().cast();
             let mtm = MainThreadMarker::new_unchecked();
             let config = WKSnapshotConfiguration::new(mtm);
+            // The snapshot rect is in view coordinates, which match CSS pixels
+            if let Some(clip) = clip {
+                config.setRect(NSRect::new(
+                    NSPoint::new(clip.x, clip.y),
+                    NSSize::new(clip.width, clip.height),
+                ));
+            }
 
             let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
             let block = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
@@ -297,35 +401,249 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
             Err(_) => Err(WebDriverErrorResponse::script_timeout()),
         }
     }
+}
+
+/// Stop waiting for a frame probe that will never be answered
+fn forget_frame_probe(nonce: &str) {
+    if let Ok(mut probes) = FRAME_PROBES.lock() {
+        probes.remove(nonce);
+    }
+}
+
+/// Register `WKWebView` handlers at webview creation time.
+/// This is called from the plugin's `on_webview_ready` hook to ensure
+/// the UI delegate is registered before any navigation completes.
+pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
+    use objc2::ffi::{objc_setAssociatedObject, OBJC_ASSOCIATION_RETAIN_NONATOMIC};
+
+    // Fresh per-window alert state, so a recreated window doesn't inherit a stale prompt
+    let manager = webview.app_handle().state::<AlertStateManager>();
+    let alert_state = manager.reset(webview.label());
+
+    let _ = webview.with_webview(move |webview| unsafe {
+        let wk_webview: &WKWebView = &*webview.inner().cast();
+
+        let delegate = WebDriverUIDelegate::new(alert_state);
+        let delegate_protocol: Retained<ProtocolObject<dyn WKUIDelegate>> =
+            ProtocolObject::from_retained(delegate);
+
+        let _: () = msg_send![wk_webview, setUIDelegate: &*delegate_protocol];
+
+        // Associate delegate with webview - released when webview is deallocated
+        objc_setAssociatedObject(
+            std::ptr::from_ref::<WKWebView>(wk_webview)
+                .cast_mut()
+                .cast(),
+            std::ptr::addr_of!(DELEGATE_KEY).cast(),
+            Retained::into_raw(delegate_protocol).cast(),
+            OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+        );
+
+        tracing::debug!("Registered UI delegate for webview");
+
+        // Frame probe replies, used to target frames with `evaluateJavaScript:inFrame:`
+        let mtm = MainThreadMarker::new_unchecked();
+        let controller = wk_webview.configuration().userContentController();
+        let probe_script = WKUserScript::initWithSource_injectionTime_forMainFrameOnly(
+            WKUserScript::alloc(mtm),
+            &NSString::from_str(FRAME_PROBE_SCRIPT),
+            WKUserScriptInjectionTime::AtDocumentStart,
+            false,
+        );
+        controller.addUserScript(&probe_script);
+
+        let frame_handler = WebDriverFrameProbeHandler::new();
+        controller.addScriptMessageHandler_name(
+            ProtocolObject::from_ref(&*frame_handler),
+            &NSString::from_str(FRAME_HANDLER_NAME),
+        );
+    });
+}
+
+#[async_trait]
+impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
+    // =========================================================================
+    // Webview Access
+    // =========================================================================
+
+    fn webview(&self) -> &Webview<R> {
+        &self.webview
+    }
+
+    // =========================================================================
+    // Core JavaScript Execution
+    // =========================================================================
+
+    async fn evaluate_js(&self, script: &str) -> Result<Value, WebDriverErrorResponse> {
+        let mut chain = Vec::new();
+        let result = match self.resolve_frame(&mut chain).await {
+            Ok(()) => {
+                let script = wrap_script_for_frame_context(script, &self.frame_tail(&chain));
+                self.evaluate_in_frame(chain.last().map(String::as_str), &script)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        self.release_frames(chain);
+        result
+    }
+
+    // =========================================================================
+    // Async Script Execution (using callAsyncJavaScript)
+    // =========================================================================
+
+    async fn execute_async_script(
+        &self,
+        script: &str,
+        args: &[Value],
+    ) -> Result<Value, WebDriverErrorResponse> {
+        let args_json = serde_json::to_string(args)
+            .map_err(|e| WebDriverErrorResponse::invalid_argument(&e.to_string()))?;
+
+        // Build wrapper that includes argument deserialization
+        // callAsyncJavaScript handles Promises natively - we wrap the script in a Promise
+        // and provide __done via closure. It runs directly in the selected frame.
+        let wrapper = format!(
+            r"return new Promise((resolve, reject) => {{
+                var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, window); }};
+                var __done = function(result, error) {{
+                    if (error) {{
+                        reject(new Error(typeof error === 'string' ? error : String(error)));
+                        return;
+                    }}
+                    try {{
+                        resolve(({SERIALIZE_RESULT_JS})(result, window));
+                    }} catch (e) {{
+                        reject(e);
+                    }}
+                }};
+                var __args = {args_json}.map(deserializeArg);
+                __args.push(__done);
+                try {{
+                    (function() {{ {script} }}).apply(null, __args);
+                }} catch (e) {{
+                    reject(e);
+                }}
+            }});"
+        );
+
+        self.call_async_js(wrapper, None).await
+    }
+
+    async fn execute_script_in_new_realm(
+        &self,
+        script: &str,
+        args: &[Value],
+        is_async: bool,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        let body = format!("return {};", new_realm_script(script, args, is_async)?);
+        let world_name = format!("webdriver-{}", uuid::Uuid::new_v4());
+        let outcome = self.call_async_js(body, Some(world_name)).await?;
+        extract_script_outcome(&outcome)
+    }
+
+    // =========================================================================
+    // Preload Scripts
+    // =========================================================================
+
+    async fn add_preload_script(&self, script: &str) -> Result<String, WebDriverErrorResponse> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let key = id.clone();
+        let source = script.to_string();
+
+        self.webview
+            .with_webview(move |webview| unsafe {
+                let wk_webview: &WKWebView = &*webview.inner().cast();
+                let mtm = MainThreadMarker::new_unchecked();
+                let script = WKUserScript::initWithSource_injectionTime_forMainFrameOnly(
+                    WKUserScript::alloc(mtm),
+                    &NSString::from_str(&source),
+                    WKUserScriptInjectionTime::AtDocumentStart,
+                    false,
+                );
+                wk_webview
+                    .configuration()
+                    .userContentController()
+                    .addUserScript(&script);
+                PRELOAD_SCRIPTS.with(|scripts| scripts.borrow_mut().insert(key, script));
+            })
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        Ok(id)
+    }
+
+    async fn remove_preload_script(&self, id: &str) -> Result<(), WebDriverErrorResponse> {
+        let key = id.to_string();
+
+        self.webview
+            .with_webview(move |webview| unsafe {
+                let Some(removed) =
+                    PRELOAD_SCRIPTS.with(|scripts| scripts.borrow_mut().remove(&key))
+                else {
+                    return;
+                };
+
+                // User scripts can only be removed all at once, so re-add the others,
+                // including the frame probe and Tauri's own scripts
+                let wk_webview: &WKWebView = &*webview.inner().cast();
+                let controller = wk_webview.configuration().userContentController();
+                let scripts = controller.userScripts();
+                controller.removeAllUserScripts();
+                for i in 0..scripts.count() {
+                    let script = scripts.objectAtIndex(i);
+                    if !std::ptr::eq(&*script, &*removed) {
+                        controller.addUserScript(&script);
+                    }
+                }
+            })
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+
+    // =========================================================================
+    // Screenshots
+    // =========================================================================
+
+    async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        self.snapshot(None).await
+    }
 
     async fn take_element_screenshot(
         &self,
         js_var: &str,
     ) -> Result<String, WebDriverErrorResponse> {
-        // For element screenshots, we use JavaScript canvas approach
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
+        let clip = self.get_element_screenshot_clip(js_var).await?;
+        self.snapshot(Some(clip)).await
+    }
 
-                // Use html2canvas-like approach if available, otherwise scroll into view
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        // A snapshot rect beyond the visible bounds makes WebKit render the rest of the page
+        let clip = self.get_full_page_clip().await?;
+        self.snapshot(Some(clip)).await
+    }
 
-                // Return element bounds for clipping
-                var rect = el.getBoundingClientRect();
-                return {{
-                    x: rect.x,
-                    y: rect.y,
-                    width: rect.width,
-                    height: rect.height
-                }};
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
+    async fn take_window_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
 
-        self.take_screenshot().await
+        let result = self.webview.with_webview(move |webview| unsafe {
+            match webview.ns_window().cast::<AnyObject>().as_ref() {
+                Some(ns_window) => capture_ns_window(ns_window, tx),
+                None => {
+                    let _ = tx.send(Err("No native window".to_string()));
+                }
+            }
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(base64))) => Ok(base64),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&error)),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
     }
 
     // =========================================================================
@@ -333,101 +651,565 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for MacOSExecutor<R> {
     // =========================================================================
 
     async fn print_page(&self, options: PrintOptions) -> Result<String, WebDriverErrorResponse> {
-        // First, inject CSS @page rules for print settings
-        let page_width = options.page_width.unwrap_or(21.0);
-        let page_height = options.page_height.unwrap_or(29.7);
-        let margin_top = options.margin_top.unwrap_or(1.0);
-        let margin_bottom = options.margin_bottom.unwrap_or(1.0);
-        let margin_left = options.margin_left.unwrap_or(1.0);
-        let margin_right = options.margin_right.unwrap_or(1.0);
-        let orientation = options.orientation.as_deref().unwrap_or("portrait");
-
-        // Inject @page CSS rules
-        let css_script = format!(
-            r"(function() {{
-                var style = document.createElement('style');
-                style.id = '__webdriver_print_style';
-                style.textContent = `
-                    @page {{
-                        size: {page_width}cm {page_height}cm {orientation};
-                        margin: {margin_top}cm {margin_right}cm {margin_bottom}cm {margin_left}cm;
-                    }}
-                    @media print {{
-                        body {{
-                            -webkit-print-color-adjust: exact;
-                            print-color-adjust: exact;
-                        }}
-                    }}
-                `;
-                document.head.appendChild(style);
-                return true;
-            }})()"
+        // NSPrintInfo measures in points
+        const POINTS_PER_CM: f64 = 72.0 / 2.54;
+        let points = |value: Option<f64>, default: f64| value.unwrap_or(default) * POINTS_PER_CM;
+        let paper_size = NSSize::new(
+            points(options.page_width, 21.0),
+            points(options.page_height, 29.7),
         );
-        self.evaluate_js(&css_script).await?;
+        let margins = [
+            points(options.margin_top, 1.0),
+            points(options.margin_right, 1.0),
+            points(options.margin_bottom, 1.0),
+            points(options.margin_left, 1.0),
+        ];
+        let orientation = if options.orientation.as_deref() == Some("landscape") {
+            NSPaperOrientation::Landscape
+        } else {
+            NSPaperOrientation::Portrait
+        };
+        let scale = options.scale.unwrap_or(1.0);
+        let background = options.background.unwrap_or(false);
+        // NSPrintInfo holds a single first/last page pair
+        let page_range = match options.page_ranges.as_deref() {
+            None | Some([]) => None,
+            Some([range]) => Some(parse_page_range(range)?),
+            Some(_) => {
+                return Err(WebDriverErrorResponse::unsupported_operation(
+                    "Printing more than one page range is not supported on macOS",
+                ))
+            }
+        };
+
+        // The print operation saves the PDF to a file rather than handing back data
+        let path =
+            std::env::temp_dir().join(format!("webdriver-print-{}.pdf", uuid::Uuid::new_v4()));
+        let path_string = path.to_string_lossy().into_owned();
+        let print_id = uuid::Uuid::new_v4().to_string();
+        let cleanup_id = print_id.clone();
 
-        // Now create PDF using WKWebView's native API
         let (tx, rx) = oneshot::channel();
 
-        let result = self.window.with_webview(move |webview| unsafe {
+        let result = self.webview.with_webview(move |webview| unsafe {
             let wk_webview: &WKWebView = &*webview.inner().cast();
             let mtm = MainThreadMarker::new_unchecked();
+            let Some(window) = wk_webview.window() else {
+                let _ = tx.send(Err("Webview is not in a window".to_string()));
+                return;
+            };
 
-            // Create PDF configuration
-            let config = WKPDFConfiguration::new(mtm);
-            // Note: WKPDFConfiguration only has rect and allowTransparentBackground
-            // Page size/margins are handled via CSS @page rules above
+            let print_info = NSPrintInfo::new();
+            print_info.setPaperSize(paper_size);
+            print_info.setOrientation(orientation);
+            print_info.setTopMargin(margins[0]);
+            print_info.setRightMargin(margins[1]);
+            print_info.setBottomMargin(margins[2]);
+            print_info.setLeftMargin(margins[3]);
+            print_info.setScalingFactor(scale);
+            print_info.setHorizontalPagination(NSPrintingPaginationMode::Automatic);
+            print_info.setVerticalPagination(NSPrintingPaginationMode::Automatic);
+            print_info.setJobDisposition(NSPrintSaveJob);
+            let url = NSURL::fileURLWithPath(&NSString::from_str(&path_string));
+            let dictionary = print_info.dictionary();
+            dictionary.insert(NSPrintJobSavingURL, AsRef::<AnyObject>::as_ref(&*url));
+            if let Some(range) = page_range {
+                let page = |page: u32| NSNumber::new_i32(i32::try_from(page).unwrap_or(i32::MAX));
+                dictionary.insert(
+                    NSPrintAllPages,
+                    AsRef::<AnyObject>::as_ref(&*NSNumber::new_bool(false)),
+                );
+                dictionary.insert(
+                    NSPrintFirstPage,
+                    AsRef::<AnyObject>::as_ref(&*page(range.start)),
+                );
+                if let Some(end) = range.end {
+                    dictionary.insert(NSPrintLastPage, AsRef::<AnyObject>::as_ref(&*page(end)));
+                }
+            }
 
-            let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
-            let block = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
-                let response = if !error.is_null() {
-                    let error_ref = &*error;
-                    let description = error_ref.localizedDescription();
-                    Err(description.to_string())
-                } else if data.is_null() {
-                    Err("No PDF data returned".to_string())
-                } else {
-                    let data_ref = &*data;
-                    let bytes = data_ref.to_vec();
-                    Ok(bytes)
-                };
+            // `shouldPrintBackgrounds` is macOS 13.3+; older versions never print them.
+            // The delegate restores the app's setting once the operation is done.
+            let preferences = wk_webview.configuration().preferences();
+            let restore_background = preferences
+                .respondsToSelector(sel!(setShouldPrintBackgrounds:))
+                .then(|| {
+                    let previous = preferences.shouldPrintBackgrounds();
+                    preferences.setShouldPrintBackgrounds(background);
+                    (preferences, previous)
+                });
+
+            let operation = wk_webview.printOperationWithPrintInfo(&print_info);
+            operation.setShowsPrintPanel(false);
+            operation.setShowsProgressPanel(false);
+            // The operation's view has no size of its own, which prints blank pages
+            if let Some(view) = operation.view() {
+                view.setFrame(wk_webview.frame());
+            }
 
-                if let Ok(mut guard) = tx.lock() {
-                    if let Some(tx) = guard.take() {
-                        let _ = tx.send(response);
-                    }
-                }
-            });
+            let delegate = WebDriverPrintDelegate::new(mtm, tx, restore_background);
+            operation.runOperationModalForWindow_delegate_didRunSelector_contextInfo(
+                &window,
+                Some(&delegate),
+                Some(sel!(printOperationDidRun:success:contextInfo:)),
+                std::ptr::null_mut(),
+            );
 
-            wk_webview.createPDFWithConfiguration_completionHandler(Some(&config), &block);
+            // Keep the delegate alive until the operation reports back
+            PRINT_DELEGATES.with(|delegates| delegates.borrow_mut().insert(print_id, delegate));
         });
 
         if let Err(e) = result {
             return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
 
-        // Wait for result
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
-        let pdf_result = match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(Ok(bytes))) => Ok(bytes),
+        let outcome = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(true))) => std::fs::read(&path)
+                .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string())),
+            Ok(Ok(Ok(false))) => Err(WebDriverErrorResponse::unknown_error("Printing failed")),
             Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&error)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
-            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+            // The operation may still report back, so its delegate stays registered
+            Err(_) => return Err(WebDriverErrorResponse::script_timeout()),
+        };
+
+        let _ = self.webview.with_webview(move |_| {
+            PRINT_DELEGATES.with(|delegates| delegates.borrow_mut().remove(&cleanup_id));
+        });
+        let _ = std::fs::remove_file(&path);
+
+        outcome.map(|bytes| BASE64_STANDARD.encode(&bytes))
+    }
+
+    // =========================================================================
+    // Native Input
+    // =========================================================================
+
+    async fn dispatch_native_pointer_event(
+        &self,
+        event_type: PointerEventType,
+        x: i32,
+        y: i32,
+        button: u32,
+        pointer: &PointerInput,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let point = display_point(&self.window(), self.viewport_to_screen(x, y)?)?;
+        // Moves while a button is held become drags
+        let held = match event_type {
+            PointerEventType::Down | PointerEventType::Up => Some(button),
+            PointerEventType::Move => match pointer.buttons {
+                0 => None,
+                buttons => Some(buttons.trailing_zeros()),
+            },
+        };
+        let (event_type, cg_button) = mouse_event_type(event_type, held);
+        unsafe {
+            let event = CGEventCreateMouseEvent(std::ptr::null(), event_type, point, cg_button);
+            post_event(event, &pointer.modifiers)
+        }
+    }
+
+    async fn dispatch_native_key_event(
+        &self,
+        key: &str,
+        is_down: bool,
+        modifiers: &ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        unsafe {
+            if let Some(keycode) = mac_keycode(key) {
+                let event = CGEventCreateKeyboardEvent(std::ptr::null(), keycode, is_down);
+                return post_event(event, modifiers);
+            }
+            // Characters are typed as Unicode, independent of the keyboard layout
+            let event = CGEventCreateKeyboardEvent(std::ptr::null(), 0, is_down);
+            if !event.is_null() {
+                let units: Vec<u16> = key.encode_utf16().collect();
+                CGEventKeyboardSetUnicodeString(event, units.len(), units.as_ptr());
+            }
+            post_event(event, modifiers)
+        }
+    }
+
+    async fn dispatch_native_scroll_event(
+        &self,
+        x: i32,
+        y: i32,
+        delta_x: i32,
+        delta_y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let point = display_point(&self.window(), self.viewport_to_screen(x, y)?)?;
+        unsafe {
+            // Positive wheel values scroll up and left, unlike WebDriver deltas
+            let event = CGEventCreateScrollWheelEvent2(
+                std::ptr::null(),
+                CG_SCROLL_EVENT_UNIT_PIXEL,
+                2,
+                -delta_y,
+                -delta_x,
+                0,
+            );
+            if !event.is_null() {
+                CGEventSetLocation(event, point);
+            }
+            post_event(event, &ModifierState::default())
+        }
+    }
+}
+
+// =============================================================================
+// CoreGraphics Window Capture
+// =============================================================================
+
+/// Opaque `CGImage` type
+#[repr(C)]
+struct CGImage {
+    _private: [u8; 0],
+}
+
+unsafe impl RefEncode for CGImage {
+    const ENCODING_REF: Encoding = Encoding::Pointer(&Encoding::Struct("CGImage", &[]));
+}
+
+/// `kCGWindowListOptionIncludingWindow`
+const CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+/// `kCGWindowImageBoundsIgnoreFraming` - exclude the window shadow
+const CG_WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGWindowListCreateImage(
+        screen_bounds: NSRect,
+        list_option: u32,
+        window_id: u32,
+        image_option: u32,
+    ) -> *mut CGImage;
+    fn CGImageRelease(image: *mut CGImage);
+}
+
+/// Capture a native window, including its titlebar, as base64-encoded PNG.
+/// macOS 14+ captures through ScreenCaptureKit, which deprecates
+/// `CGWindowListCreateImage`; older versions fall back to the latter.
+unsafe fn capture_ns_window(ns_window: &AnyObject, tx: oneshot::Sender<Result<String, String>>) {
+    let window_number: isize = msg_send![ns_window, windowNumber];
+    let Ok(window_id) = u32::try_from(window_number) else {
+        let _ = tx.send(Err("Window is not on screen".to_string()));
+        return;
+    };
+
+    match screenshot_manager() {
+        Some(manager) => capture_with_screen_capture_kit(manager, window_id, tx),
+        None => {
+            let _ = tx.send(capture_with_window_list(window_id));
+        }
+    }
+}
+
+/// Capture a window with `CGWindowListCreateImage`
+unsafe fn capture_with_window_list(window_id: u32) -> Result<String, String> {
+    // CGRectNull captures the minimum rect enclosing the window
+    let null_rect = NSRect::new(
+        NSPoint::new(f64::INFINITY, f64::INFINITY),
+        NSSize::new(0.0, 0.0),
+    );
+    let image = CGWindowListCreateImage(
+        null_rect,
+        CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW,
+        window_id,
+        CG_WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING,
+    );
+    if image.is_null() {
+        return Err("Failed to capture window".to_string());
+    }
+
+    let response = cg_image_to_png_base64(image);
+    CGImageRelease(image);
+    response
+}
+
+/// Encode a `CGImage` as base64 PNG, without taking ownership of it
+unsafe fn cg_image_to_png_base64(image: *mut CGImage) -> Result<String, String> {
+    let bitmap_rep: Option<Retained<NSBitmapImageRep>> =
+        msg_send![NSBitmapImageRep::alloc(), initWithCGImage: image.cast_const()];
+    let bitmap_rep = bitmap_rep.ok_or("Failed to create bitmap image rep")?;
+    bitmap_rep_to_png_base64(&bitmap_rep)
+}
+
+// =============================================================================
+// ScreenCaptureKit Window Capture
+// =============================================================================
+
+/// `SCScreenshotManager`, if this macOS has it (14.0+). ScreenCaptureKit is loaded
+/// at runtime so apps still launch on versions without the framework.
+unsafe fn screenshot_manager() -> Option<&'static AnyClass> {
+    static LOADED: LazyLock<bool> = LazyLock::new(|| unsafe {
+        let path = NSString::from_str("/System/Library/Frameworks/ScreenCaptureKit.framework");
+        let Some(bundle_class) = AnyClass::get(c"NSBundle") else {
+            return false;
+        };
+        let bundle: Option<Retained<AnyObject>> = msg_send![bundle_class, bundleWithPath: &*path];
+        bundle.is_some_and(|bundle| msg_send![&*bundle, load])
+    });
+    if *LOADED {
+        AnyClass::get(c"SCScreenshotManager")
+    } else {
+        None
+    }
+}
+
+/// Sender shared by completion handlers, of which only the first response counts
+type CaptureSender = Arc<std::sync::Mutex<Option<oneshot::Sender<Result<String, String>>>>>;
+
+fn send_capture(tx: &CaptureSender, response: Result<String, String>) {
+    if let Ok(mut guard) = tx.lock() {
+        if let Some(tx) = guard.take() {
+            let _ = tx.send(response);
+        }
+    }
+}
+
+/// Capture a window with `SCScreenshotManager`, at the display's pixel scale and
+/// without its shadow
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+unsafe fn capture_with_screen_capture_kit(
+    manager: &'static AnyClass,
+    window_id: u32,
+    tx: oneshot::Sender<Result<String, String>>,
+) {
+    let Some(content_class) = AnyClass::get(c"SCShareableContent") else {
+        let _ = tx.send(Err("ScreenCaptureKit is unavailable".to_string()));
+        return;
+    };
+    let tx: CaptureSender = Arc::new(std::sync::Mutex::new(Some(tx)));
+
+    let content_handler = RcBlock::new(move |content: *mut AnyObject, error: *mut NSError| {
+        if !error.is_null() {
+            send_capture(&tx, Err((*error).localizedDescription().to_string()));
+            return;
+        }
+        let Some(content) = content.as_ref() else {
+            send_capture(&tx, Err("No shareable content".to_string()));
+            return;
         };
 
-        // Clean up injected style
-        let _ = self
-            .evaluate_js(
-                r"(function() {
-                var style = document.getElementById('__webdriver_print_style');
-                if (style) style.remove();
-                return true;
-            })()",
-            )
-            .await;
+        let windows: Retained<NSArray<AnyObject>> = msg_send![content, windows];
+        let window = windows.iter().find(|window| {
+            let id: u32 = msg_send![&**window, windowID];
+            id == window_id
+        });
+        let (Some(window), Some(filter_class), Some(config_class)) = (
+            window,
+            AnyClass::get(c"SCContentFilter"),
+            AnyClass::get(c"SCStreamConfiguration"),
+        ) else {
+            send_capture(&tx, Err("Window is not shareable".to_string()));
+            return;
+        };
+
+        let filter: Allocated<AnyObject> = msg_send![filter_class, alloc];
+        let filter: Retained<AnyObject> =
+            msg_send![filter, initWithDesktopIndependentWindow: &*window];
+        let rect: NSRect = msg_send![&*filter, contentRect];
+        let scale: f32 = msg_send![&*filter, pointPixelScale];
+        let pixels = |points: f64| (points * f64::from(scale)).round() as usize;
+
+        let config: Retained<AnyObject> = msg_send![config_class, new];
+        let _: () = msg_send![&*config, setWidth: pixels(rect.size.width)];
+        let _: () = msg_send![&*config, setHeight: pixels(rect.size.height)];
+        let _: () = msg_send![&*config, setShowsCursor: false];
+        let _: () = msg_send![&*config, setIgnoreShadowsSingleWindow: true];
+
+        let tx = tx.clone();
+        let image_handler = RcBlock::new(move |image: *mut CGImage, error: *mut NSError| {
+            let response = if !error.is_null() {
+                Err((*error).localizedDescription().to_string())
+            } else if image.is_null() {
+                Err("No image returned".to_string())
+            } else {
+                cg_image_to_png_base64(image)
+            };
+            send_capture(&tx, response);
+        });
+        let _: () = msg_send![
+            manager,
+            captureImageWithFilter: &*filter,
+            configuration: &*config,
+            completionHandler: &*image_handler
+        ];
+    });
+
+    let _: () = msg_send![
+        content_class,
+        getShareableContentExcludingDesktopWindows: true,
+        onScreenWindowsOnly: false,
+        completionHandler: &*content_handler
+    ];
+}
+
+// =============================================================================
+// CoreGraphics Input Events
+// =============================================================================
+
+/// Opaque `CGEvent` type
+type CGEventRef = *mut std::ffi::c_void;
+
+/// `kCGScrollEventUnitPixel`
+const CG_SCROLL_EVENT_UNIT_PIXEL: u32 = 0;
+
+/// `CGEventFlags` masks for held modifiers
+const CG_EVENT_FLAG_MASK_SHIFT: u64 = 1 << 17;
+const CG_EVENT_FLAG_MASK_CONTROL: u64 = 1 << 18;
+const CG_EVENT_FLAG_MASK_ALTERNATE: u64 = 1 << 19;
+const CG_EVENT_FLAG_MASK_COMMAND: u64 = 1 << 20;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventCreateMouseEvent(
+        source: *const std::ffi::c_void,
+        mouse_type: u32,
+        location: NSPoint,
+        button: u32,
+    ) -> CGEventRef;
+    fn CGEventCreateKeyboardEvent(
+        source: *const std::ffi::c_void,
+        keycode: u16,
+        key_down: bool,
+    ) -> CGEventRef;
+    fn CGEventCreateScrollWheelEvent2(
+        source: *const std::ffi::c_void,
+        units: u32,
+        wheel_count: u32,
+        wheel1: i32,
+        wheel2: i32,
+        wheel3: i32,
+    ) -> CGEventRef;
+    fn CGEventKeyboardSetUnicodeString(event: CGEventRef, length: usize, string: *const u16);
+    fn CGEventSetLocation(event: CGEventRef, location: NSPoint);
+    fn CGEventSetFlags(event: CGEventRef, flags: u64);
+    fn CGEventPostToPid(pid: i32, event: CGEventRef);
+}
 
-        // Return base64 encoded PDF
-        pdf_result.map(|bytes| BASE64_STANDARD.encode(&bytes))
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(cf: *const std::ffi::c_void);
+}
+
+/// Global display point (top-left origin, in points) of a screen position in physical pixels
+fn display_point<R: Runtime>(
+    window: &tauri::Window<R>,
+    (x, y): (f64, f64),
+) -> Result<NSPoint, WebDriverErrorResponse> {
+    let scale = window
+        .scale_factor()
+        .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+    Ok(NSPoint::new(x / scale, y / scale))
+}
+
+/// `CGEventType` and `CGMouseButton` for a pointer event with the `WebDriver` button
+/// pressed, released or held (`None` for a move without buttons)
+fn mouse_event_type(event_type: PointerEventType, button: Option<u32>) -> (u32, u32) {
+    // WebDriver buttons are left, middle, right; CoreGraphics orders left, right, center
+    let Some(cg_button) = button.map(|button| match button {
+        1 => 2,
+        2 => 1,
+        other => other,
+    }) else {
+        return (5, 0);
+    };
+    let event_type = match (event_type, cg_button) {
+        (PointerEventType::Down, 0) => 1,
+        (PointerEventType::Up, 0) => 2,
+        (PointerEventType::Down, 1) => 3,
+        (PointerEventType::Up, 1) => 4,
+        (PointerEventType::Down, _) => 25,
+        (PointerEventType::Up, _) => 26,
+        // Moves with a button held are drags
+        (PointerEventType::Move, 0) => 6,
+        (PointerEventType::Move, 1) => 7,
+        (PointerEventType::Move, _) => 27,
+    };
+    (event_type, cg_button)
+}
+
+/// Mac virtual keycode for a normalized `WebDriver` key. Characters have none and
+/// are typed as Unicode instead.
+fn mac_keycode(key: &str) -> Option<u16> {
+    let mut chars = key.chars();
+    let ch = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    let keycode = match ch {
+        '\u{E050}' => 60,
+        '\u{E051}' => 62,
+        '\u{E052}' => 61,
+        '\u{E053}' => 54,
+        _ => match normalized_key(ch)? {
+            "Help" => 114,
+            "Backspace" => 51,
+            "Tab" => 48,
+            "Clear" => 71,
+            "Enter" => 36,
+            "Shift" => 56,
+            "Control" => 59,
+            "Alt" => 58,
+            "Meta" => 55,
+            "Escape" => 53,
+            " " => 49,
+            "PageUp" => 116,
+            "PageDown" => 121,
+            "End" => 119,
+            "Home" => 115,
+            "ArrowLeft" => 123,
+            "ArrowRight" => 124,
+            "ArrowDown" => 125,
+            "ArrowUp" => 126,
+            "Delete" => 117,
+            "F1" => 122,
+            "F2" => 120,
+            "F3" => 99,
+            "F4" => 118,
+            "F5" => 96,
+            "F6" => 97,
+            "F7" => 98,
+            "F8" => 100,
+            "F9" => 101,
+            "F10" => 109,
+            "F11" => 103,
+            "F12" => 111,
+            _ => return None,
+        },
+    };
+    Some(keycode)
+}
+
+/// Apply held modifiers, post an event to this process and release it
+unsafe fn post_event(
+    event: CGEventRef,
+    modifiers: &ModifierState,
+) -> Result<(), WebDriverErrorResponse> {
+    if event.is_null() {
+        return Err(WebDriverErrorResponse::unknown_error(
+            "Failed to create input event",
+        ));
+    }
+    let mut flags = 0;
+    if modifiers.shift {
+        flags |= CG_EVENT_FLAG_MASK_SHIFT;
+    }
+    if modifiers.ctrl {
+        flags |= CG_EVENT_FLAG_MASK_CONTROL;
+    }
+    if modifiers.alt {
+        flags |= CG_EVENT_FLAG_MASK_ALTERNATE;
+    }
+    if modifiers.meta {
+        flags |= CG_EVENT_FLAG_MASK_COMMAND;
     }
+    CGEventSetFlags(event, flags);
+    CGEventPostToPid(std::process::id().cast_signed(), event);
+    CFRelease(event.cast_const());
+    Ok(())
 }
 
 // =============================================================================
@@ -442,6 +1224,11 @@ unsafe fn image_to_png_base64(image: &NSImage) -> Result<String, String> {
     let bitmap_rep = NSBitmapImageRep::imageRepWithData(&tiff_data)
         .ok_or("Failed to create bitmap image rep")?;
 
+    bitmap_rep_to_png_base64(&bitmap_rep)
+}
+
+/// Convert `NSBitmapImageRep` to PNG and encode as base64
+unsafe fn bitmap_rep_to_png_base64(bitmap_rep: &NSBitmapImageRep) -> Result<String, String> {
     let empty_dict: objc2::rc::Retained<NSDictionary<NSString>> = NSDictionary::new();
     let png_data: Option<objc2::rc::Retained<NSData>> =
         bitmap_rep.representationUsingType_properties(NSBitmapImageFileType::PNG, &empty_dict);
@@ -578,7 +1365,7 @@ define_class!(
             let (tx, rx) = std::sync::mpsc::channel();
 
             // Store alert state with responder (using per-window state from ivars)
-            self.ivars().alert_state.set_pending(PendingAlert {
+            let alert_id = self.ivars().alert_state.set_pending(PendingAlert {
                 message: message_str,
                 default_text: None,
                 alert_type: AlertType::Alert,
@@ -586,8 +1373,7 @@ define_class!(
             });
 
             // Wait for accept/dismiss (with timeout)
-            let timeout = std::time::Duration::from_secs(30);
-            let _ = rx.recv_timeout(timeout);
+            self.ivars().alert_state.wait_for_response(alert_id, &rx);
 
             completion_handler.call(());
         }
@@ -608,7 +1394,7 @@ define_class!(
             let (tx, rx) = std::sync::mpsc::channel();
 
             // Store confirm state with responder (using per-window state from ivars)
-            self.ivars().alert_state.set_pending(PendingAlert {
+            let alert_id = self.ivars().alert_state.set_pending(PendingAlert {
                 message: message_str,
                 default_text: None,
                 alert_type: AlertType::Confirm,
@@ -616,11 +1402,10 @@ define_class!(
             });
 
             // Wait for accept/dismiss (with timeout)
-            let timeout = std::time::Duration::from_secs(30);
-            let response = rx.recv_timeout(timeout);
+            let response = self.ivars().alert_state.wait_for_response(alert_id, &rx);
 
-            // Return true if accepted, false if dismissed or timeout
-            let accepted = response.map(|r| r.accepted).unwrap_or(true);
+            // Return true if accepted, false if dismissed
+            let accepted = response.accepted;
 
             completion_handler.call((objc2::runtime::Bool::from(accepted),));
         }
@@ -643,7 +1428,7 @@ define_class!(
             let (tx, rx) = std::sync::mpsc::channel();
 
             // Store prompt state with responder (using per-window state from ivars)
-            self.ivars().alert_state.set_pending(PendingAlert {
+            let alert_id = self.ivars().alert_state.set_pending(PendingAlert {
                 message: prompt_str,
                 default_text: default.clone(),
                 alert_type: AlertType::Prompt,
@@ -651,17 +1436,15 @@ define_class!(
             });
 
             // Wait for accept/dismiss (with timeout)
-            let timeout = std::time::Duration::from_secs(30);
-            let response = rx.recv_timeout(timeout);
+            let response = self.ivars().alert_state.wait_for_response(alert_id, &rx);
 
             // Return the prompt text if accepted, null if dismissed
-            let result: *mut NSString = match response {
-                Ok(r) if r.accepted => {
-                    let text = r.prompt_text.or(default).unwrap_or_default();
-                    let ns_str = NSString::from_str(&text);
-                    Retained::into_raw(ns_str)
-                }
-                _ => std::ptr::null_mut(), // Dismissed or timeout = null (cancel)
+            let result: *mut NSString = if response.accepted {
+                let text = response.prompt_text.or(default).unwrap_or_default();
+                let ns_str = NSString::from_str(&text);
+                Retained::into_raw(ns_str)
+            } else {
+                std::ptr::null_mut()
             };
 
             completion_handler.call((result,));
@@ -679,3 +1462,109 @@ impl WebDriverUIDelegate {
         msg_send![super(this), init]
     }
 }
+
+// =============================================================================
+// Print Operation Delegate
+// =============================================================================
+
+/// Instance variables for `WebDriverPrintDelegate`
+struct WebDriverPrintDelegateIvars {
+    done: Cell<Option<oneshot::Sender<Result<bool, String>>>>,
+    /// Preferences whose `shouldPrintBackgrounds` to reset, and the value to reset it to
+    restore_background: Cell<Option<(Retained<WKPreferences>, bool)>>,
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "WebDriverPrintDelegate"]
+    #[ivars = WebDriverPrintDelegateIvars]
+    struct WebDriverPrintDelegate;
+
+    unsafe impl NSObjectProtocol for WebDriverPrintDelegate {}
+
+    impl WebDriverPrintDelegate {
+        /// Report whether the print operation saved its file
+        #[unsafe(method(printOperationDidRun:success:contextInfo:))]
+        fn print_operation_did_run(
+            &self,
+            _operation: &NSPrintOperation,
+            success: objc2::runtime::Bool,
+            _context_info: *mut c_void,
+        ) {
+            if let Some((preferences, previous)) = self.ivars().restore_background.take() {
+                unsafe { preferences.setShouldPrintBackgrounds(previous) };
+            }
+            if let Some(tx) = self.ivars().done.take() {
+                let _ = tx.send(Ok(success.as_bool()));
+            }
+        }
+    }
+);
+
+impl WebDriverPrintDelegate {
+    fn new(
+        mtm: MainThreadMarker,
+        done: oneshot::Sender<Result<bool, String>>,
+        restore_background: Option<(Retained<WKPreferences>, bool)>,
+    ) -> Retained<Self> {
+        let this = Self::alloc(mtm);
+        let this = this.set_ivars(WebDriverPrintDelegateIvars {
+            done: Cell::new(Some(done)),
+            restore_background: Cell::new(restore_background),
+        });
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+// =============================================================================
+// Frame Probe Message Handler
+// =============================================================================
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "WebDriverFrameProbeHandler"]
+    struct WebDriverFrameProbeHandler;
+
+    unsafe impl NSObjectProtocol for WebDriverFrameProbeHandler {}
+
+    #[allow(non_snake_case)]
+    unsafe impl WKScriptMessageHandler for WebDriverFrameProbeHandler {
+        /// Record the `WKFrameInfo` of a frame answering a probe
+        #[unsafe(method(userContentController:didReceiveScriptMessage:))]
+        fn userContentController_didReceiveScriptMessage(
+            &self,
+            _controller: &WKUserContentController,
+            message: &WKScriptMessage,
+        ) {
+            let body = unsafe { message.body() };
+            let Some(nonce) = (unsafe { ns_object_to_json(&body) })
+                .as_str()
+                .map(String::from)
+            else {
+                return;
+            };
+
+            let frame_info = unsafe { message.frameInfo() };
+            FRAME_INFOS.with(|infos| infos.borrow_mut().insert(nonce.clone(), frame_info));
+
+            if let Ok(mut probes) = FRAME_PROBES.lock() {
+                if let Some(tx) = probes.remove(&nonce) {
+                    let _ = tx.send(());
+                }
+            }
+        }
+    }
+);
+
+impl WebDriverFrameProbeHandler {
+    /// # Safety
+    /// Must be called from the main thread.
+    unsafe fn new() -> Retained<Self> {
+        let mtm = MainThreadMarker::new_unchecked();
+        let this = Self::alloc(mtm);
+        let this = this.set_ivars(());
+        msg_send![super(this), init]
+    }
+}
@@ -0,0 +1,62 @@
+//! Registry of every webview created by the app.
+//!
+//! `Manager::webviews()` is gated behind Tauri's `unstable` feature, so webviews are
+//! recorded from the plugin's `on_webview_ready` hook instead. This includes child
+//! webviews embedded in multi-webview windows, not just `WebviewWindow`s.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{Runtime, Webview};
+
+/// Tracks live webviews by label
+pub struct WebviewRegistry<R: Runtime> {
+    webviews: Mutex<HashMap<String, Webview<R>>>,
+}
+
+impl<R: Runtime> WebviewRegistry<R> {
+    /// Create a new empty registry
+    pub fn new() -> Self {
+        Self {
+            webviews: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a newly created webview
+    pub fn register(&self, webview: &Webview<R>) {
+        if let Ok(mut webviews) = self.webviews.lock() {
+            webviews.insert(webview.label().to_string(), webview.clone());
+        }
+    }
+
+    /// Get a live webview by label
+    pub fn get(&self, label: &str) -> Option<Webview<R>> {
+        let mut webviews = self.webviews.lock().ok()?;
+        webviews.retain(|_, webview| is_alive(webview));
+        webviews.get(label).cloned()
+    }
+
+    /// Get all live webviews
+    pub fn webviews(&self) -> Vec<Webview<R>> {
+        let Ok(mut webviews) = self.webviews.lock() else {
+            return Vec::new();
+        };
+        webviews.retain(|_, webview| is_alive(webview));
+        webviews.values().cloned().collect()
+    }
+}
+
+impl<R: Runtime> Default for WebviewRegistry<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A webview is alive while its hosting window still lists it
+fn is_alive<R: Runtime>(webview: &Webview<R>) -> bool {
+    webview
+        .window()
+        .webviews()
+        .iter()
+        .any(|w| w.label() == webview.label())
+}
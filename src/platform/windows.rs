@@ -1,85 +1,63 @@
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
 use serde_json::Value;
-use tauri::{Manager, Runtime, WebviewWindow};
+use tauri::{Manager, Runtime, Webview};
 use tokio::sync::oneshot;
+use webview2_com::AddScriptToExecuteOnDocumentCreatedCompletedHandler;
 use webview2_com::Microsoft::Web::WebView2::Win32::{
-    ICoreWebView2, ICoreWebView2CapturePreviewCompletedHandler, ICoreWebView2Environment6,
-    ICoreWebView2ExecuteScriptCompletedHandler, ICoreWebView2PrintToPdfCompletedHandler,
-    ICoreWebView2ScriptDialogOpeningEventHandler, ICoreWebView2WebMessageReceivedEventHandler,
-    ICoreWebView2_7, COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
-    COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE, COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT,
+    ICoreWebView2, ICoreWebView2CallDevToolsProtocolMethodCompletedHandler,
+    ICoreWebView2CapturePreviewCompletedHandler, ICoreWebView2Environment6,
+    ICoreWebView2ExecuteScriptCompletedHandler, ICoreWebView2PrintSettings2,
+    ICoreWebView2PrintToPdfCompletedHandler, ICoreWebView2ScriptDialogOpeningEventHandler,
+    ICoreWebView2WebMessageReceivedEventHandler, ICoreWebView2_11, ICoreWebView2_7,
+    COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG, COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE,
+    COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT,
 };
 use windows::core::{Interface, HSTRING, PCWSTR};
-use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Foundation::{HGLOBAL, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+    ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+};
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
 use windows::Win32::System::Com::StructuredStorage::CreateStreamOnHGlobal;
 use windows::Win32::System::Com::{
     CoInitializeEx, COINIT_APARTMENTTHREADED, STATFLAG_NONAME, STREAM_SEEK_SET,
 };
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSEEVENTF_ABSOLUTE,
+    MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
+    MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
+    MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL, MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT,
+    MOUSE_EVENT_FLAGS, VIRTUAL_KEY, VK_ADD, VK_BACK, VK_CANCEL, VK_CLEAR, VK_DECIMAL, VK_DELETE,
+    VK_DIVIDE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_HELP, VK_HOME, VK_INSERT, VK_LCONTROL,
+    VK_LEFT, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MULTIPLY, VK_NEXT, VK_NUMPAD0, VK_OEM_AUTO, VK_PAUSE,
+    VK_PRIOR, VK_RCONTROL, VK_RETURN, VK_RIGHT, VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SEPARATOR,
+    VK_SPACE, VK_SUBTRACT, VK_TAB, VK_UP,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetSystemMetrics, GetWindowRect, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+    SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+};
 use windows_core::BOOL;
 
-use crate::platform::alert_state::{AlertState, AlertStateManager, AlertType, PendingAlert};
-use crate::platform::{wrap_script_for_frame_context, FrameId, PlatformExecutor, PrintOptions};
+use crate::platform::async_script::{AsyncScriptState, HANDLER_NAME};
+use crate::platform::{
+    extract_script_outcome, frame_window_script, new_realm_script, wrap_script_for_frame_context,
+    FrameContext, FrameId, ModifierState, PlatformExecutor, PointerEventType, PointerInput,
+    PrintOptions, DESERIALIZE_ARG_JS, ELEMENT_REFS_JS, SERIALIZE_RESULT_JS,
+};
 use crate::server::response::WebDriverErrorResponse;
+use crate::webdriver::alert::{AlertState, AlertStateManager, AlertType, PendingAlert};
+use crate::webdriver::image::Image;
+use crate::webdriver::keys::normalized_key;
 use crate::webdriver::Timeouts;
 
-// =============================================================================
-// Async Script State
-// =============================================================================
-
-/// Handler name used for postMessage calls
-const HANDLER_NAME: &str = "webdriver_async";
-
-/// Shared state for pending async script operations.
-/// This is managed via Tauri's state system (`app.manage()`).
-#[derive(Default)]
-pub struct AsyncScriptState {
-    pending: Mutex<HashMap<String, oneshot::Sender<Result<Value, String>>>>,
-    /// Track which webviews have native handlers registered (by window label)
-    registered_handlers: Mutex<HashSet<String>>,
-}
-
-impl AsyncScriptState {
-    /// Register a pending async operation and return the receiver
-    pub fn register(&self, id: String) -> oneshot::Receiver<Result<Value, String>> {
-        let (tx, rx) = oneshot::channel();
-        if let Ok(mut pending) = self.pending.lock() {
-            pending.insert(id, tx);
-        }
-        rx
-    }
-
-    /// Complete a pending async operation with a result
-    pub fn complete(&self, id: &str, result: Result<Value, String>) {
-        if let Ok(mut pending) = self.pending.lock() {
-            if let Some(tx) = pending.remove(id) {
-                let _ = tx.send(result);
-            }
-        }
-    }
-
-    /// Cancel a pending async operation
-    pub fn cancel(&self, id: &str) {
-        if let Ok(mut pending) = self.pending.lock() {
-            pending.remove(id);
-        }
-    }
-
-    /// Check if a handler is registered for a window label, and mark it as registered if not.
-    /// Returns true if the handler was already registered, false if it needs to be registered.
-    pub fn mark_handler_registered(&self, label: &str) -> bool {
-        if let Ok(mut handlers) = self.registered_handlers.lock() {
-            !handlers.insert(label.to_string())
-        } else {
-            false
-        }
-    }
-}
-
 /// Wrapper for raw COM pointer to allow sending across threads.
 /// SAFETY: The COM object must only be accessed from a COM-initialized thread.
 struct SendableComPtr(*mut std::ffi::c_void);
@@ -94,33 +72,303 @@ impl SendableComPtr {
 /// Windows `WebView2` executor
 #[derive(Clone)]
 pub struct WindowsExecutor<R: Runtime> {
-    window: WebviewWindow<R>,
+    webview: Webview<R>,
     timeouts: Timeouts,
-    frame_context: Vec<FrameId>,
+    frame_context: FrameContext,
 }
 
 impl<R: Runtime> WindowsExecutor<R> {
-    pub fn new(window: WebviewWindow<R>, timeouts: Timeouts, frame_context: Vec<FrameId>) -> Self {
+    pub fn new(webview: Webview<R>, timeouts: Timeouts, frame_context: FrameContext) -> Self {
         Self {
-            window,
+            webview,
             timeouts,
             frame_context,
         }
     }
+
+    /// Inject input through `SendInput`, which delivers to whichever window has focus.
+    /// The actions handler raises the window beforehand, except for sessions with
+    /// `hiddenWindows`, whose windows input can't reach.
+    fn send_native_input(&self, inputs: &[INPUT]) -> Result<(), WebDriverErrorResponse> {
+        let hwnd = self
+            .webview
+            .window()
+            .hwnd()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        if unsafe { GetForegroundWindow() }.0 != hwnd.0 {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "Native input needs the window in the foreground, which hiddenWindows prevents",
+            ));
+        }
+
+        let size = i32::try_from(std::mem::size_of::<INPUT>()).unwrap_or(i32::MAX);
+        let sent = unsafe { SendInput(inputs, size) };
+        if sent as usize == inputs.len() {
+            Ok(())
+        } else {
+            Err(WebDriverErrorResponse::unknown_error(
+                "SendInput was blocked by another thread or a higher integrity process",
+            ))
+        }
+    }
+
+    /// Run a script in the top-level document via `ExecuteScript`
+    async fn run_script(&self, script: String) -> Result<Value, WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
+
+        let result = self.webview.with_webview(move |webview| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            if let Ok(webview2) = webview.controller().CoreWebView2() {
+                let script_hstring = HSTRING::from(&script);
+
+                let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+                let handler: ICoreWebView2ExecuteScriptCompletedHandler =
+                    ExecuteScriptHandler::new(tx).into();
+
+                webview2
+                    .ExecuteScript(PCWSTR(script_hstring.as_ptr()), &handler)
+                    .ok();
+            }
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(value))) => Ok(serde_json::json!({
+                "success": true,
+                "value": value
+            })),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    /// Whether the selected frame's document is reachable from the top-level document
+    async fn is_frame_script_accessible(&self) -> Result<bool, WebDriverErrorResponse> {
+        let probe = format!(
+            "(function() {{ try {{ return !!{}.document.documentElement; }} catch (e) {{ return false; }} }})()",
+            frame_window_script(&self.frame_context)
+        );
+        let result = self.run_script(probe).await?;
+        Ok(result.get("value") == Some(&Value::Bool(true)))
+    }
+
+    /// Call a Chrome `DevTools` Protocol method, optionally on an attached target session
+    async fn call_devtools_method(
+        &self,
+        session_id: Option<&str>,
+        method: &str,
+        params: &Value,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
+        let session_id = session_id.map(String::from);
+        let method_owned = method.to_string();
+        let params = params.to_string();
+
+        let result = self.webview.with_webview(move |webview| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+            let handler: ICoreWebView2CallDevToolsProtocolMethodCompletedHandler =
+                DevToolsMethodHandler::new(tx.clone()).into();
+            let method_hstring = HSTRING::from(&method_owned);
+            let params_hstring = HSTRING::from(&params);
+
+            let call = webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|webview2| match &session_id {
+                    Some(session_id) => {
+                        let session_hstring = HSTRING::from(session_id);
+                        webview2.cast::<ICoreWebView2_11>().and_then(|webview2| {
+                            webview2.CallDevToolsProtocolMethodForSession(
+                                PCWSTR(session_hstring.as_ptr()),
+                                PCWSTR(method_hstring.as_ptr()),
+                                PCWSTR(params_hstring.as_ptr()),
+                                &handler,
+                            )
+                        })
+                    }
+                    None => webview2.CallDevToolsProtocolMethod(
+                        PCWSTR(method_hstring.as_ptr()),
+                        PCWSTR(params_hstring.as_ptr()),
+                        &handler,
+                    ),
+                });
+
+            if let Err(e) = call {
+                if let Ok(mut guard) = tx.lock() {
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(Err(e.to_string()));
+                    }
+                }
+            }
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
+        }
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(value))) => Ok(value),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&format!(
+                "{method} failed: {error}"
+            ))),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    /// Capture a PNG with `Page.captureScreenshot`, returning its base64 data
+    async fn capture_devtools_screenshot(
+        &self,
+        params: Value,
+    ) -> Result<String, WebDriverErrorResponse> {
+        let result = self
+            .call_devtools_method(None, "Page.captureScreenshot", &params)
+            .await?;
+        result
+            .get("data")
+            .and_then(Value::as_str)
+            .filter(|data| !data.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| WebDriverErrorResponse::unknown_error("Screenshot returned empty data"))
+    }
+
+    /// Evaluate a script in the selected frame through `DevTools` targets.
+    /// `WebView2` runs cross-origin frames out of process, each as its own target.
+    async fn evaluate_js_in_frame_target(
+        &self,
+        script: &str,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        let mut sessions = Vec::new();
+        let result = self
+            .evaluate_js_in_attached_frame(script, &mut sessions)
+            .await;
+
+        for session_id in sessions.iter().rev() {
+            let params = serde_json::json!({ "sessionId": session_id });
+            let _ = self
+                .call_devtools_method(None, "Target.detachFromTarget", &params)
+                .await;
+        }
+
+        result
+    }
+
+    /// Walk the frame chain, attaching to each out-of-process frame on the way
+    async fn evaluate_js_in_attached_frame(
+        &self,
+        script: &str,
+        sessions: &mut Vec<String>,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        // Start of the frames still to be walked with plain JS inside the current target
+        let mut segment_start = 0;
+
+        for (i, frame) in self.frame_context.iter().enumerate() {
+            let owner = match frame {
+                FrameId::Index(index) => {
+                    format!("document.querySelectorAll('iframe, frame')[{index}]")
+                }
+                FrameId::Element(js_var) => format!("{ELEMENT_REFS_JS}.get('{js_var}')"),
+            };
+            let segment = FrameContext::from(self.frame_context[segment_start..i].to_vec());
+            let owner = wrap_script_for_frame_context(&owner, &segment);
+            let session = sessions.last().map(String::as_str);
+
+            let evaluated = self
+                .call_devtools_method(
+                    session,
+                    "Runtime.evaluate",
+                    &serde_json::json!({ "expression": owner }),
+                )
+                .await?;
+            let object_id = evaluated
+                .pointer("/result/objectId")
+                .and_then(Value::as_str)
+                .ok_or_else(WebDriverErrorResponse::no_such_frame)?;
+
+            let described = self
+                .call_devtools_method(
+                    session,
+                    "DOM.describeNode",
+                    &serde_json::json!({ "objectId": object_id }),
+                )
+                .await?;
+            let frame_id = described
+                .pointer("/node/frameId")
+                .and_then(Value::as_str)
+                .ok_or_else(WebDriverErrorResponse::no_such_frame)?;
+
+            // Out-of-process frames are targets whose id is the frame id. Attaching
+            // fails for in-process frames, which are then entered with plain JS.
+            let attach = serde_json::json!({ "targetId": frame_id, "flatten": true });
+            if let Ok(attached) = self
+                .call_devtools_method(session, "Target.attachToTarget", &attach)
+                .await
+            {
+                if let Some(session_id) = attached.get("sessionId").and_then(Value::as_str) {
+                    sessions.push(session_id.to_string());
+                    segment_start = i + 1;
+                }
+            }
+        }
+
+        let segment = FrameContext::from(self.frame_context[segment_start..].to_vec());
+        let expression = wrap_script_for_frame_context(script, &segment);
+        let params = serde_json::json!({
+            "expression": expression,
+            "returnByValue": true,
+            "awaitPromise": true
+        });
+        let evaluated = self
+            .call_devtools_method(
+                sessions.last().map(String::as_str),
+                "Runtime.evaluate",
+                &params,
+            )
+            .await?;
+
+        if let Some(exception) = evaluated.get("exceptionDetails") {
+            let message = exception
+                .pointer("/exception/description")
+                .or_else(|| exception.get("text"))
+                .and_then(Value::as_str)
+                .unwrap_or("Script error");
+            return Err(WebDriverErrorResponse::from_js_error(message, None));
+        }
+
+        Ok(serde_json::json!({
+            "success": true,
+            "value": evaluated.pointer("/result/value").cloned().unwrap_or(Value::Null)
+        }))
+    }
 }
 
 /// Register `WebView2` handlers at webview creation time.
 /// This is called from the plugin's `on_webview_ready` hook to ensure
-/// the script dialog handler is registered before any navigation completes.
+/// the script dialog and message handlers are registered before any navigation completes,
+/// including for windows created after the session started.
 pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
-    // Get per-window alert state from the manager
+    // Fresh per-window alert state, so a recreated window doesn't inherit a stale prompt
     let manager = webview.app_handle().state::<AlertStateManager>();
-    let alert_state = manager.get_or_create(webview.label());
+    let alert_state = manager.reset(webview.label());
+    let app = webview.app_handle().clone();
 
     let _ = webview.with_webview(move |webview| unsafe {
         let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
 
         if let Ok(webview2) = webview.controller().CoreWebView2() {
+            // Native message handler used by execute_async_script
+            let async_state = app.state::<AsyncScriptState>();
+            register_message_handler(&webview2, async_state.inner());
+
             // Disable default script dialogs so ScriptDialogOpening event fires
             if let Ok(settings) = webview2.Settings() {
                 if let Err(e) = settings.SetAreDefaultScriptDialogsEnabled(false) {
@@ -151,11 +399,11 @@ pub fn register_webview_handlers<R: Runtime>(webview: &tauri::Webview<R>) {
 #[async_trait]
 impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
     // =========================================================================
-    // Window Access
+    // Webview Access
     // =========================================================================
 
-    fn window(&self) -> &WebviewWindow<R> {
-        &self.window
+    fn webview(&self) -> &Webview<R> {
+        &self.webview
     }
 
     // =========================================================================
@@ -163,42 +411,14 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
     // =========================================================================
 
     async fn evaluate_js(&self, script: &str) -> Result<Value, WebDriverErrorResponse> {
-        let (tx, rx) = oneshot::channel();
-        let script_owned = wrap_script_for_frame_context(script, &self.frame_context);
-
-        let result = self.window.with_webview(move |webview| unsafe {
-            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
-
-            if let Ok(webview2) = webview.controller().CoreWebView2() {
-                let script_hstring = HSTRING::from(&script_owned);
-
-                let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
-                let handler: ICoreWebView2ExecuteScriptCompletedHandler =
-                    ExecuteScriptHandler::new(tx).into();
-
-                webview2
-                    .ExecuteScript(PCWSTR(script_hstring.as_ptr()), &handler)
-                    .ok();
-            }
-        });
-
-        if let Err(e) = result {
-            return Err(WebDriverErrorResponse::javascript_error(
-                &e.to_string(),
-                None,
-            ));
+        // Cross-origin frames can't be reached by walking `contentWindow` from the top
+        // document, so scripts for those are routed through DevTools targets instead
+        if !self.frame_context.is_empty() && !self.is_frame_script_accessible().await? {
+            return self.evaluate_js_in_frame_target(script).await;
         }
 
-        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(Ok(value))) => Ok(serde_json::json!({
-                "success": true,
-                "value": value
-            })),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
-            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
-            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
-        }
+        self.run_script(wrap_script_for_frame_context(script, &self.frame_context))
+            .await
     }
 
     // =========================================================================
@@ -208,10 +428,21 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
     async fn take_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
         // Use WebView2's native CapturePreview API
         let (tx, rx) = oneshot::channel();
+        let shown = Arc::new(AtomicBool::new(false));
+        let shown_for_capture = shown.clone();
 
-        let result = self.window.with_webview(move |webview| {
+        let result = self.webview.with_webview(move |webview| {
             unsafe {
-                if let Ok(webview2) = webview.controller().CoreWebView2() {
+                // WebView2 suspends rendering while its controller is hidden, in which
+                // case CapturePreview never completes. Show it for the capture only.
+                let controller = webview.controller();
+                let mut is_visible = BOOL::default();
+                if controller.IsVisible(&mut is_visible).is_ok() && !is_visible.as_bool() {
+                    let _ = controller.SetIsVisible(true);
+                    shown_for_capture.store(true, Ordering::SeqCst);
+                }
+
+                if let Ok(webview2) = controller.CoreWebView2() {
                     // Create an in-memory stream for the PNG image
                     let stream = match CreateStreamOnHGlobal(HGLOBAL::default(), true) {
                         Ok(s) => s,
@@ -249,7 +480,13 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         }
 
         let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
-        match tokio::time::timeout(timeout, rx).await {
+        let captured = tokio::time::timeout(timeout, rx).await;
+        if shown.load(Ordering::SeqCst) {
+            let _ = self.webview.with_webview(|webview| unsafe {
+                let _ = webview.controller().SetIsVisible(false);
+            });
+        }
+        match captured {
             Ok(Ok(Ok(base64))) => {
                 if base64.is_empty() {
                     Err(WebDriverErrorResponse::unknown_error(
@@ -269,21 +506,85 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         &self,
         js_var: &str,
     ) -> Result<String, WebDriverErrorResponse> {
-        // Scroll element into view first
-        let script = format!(
-            r"(function() {{
-                var el = window.{js_var};
-                if (!el || !el.isConnected) {{
-                    throw new Error('stale element reference');
-                }}
-                el.scrollIntoView({{ block: 'center', inline: 'center' }});
-                return true;
-            }})()"
-        );
-        self.evaluate_js(&script).await?;
+        let clip = self.get_element_screenshot_clip(js_var).await?;
+
+        // DevTools clips are relative to the document, so add the scroll offset
+        let metrics = self
+            .call_devtools_method(None, "Page.getLayoutMetrics", &serde_json::json!({}))
+            .await?;
+        let viewport = metrics.get("cssVisualViewport");
+        let page_offset = |key: &str| {
+            viewport
+                .and_then(|viewport| viewport.get(key))
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+        };
 
-        // Take full screenshot and return (element clipping can be added later)
-        self.take_screenshot().await
+        self.capture_devtools_screenshot(serde_json::json!({
+            "format": "png",
+            "clip": {
+                "x": clip.x + page_offset("pageX"),
+                "y": clip.y + page_offset("pageY"),
+                "width": clip.width,
+                "height": clip.height,
+                "scale": 1
+            }
+        }))
+        .await
+    }
+
+    async fn take_full_page_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        let metrics = self
+            .call_devtools_method(None, "Page.getLayoutMetrics", &serde_json::json!({}))
+            .await?;
+        let content = metrics.get("cssContentSize");
+        let content_size = |key: &str| {
+            content
+                .and_then(|content| content.get(key))
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+        };
+
+        self.capture_devtools_screenshot(serde_json::json!({
+            "format": "png",
+            "captureBeyondViewport": true,
+            "clip": {
+                "x": 0,
+                "y": 0,
+                "width": content_size("width"),
+                "height": content_size("height"),
+                "scale": 1
+            }
+        }))
+        .await
+    }
+
+    async fn take_window_screenshot(&self) -> Result<String, WebDriverErrorResponse> {
+        // HWNDs aren't `Send`, so only the address crosses to the main thread
+        let hwnd = self
+            .webview
+            .window()
+            .hwnd()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?
+            .0 as usize;
+        let (tx, rx) = oneshot::channel();
+
+        self.webview
+            .with_webview(move |_| unsafe {
+                let _ = tx.send(capture_window(HWND(hwnd as *mut std::ffi::c_void)));
+            })
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        let image = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(image))) => image,
+            Ok(Ok(Err(error))) => return Err(WebDriverErrorResponse::unknown_error(&error)),
+            Ok(Err(_)) => return Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => return Err(WebDriverErrorResponse::script_timeout()),
+        };
+        tokio::task::spawn_blocking(move || image.to_base64_png())
+            .await
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?
     }
 
     // =========================================================================
@@ -314,8 +615,10 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         let margin_bottom = options.margin_bottom;
         let margin_left = options.margin_left;
         let margin_right = options.margin_right;
+        // Already validated and normalized to `start-end` by the handler
+        let page_ranges = options.page_ranges.map(|ranges| ranges.join(","));
 
-        let result = self.window.with_webview(move |webview| unsafe {
+        let result = self.webview.with_webview(move |webview| unsafe {
             let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
 
             let webview2 = match webview.controller().CoreWebView2() {
@@ -429,6 +732,25 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
                 let _ = settings.SetMarginRight(m / 2.54);
             }
 
+            // Page ranges use the same syntax as WebDriver, e.g. "1-3,5,7-"
+            if let Some(ref ranges) = page_ranges {
+                match settings.cast::<ICoreWebView2PrintSettings2>() {
+                    Ok(settings2) => {
+                        let _ = settings2.SetPageRanges(&HSTRING::from(ranges));
+                    }
+                    Err(e) => {
+                        if let Ok(mut guard) = tx.lock() {
+                            if let Some(tx) = guard.take() {
+                                let _ = tx.send(Err(format!(
+                                    "Page ranges require a newer WebView2 runtime: {e:?}"
+                                )));
+                            }
+                        }
+                        return;
+                    }
+                }
+            }
+
             // Create completion handler
             let handler: ICoreWebView2PrintToPdfCompletedHandler =
                 handlers::PrintToPdfHandler::new(tx).into();
@@ -467,6 +789,90 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         Ok(BASE64_STANDARD.encode(&pdf_data))
     }
 
+    // =========================================================================
+    // Native Input
+    // =========================================================================
+
+    async fn dispatch_native_pointer_event(
+        &self,
+        event_type: PointerEventType,
+        x: i32,
+        y: i32,
+        button: u32,
+        _pointer: &PointerInput,
+    ) -> Result<(), WebDriverErrorResponse> {
+        // Held modifiers and buttons are part of the OS input state, set by their own key actions
+        let (screen_x, screen_y) = self.viewport_to_screen(x, y)?;
+        let (dx, dy) = absolute_mouse_position(screen_x, screen_y);
+        let mut inputs = vec![mouse_input(dx, dy, 0, MOUSEEVENTF_MOVE)];
+        let is_down = match event_type {
+            PointerEventType::Move => None,
+            PointerEventType::Down => Some(true),
+            PointerEventType::Up => Some(false),
+        };
+        if let Some(is_down) = is_down {
+            let (flags, data) = mouse_button_flags(button, is_down).ok_or_else(|| {
+                WebDriverErrorResponse::invalid_argument(&format!(
+                    "Unsupported mouse button: {button}"
+                ))
+            })?;
+            inputs.push(mouse_input(dx, dy, data, flags));
+        }
+        self.send_native_input(&inputs)
+    }
+
+    async fn dispatch_native_key_event(
+        &self,
+        key: &str,
+        is_down: bool,
+        _modifiers: &ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let up = if is_down {
+            KEYBD_EVENT_FLAGS(0)
+        } else {
+            KEYEVENTF_KEYUP
+        };
+        let inputs: Vec<INPUT> = match virtual_key(key) {
+            Some((vk, extended)) => {
+                let flags = if extended {
+                    up | KEYEVENTF_EXTENDEDKEY
+                } else {
+                    up
+                };
+                vec![keyboard_input(vk, 0, flags)]
+            }
+            // Characters are typed as Unicode, independent of the keyboard layout
+            None => key
+                .encode_utf16()
+                .map(|unit| keyboard_input(VIRTUAL_KEY(0), unit, up | KEYEVENTF_UNICODE))
+                .collect(),
+        };
+        self.send_native_input(&inputs)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    async fn dispatch_native_scroll_event(
+        &self,
+        x: i32,
+        y: i32,
+        delta_x: i32,
+        delta_y: i32,
+    ) -> Result<(), WebDriverErrorResponse> {
+        let (screen_x, screen_y) = self.viewport_to_screen(x, y)?;
+        let (dx, dy) = absolute_mouse_position(screen_x, screen_y);
+        let wheel = |delta: i32| (f64::from(delta) * WHEEL_DELTA_PER_PIXEL).round() as i32;
+
+        let mut inputs = vec![mouse_input(dx, dy, 0, MOUSEEVENTF_MOVE)];
+        if delta_y != 0 {
+            // Positive wheel data scrolls up, unlike WebDriver deltas
+            inputs.push(mouse_input(dx, dy, -wheel(delta_y), MOUSEEVENTF_WHEEL));
+        }
+        if delta_x != 0 {
+            inputs.push(mouse_input(dx, dy, wheel(delta_x), MOUSEEVENTF_HWHEEL));
+        }
+        self.send_native_input(&inputs)
+    }
+
     // =========================================================================
     // Async Script Execution
     // =========================================================================
@@ -482,71 +888,38 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         let async_id = uuid::Uuid::new_v4().to_string();
 
         // Get async state and register this operation
-        let app = self.window.app_handle().clone();
+        // (the native message handler is registered in `register_webview_handlers`)
+        let app = self.webview.app_handle().clone();
         let async_state = app.state::<AsyncScriptState>();
-        let label = self.window.label().to_string();
-
-        // Register handler if not already registered for this window
-        if !async_state.mark_handler_registered(&label) {
-            let app_clone = app.clone();
-            let handler_result = self.window.with_webview(move |webview| unsafe {
-                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
-
-                if let Ok(webview2) = webview.controller().CoreWebView2() {
-                    let state = app_clone.state::<AsyncScriptState>();
-                    register_message_handler(&webview2, state.inner());
-                }
-            });
 
-            if let Err(e) = handler_result {
-                return Err(WebDriverErrorResponse::unknown_error(&format!(
-                    "Failed to register message handler: {e}"
-                )));
-            }
-        }
+        let mut pending = async_state.register(async_id.clone());
 
-        let rx = async_state.register(async_id.clone());
-
-        // Build wrapper script using postMessage
+        // Build wrapper script using postMessage. The wrapper runs in the selected frame
+        // via `evaluate_js`, but only the top-level document can post to the host.
         let wrapper = format!(
             r"(function() {{
-                var ELEMENT_KEY = 'element-6066-11e4-a52e-4f735466cecf';
-                function deserializeArg(arg) {{
-                    if (arg === null || arg === undefined) return arg;
-                    if (Array.isArray(arg)) return arg.map(deserializeArg);
-                    if (typeof arg === 'object') {{
-                        if (arg[ELEMENT_KEY]) {{
-                            var el = window['__wd_el_' + arg[ELEMENT_KEY].replace(/-/g, '')];
-                            if (!el) throw new Error('stale element reference');
-                            return el;
-                        }}
-                        var result = {{}};
-                        for (var key in arg) {{
-                            if (arg.hasOwnProperty(key)) result[key] = deserializeArg(arg[key]);
-                        }}
-                        return result;
-                    }}
-                    return arg;
-                }}
-                var __done = function(r) {{
-                    window.chrome.webview.postMessage(JSON.stringify({{
+                var deserializeArg = function(arg) {{ return ({DESERIALIZE_ARG_JS})(arg, window); }};
+                var __post = function(result, error) {{
+                    window.top.chrome.webview.postMessage(JSON.stringify({{
                         handler: '{HANDLER_NAME}',
                         id: '{async_id}',
-                        result: r,
-                        error: null
+                        result: result,
+                        error: error
                     }}));
                 }};
-                var __args = {args_json}.map(deserializeArg);
-                __args.push(__done);
+                var __done = function(r) {{
+                    try {{
+                        __post(({SERIALIZE_RESULT_JS})(r, window), null);
+                    }} catch (e) {{
+                        __post(null, e.message || String(e));
+                    }}
+                }};
                 try {{
+                    var __args = {args_json}.map(deserializeArg);
+                    __args.push(__done);
                     (function() {{ {script} }}).apply(null, __args);
                 }} catch (e) {{
-                    window.chrome.webview.postMessage(JSON.stringify({{
-                        handler: '{HANDLER_NAME}',
-                        id: '{async_id}',
-                        result: null,
-                        error: e.message || String(e)
-                    }}));
+                    __post(null, e.message || String(e));
                 }}
             }})()"
         );
@@ -558,16 +931,343 @@ impl<R: Runtime + 'static> PlatformExecutor<R> for WindowsExecutor<R> {
         let timeout_ms = self.timeouts.script_ms;
         let timeout = std::time::Duration::from_millis(timeout_ms);
 
-        match tokio::time::timeout(timeout, rx).await {
+        match tokio::time::timeout(timeout, &mut pending.receiver).await {
             Ok(Ok(Ok(value))) => Ok(value),
-            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::javascript_error(&error, None)),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::from_js_error(&error, None)),
             Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
-            Err(_) => {
-                async_state.cancel(&async_id);
-                Err(WebDriverErrorResponse::script_timeout())
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    async fn execute_script_in_new_realm(
+        &self,
+        script: &str,
+        args: &[Value],
+        is_async: bool,
+    ) -> Result<Value, WebDriverErrorResponse> {
+        if !self.frame_context.is_empty() {
+            return Err(WebDriverErrorResponse::unsupported_operation(
+                "New realms are only supported in top-level browsing contexts on this platform",
+            ));
+        }
+        let expression = new_realm_script(script, args, is_async)?;
+
+        let tree = self
+            .call_devtools_method(None, "Page.getFrameTree", &serde_json::json!({}))
+            .await?;
+        let frame_id = tree
+            .pointer("/frameTree/frame/id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| WebDriverErrorResponse::unknown_error("No main frame"))?;
+        let world = self
+            .call_devtools_method(
+                None,
+                "Page.createIsolatedWorld",
+                &serde_json::json!({
+                    "frameId": frame_id,
+                    "worldName": format!("webdriver-{}", uuid::Uuid::new_v4())
+                }),
+            )
+            .await?;
+        let context_id = world
+            .get("executionContextId")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| WebDriverErrorResponse::unknown_error("No isolated world created"))?;
+
+        let evaluated = self
+            .call_devtools_method(
+                None,
+                "Runtime.evaluate",
+                &serde_json::json!({
+                    "expression": expression,
+                    "contextId": context_id,
+                    "returnByValue": true,
+                    "awaitPromise": true
+                }),
+            )
+            .await?;
+        if let Some(exception) = evaluated.get("exceptionDetails") {
+            let message = exception
+                .pointer("/exception/description")
+                .or_else(|| exception.get("text"))
+                .and_then(Value::as_str)
+                .unwrap_or("Script error");
+            return Err(WebDriverErrorResponse::from_js_error(message, None));
+        }
+        extract_script_outcome(evaluated.pointer("/result/value").unwrap_or(&Value::Null))
+    }
+
+    // =========================================================================
+    // Preload Scripts
+    // =========================================================================
+
+    async fn add_preload_script(&self, script: &str) -> Result<String, WebDriverErrorResponse> {
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+        let source = HSTRING::from(script);
+
+        let result = self.webview.with_webview(move |webview| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let send = move |response: Result<String, String>| {
+                if let Ok(mut guard) = tx.lock() {
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(response);
+                    }
+                }
+            };
+
+            let webview2 = match webview.controller().CoreWebView2() {
+                Ok(wv) => wv,
+                Err(e) => {
+                    send(Err(format!("Failed to get CoreWebView2: {e:?}")));
+                    return;
+                }
+            };
+
+            // Runs in the top-level document and child frames of future navigations
+            let send_completed = send.clone();
+            let handler = AddScriptToExecuteOnDocumentCreatedCompletedHandler::create(Box::new(
+                move |result, id| {
+                    send_completed(result.map(|()| id).map_err(|e| format!("{e:?}")));
+                    Ok(())
+                },
+            ));
+            if let Err(e) = webview2.AddScriptToExecuteOnDocumentCreated(&source, &handler) {
+                send(Err(format!(
+                    "AddScriptToExecuteOnDocumentCreated failed: {e:?}"
+                )));
             }
+        });
+
+        if let Err(e) = result {
+            return Err(WebDriverErrorResponse::unknown_error(&e.to_string()));
         }
+
+        let timeout = std::time::Duration::from_millis(self.timeouts.script_ms);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(id))) => Ok(id),
+            Ok(Ok(Err(error))) => Err(WebDriverErrorResponse::unknown_error(&error)),
+            Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+            Err(_) => Err(WebDriverErrorResponse::script_timeout()),
+        }
+    }
+
+    async fn remove_preload_script(&self, id: &str) -> Result<(), WebDriverErrorResponse> {
+        let id = HSTRING::from(id);
+
+        self.webview
+            .with_webview(move |webview| unsafe {
+                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                if let Ok(webview2) = webview.controller().CoreWebView2() {
+                    if let Err(e) = webview2.RemoveScriptToExecuteOnDocumentCreated(&id) {
+                        tracing::error!("RemoveScriptToExecuteOnDocumentCreated failed: {e:?}");
+                    }
+                }
+            })
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))
+    }
+}
+
+// =============================================================================
+// Window Capture
+// =============================================================================
+
+/// `PW_RENDERFULLCONTENT` - include DirectComposition content such as WebView2,
+/// which a plain `PrintWindow` leaves black
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+/// Capture a native window, including its title bar, with `PrintWindow`.
+/// This works while the window is covered by others, unlike copying from the screen.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+unsafe fn capture_window(hwnd: HWND) -> Result<Image, String> {
+    let mut rect = RECT::default();
+    GetWindowRect(hwnd, &mut rect).map_err(|e| format!("Failed to get window rect: {e}"))?;
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        return Err("Window has no size".to_string());
     }
+
+    let screen_dc = GetDC(None);
+    let memory_dc = CreateCompatibleDC(Some(screen_dc));
+    let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+    let previous = SelectObject(memory_dc, bitmap.into());
+    let printed = PrintWindow(hwnd, memory_dc, PW_RENDERFULLCONTENT).as_bool();
+    // GetDIBits needs the bitmap deselected
+    SelectObject(memory_dc, previous);
+
+    // A negative height asks for top-down rows
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let lines = GetDIBits(
+        memory_dc,
+        bitmap,
+        0,
+        height as u32,
+        Some(pixels.as_mut_ptr().cast()),
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+
+    let _ = DeleteObject(bitmap.into());
+    let _ = DeleteDC(memory_dc);
+    ReleaseDC(None, screen_dc);
+
+    if !printed || lines == 0 {
+        return Err("Failed to capture window".to_string());
+    }
+
+    // GDI hands back BGRX with an undefined alpha byte
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = u8::MAX;
+    }
+    Ok(Image {
+        width: width as u32,
+        height: height as u32,
+        pixels,
+    })
+}
+
+// =============================================================================
+// Native Input
+// =============================================================================
+
+/// Wheel data per scrolled pixel; Chromium scrolls 100px per `WHEEL_DELTA` (120)
+const WHEEL_DELTA_PER_PIXEL: f64 = 1.2;
+
+/// Normalized absolute coordinates (0-65535 across the virtual desktop) of a
+/// screen point in physical pixels
+#[allow(clippy::cast_possible_truncation)]
+fn absolute_mouse_position(x: f64, y: f64) -> (i32, i32) {
+    let (left, top, width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    let normalize = |value: f64, origin: i32, extent: i32| {
+        ((value - f64::from(origin)) * 65535.0 / f64::from((extent - 1).max(1))).round() as i32
+    };
+    (normalize(x, left, width), normalize(y, top, height))
+}
+
+/// `SendInput` flags and mouse data for a `WebDriver` button press or release
+fn mouse_button_flags(button: u32, is_down: bool) -> Option<(MOUSE_EVENT_FLAGS, i32)> {
+    let (down, up, data) = match button {
+        0 => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 0),
+        1 => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, 0),
+        2 => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, 0),
+        // XBUTTON1 and XBUTTON2: back and forward
+        3 => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, 1),
+        4 => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, 2),
+        _ => return None,
+    };
+    Some((if is_down { down } else { up }, data))
+}
+
+/// Mouse `INPUT` at normalized absolute coordinates
+fn mouse_input(dx: i32, dy: i32, data: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx,
+                dy,
+                mouseData: data,
+                dwFlags: flags | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Keyboard `INPUT` for a virtual key, or a UTF-16 unit with `KEYEVENTF_UNICODE`
+fn keyboard_input(vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Virtual key for a normalized `WebDriver` key and whether it is an extended key.
+/// Characters have no virtual key and are typed as Unicode instead.
+fn virtual_key(key: &str) -> Option<(VIRTUAL_KEY, bool)> {
+    let mut chars = key.chars();
+    let ch = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    let vk = match ch {
+        // Right-hand modifiers and the navigation cluster
+        '\u{E051}' => return Some((VK_RCONTROL, true)),
+        '\u{E052}' => return Some((VK_RMENU, true)),
+        '\u{E050}' => VK_RSHIFT,
+        '\u{E053}' => return Some((VK_RWIN, true)),
+        '\u{E01A}'..='\u{E023}' => {
+            VIRTUAL_KEY(VK_NUMPAD0.0 + u16::try_from(u32::from(ch) - 0xE01A).unwrap_or(0))
+        }
+        '\u{E024}' => VK_MULTIPLY,
+        '\u{E025}' => VK_ADD,
+        '\u{E026}' => VK_SEPARATOR,
+        '\u{E027}' => VK_SUBTRACT,
+        '\u{E028}' => VK_DECIMAL,
+        '\u{E029}' => return Some((VK_DIVIDE, true)),
+        _ => match normalized_key(ch)? {
+            "Cancel" => VK_CANCEL,
+            "Help" => VK_HELP,
+            "Backspace" => VK_BACK,
+            "Tab" => VK_TAB,
+            "Clear" => VK_CLEAR,
+            "Enter" => VK_RETURN,
+            "Shift" => VK_LSHIFT,
+            "Control" => VK_LCONTROL,
+            "Alt" => VK_LMENU,
+            "Meta" => return Some((VK_LWIN, true)),
+            "Pause" => VK_PAUSE,
+            "Escape" => VK_ESCAPE,
+            " " => VK_SPACE,
+            "PageUp" => return Some((VK_PRIOR, true)),
+            "PageDown" => return Some((VK_NEXT, true)),
+            "End" => return Some((VK_END, true)),
+            "Home" => return Some((VK_HOME, true)),
+            "ArrowLeft" => return Some((VK_LEFT, true)),
+            "ArrowUp" => return Some((VK_UP, true)),
+            "ArrowRight" => return Some((VK_RIGHT, true)),
+            "ArrowDown" => return Some((VK_DOWN, true)),
+            "Insert" => return Some((VK_INSERT, true)),
+            "Delete" => return Some((VK_DELETE, true)),
+            "ZenkakuHankaku" => VK_OEM_AUTO,
+            name => {
+                let number = name.strip_prefix('F')?.parse::<u16>().ok()?;
+                VIRTUAL_KEY(VK_F1.0 + number - 1)
+            }
+        },
+    };
+    Some((vk, false))
 }
 
 // =============================================================================
@@ -585,7 +1285,9 @@ mod handlers {
 
     use serde_json::Value;
     use webview2_com::Microsoft::Web::WebView2::Win32::{
-        ICoreWebView2, ICoreWebView2CapturePreviewCompletedHandler,
+        ICoreWebView2, ICoreWebView2CallDevToolsProtocolMethodCompletedHandler,
+        ICoreWebView2CallDevToolsProtocolMethodCompletedHandler_Impl,
+        ICoreWebView2CapturePreviewCompletedHandler,
         ICoreWebView2CapturePreviewCompletedHandler_Impl, ICoreWebView2Deferral,
         ICoreWebView2ExecuteScriptCompletedHandler,
         ICoreWebView2ExecuteScriptCompletedHandler_Impl, ICoreWebView2PrintToPdfCompletedHandler,
@@ -600,9 +1302,9 @@ mod handlers {
 
     use super::{
         AlertState, AlertType, AsyncScriptState, CaptureResultSender, PendingAlert,
-        PrintResultSender, ScriptResultSender, SendableComPtr, HANDLER_NAME,
+        PrintResultSender, ScriptResultSender, SendableComPtr,
     };
-    use crate::platform::alert_state::AlertResponse;
+    use crate::webdriver::alert::AlertResponse;
     use std::sync::Arc;
 
     #[implement(ICoreWebView2ExecuteScriptCompletedHandler)]
@@ -641,6 +1343,40 @@ mod handlers {
         }
     }
 
+    #[implement(ICoreWebView2CallDevToolsProtocolMethodCompletedHandler)]
+    pub struct DevToolsMethodHandler {
+        pub tx: ScriptResultSender,
+    }
+
+    impl DevToolsMethodHandler {
+        pub fn new(tx: ScriptResultSender) -> Self {
+            Self { tx }
+        }
+    }
+
+    impl ICoreWebView2CallDevToolsProtocolMethodCompletedHandler_Impl for DevToolsMethodHandler_Impl {
+        fn Invoke(
+            &self,
+            errorcode: windows::core::HRESULT,
+            returnobjectasjson: &windows::core::PCWSTR,
+        ) -> windows::core::Result<()> {
+            let json_str = unsafe { returnobjectasjson.to_string().unwrap_or_default() };
+            let response = if errorcode.is_err() {
+                // The returned JSON carries the protocol error message, if any
+                Err(format!("{errorcode:?} {json_str}"))
+            } else {
+                serde_json::from_str(&json_str).map_err(|e| e.to_string())
+            };
+
+            if let Ok(mut guard) = self.tx.lock() {
+                if let Some(tx) = guard.take() {
+                    let _ = tx.send(response);
+                }
+            }
+            Ok(())
+        }
+    }
+
     #[implement(ICoreWebView2CapturePreviewCompletedHandler)]
     pub struct CapturePreviewHandler {
         pub tx: CaptureResultSender,
@@ -816,30 +1552,7 @@ mod handlers {
                     Err(_) => return Ok(()), // Not our message format
                 };
 
-                // Check if this is our handler
-                let handler = msg.get("handler").and_then(Value::as_str);
-                if handler != Some(HANDLER_NAME) {
-                    return Ok(()); // Not for us
-                }
-
-                // Extract async ID
-                let Some(async_id) = msg.get("id").and_then(Value::as_str) else {
-                    tracing::warn!("Message missing 'id' field");
-                    return Ok(());
-                };
-                let async_id = async_id.to_string();
-
-                // Check for error
-                if let Some(error) = msg.get("error").and_then(Value::as_str) {
-                    if !error.is_empty() {
-                        state.complete(&async_id, Err(error.to_string()));
-                        return Ok(());
-                    }
-                }
-
-                // Extract result
-                let result = msg.get("result").cloned().unwrap_or(Value::Null);
-                state.complete(&async_id, Ok(result));
+                state.complete_message(&msg);
             }
             Ok(())
         }
@@ -868,7 +1581,7 @@ mod handlers {
             args: windows::core::Ref<'_, ICoreWebView2ScriptDialogOpeningEventArgs>,
         ) -> windows::core::Result<()> {
             // Extract data and prepare for async handling inside unsafe block
-            let (args_ptr, deferral_ptr, rx) = unsafe {
+            let (args_ptr, deferral_ptr, rx, alert_id) = unsafe {
                 let Some(args) = args.clone() else {
                     return Ok(());
                 };
@@ -928,7 +1641,7 @@ mod handlers {
 
                 // Create channel for WebDriver response
                 let (tx, rx) = std::sync::mpsc::channel::<AlertResponse>();
-                self.alert_state.set_pending(PendingAlert {
+                let alert_id = self.alert_state.set_pending(PendingAlert {
                     message: message.clone(),
                     default_text: default_text.clone(),
                     alert_type,
@@ -939,13 +1652,16 @@ mod handlers {
                 let args_ptr = SendableComPtr(args.into_raw());
                 let deferral_ptr = SendableComPtr(deferral.into_raw());
 
-                (args_ptr, deferral_ptr, rx)
+                (args_ptr, deferral_ptr, rx, alert_id)
             };
 
             // Spawn thread to wait for WebDriver response (don't block UI thread)
+            let alert_state = self.alert_state.clone();
             std::thread::spawn(move || {
-                let timeout = std::time::Duration::from_secs(30);
-                let response = rx.recv_timeout(timeout);
+                let AlertResponse {
+                    accepted,
+                    prompt_text,
+                } = alert_state.wait_for_response(alert_id, &rx);
 
                 // SAFETY: These pointers came from valid COM objects and we're
                 // accessing them from a single thread. All COM method calls are unsafe.
@@ -954,27 +1670,15 @@ mod handlers {
                         ICoreWebView2ScriptDialogOpeningEventArgs::from_raw(args_ptr.as_ptr());
                     let deferral = ICoreWebView2Deferral::from_raw(deferral_ptr.as_ptr());
 
-                    match response {
-                        Ok(AlertResponse {
-                            accepted,
-                            prompt_text,
-                        }) => {
-                            if accepted {
-                                // Set prompt text if provided
-                                if let Some(text) = prompt_text {
-                                    let result = windows::core::HSTRING::from(text.as_str());
-                                    let _ =
-                                        args.SetResultText(windows::core::PCWSTR(result.as_ptr()));
-                                }
-                                let _ = args.Accept();
-                            }
-                            // If not accepted, don't call Accept() - dialog returns false/null
-                        }
-                        Err(_) => {
-                            // Timeout - auto-accept
-                            let _ = args.Accept();
+                    if accepted {
+                        // Set prompt text if provided
+                        if let Some(text) = prompt_text {
+                            let result = windows::core::HSTRING::from(text.as_str());
+                            let _ = args.SetResultText(windows::core::PCWSTR(result.as_ptr()));
                         }
+                        let _ = args.Accept();
                     }
+                    // If not accepted, don't call Accept() - dialog returns false/null
 
                     // Complete the deferral to let WebView2 continue
                     let _ = deferral.Complete();
@@ -987,7 +1691,7 @@ mod handlers {
 }
 
 use handlers::{
-    CapturePreviewHandler, ExecuteScriptHandler, ScriptDialogOpeningHandler,
+    CapturePreviewHandler, DevToolsMethodHandler, ExecuteScriptHandler, ScriptDialogOpeningHandler,
     WebMessageReceivedHandler,
 };
 
@@ -1,13 +1,19 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
 use tauri::Runtime;
+use tokio::time::{sleep_until, Duration, Instant};
 
-use crate::platform::{ModifierState, PointerEventType};
-use crate::server::response::{WebDriverResponse, WebDriverResult};
+use crate::platform::{
+    ModifierState, PlatformExecutor, PointerEventType, PointerInput, PointerProperties, PointerType,
+};
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::keys::is_modifier_key;
+use crate::webdriver::{ActionState, InputSourceType};
 
 #[derive(Debug, Deserialize)]
 pub struct ActionsRequest {
@@ -18,30 +24,32 @@ pub struct ActionsRequest {
 #[serde(tag = "type")]
 pub enum ActionSequence {
     #[serde(rename = "key")]
-    Key {
-        #[serde(rename = "id")]
-        _id: String,
-        actions: Vec<KeyAction>,
-    },
+    Key { id: String, actions: Vec<KeyAction> },
     #[serde(rename = "pointer")]
     Pointer {
         id: String,
+        #[serde(default)]
+        parameters: PointerParameters,
         actions: Vec<PointerAction>,
     },
     #[serde(rename = "wheel")]
     Wheel {
-        #[serde(rename = "id")]
-        _id: String,
+        id: String,
         actions: Vec<WheelAction>,
     },
     #[serde(rename = "none")]
     None {
-        #[serde(rename = "id")]
-        _id: String,
+        id: String,
         actions: Vec<PauseAction>,
     },
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct PointerParameters {
+    #[serde(rename = "pointerType", default)]
+    pub pointer_type: PointerType,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum KeyAction {
@@ -57,19 +65,44 @@ pub enum KeyAction {
 #[serde(tag = "type")]
 pub enum PointerAction {
     #[serde(rename = "pointerDown")]
-    PointerDown { button: u32 },
+    PointerDown {
+        button: u32,
+        #[serde(flatten)]
+        properties: PointerProperties,
+    },
     #[serde(rename = "pointerUp")]
-    PointerUp { button: u32 },
+    PointerUp {
+        button: u32,
+        #[serde(flatten)]
+        properties: PointerProperties,
+    },
     #[serde(rename = "pointerMove")]
     PointerMove {
         x: i32,
         y: i32,
         duration: Option<u64>,
+        #[serde(default)]
+        origin: Option<PointerOrigin>,
+        #[serde(flatten)]
+        properties: PointerProperties,
     },
     #[serde(rename = "pause")]
     Pause { duration: Option<u64> },
 }
 
+/// Origin of pointer move and scroll coordinates
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PointerOrigin {
+    /// Offsets are relative to the in-view center point of an element
+    Element {
+        #[serde(rename = "element-6066-11e4-a52e-4f735466cecf")]
+        id: String,
+    },
+    /// `viewport` (the default) or, for pointer moves only, `pointer`
+    Named(String),
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
 pub enum WheelAction {
@@ -83,6 +116,8 @@ pub enum WheelAction {
         delta_y: i32,
         #[serde(default)]
         duration: Option<u64>,
+        #[serde(default)]
+        origin: Option<PointerOrigin>,
     },
     #[serde(rename = "pause")]
     Pause { duration: Option<u64> },
@@ -95,178 +130,552 @@ pub enum PauseAction {
     Pause { duration: Option<u64> },
 }
 
-/// Current pointer position for actions
-struct PointerState {
+impl ActionSequence {
+    fn id(&self) -> &str {
+        match self {
+            Self::Key { id, .. }
+            | Self::Pointer { id, .. }
+            | Self::Wheel { id, .. }
+            | Self::None { id, .. } => id,
+        }
+    }
+
+    fn source_type(&self) -> InputSourceType {
+        match self {
+            Self::Key { .. } => InputSourceType::Key,
+            Self::Pointer { parameters, .. } => InputSourceType::Pointer(parameters.pointer_type),
+            Self::Wheel { .. } => InputSourceType::Wheel,
+            Self::None { .. } => InputSourceType::None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Key { actions, .. } => actions.len(),
+            Self::Pointer { actions, .. } => actions.len(),
+            Self::Wheel { actions, .. } => actions.len(),
+            Self::None { actions, .. } => actions.len(),
+        }
+    }
+
+    /// Action this source performs in the given tick, if its sequence is long enough
+    fn tick_action(&self, tick: usize) -> Option<TickAction<'_>> {
+        match self {
+            Self::Key { actions, .. } => actions.get(tick).map(TickAction::Key),
+            Self::Pointer { actions, .. } => actions.get(tick).map(TickAction::Pointer),
+            Self::Wheel { actions, .. } => actions.get(tick).map(TickAction::Wheel),
+            Self::None { actions, .. } => actions
+                .get(tick)
+                .map(|PauseAction::Pause { duration }| TickAction::Pause(*duration)),
+        }
+    }
+}
+
+/// One input source's action within a tick
+enum TickAction<'a> {
+    Key(&'a KeyAction),
+    Pointer(&'a PointerAction),
+    Wheel(&'a WheelAction),
+    Pause(Option<u64>),
+}
+
+impl TickAction<'_> {
+    /// Milliseconds the action occupies within its tick
+    fn duration(&self) -> u64 {
+        match self {
+            Self::Key(KeyAction::Pause { duration })
+            | Self::Pointer(
+                PointerAction::Pause { duration } | PointerAction::PointerMove { duration, .. },
+            )
+            | Self::Wheel(WheelAction::Pause { duration } | WheelAction::Scroll { duration, .. })
+            | Self::Pause(duration) => duration.unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Whether the action takes effect at the end of its duration rather than the start of the tick
+    fn is_timed(&self) -> bool {
+        matches!(
+            self,
+            Self::Pointer(PointerAction::PointerMove { .. })
+                | Self::Wheel(WheelAction::Scroll { .. })
+        )
+    }
+}
+
+/// Interval between intermediate pointer move events (one frame at 60 Hz)
+const POINTER_MOVE_INTERVAL_MS: u64 = 16;
+
+/// Straight-line path of a pointer move with a duration
+struct PointerPath<'a> {
+    source_id: &'a str,
+    start: (i32, i32),
+    target: (i32, i32),
+    duration: u64,
+    properties: PointerProperties,
+}
+
+impl PointerPath<'_> {
+    /// Position along the path once `elapsed` of the move's duration has passed
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn position_at(&self, elapsed: Duration) -> (i32, i32) {
+        if self.duration == 0 {
+            return self.target;
+        }
+        let progress = (elapsed.as_millis() as f64 / self.duration as f64).min(1.0);
+        let step = |from: i32, to: i32| from + (f64::from(to - from) * progress).round() as i32;
+        (
+            step(self.start.0, self.target.0),
+            step(self.start.1, self.target.1),
+        )
+    }
+}
+
+/// Wheel scroll resolved to viewport coordinates
+struct Scroll {
     x: i32,
     y: i32,
+    delta_x: i32,
+    delta_y: i32,
+    duration: u64,
+}
+
+/// Delivers input as synthesized DOM events, or through the OS when the session
+/// asked for native input
+struct InputBackend<'a, R: Runtime> {
+    executor: &'a dyn PlatformExecutor<R>,
+    native: bool,
+}
+
+impl<R: Runtime + 'static> InputBackend<'_, R> {
+    async fn key(
+        &self,
+        key: &str,
+        is_down: bool,
+        modifiers: &ModifierState,
+    ) -> Result<(), WebDriverErrorResponse> {
+        if self.native {
+            self.executor
+                .dispatch_native_key_event(key, is_down, modifiers)
+                .await
+        } else {
+            self.executor
+                .dispatch_key_event(key, is_down, modifiers)
+                .await
+        }
+    }
+
+    async fn pointer(
+        &self,
+        event_type: PointerEventType,
+        (x, y): (i32, i32),
+        button: u32,
+        pointer: &PointerInput,
+    ) -> Result<(), WebDriverErrorResponse> {
+        if self.native {
+            if !matches!(pointer.pointer_type, PointerType::Mouse) {
+                return Err(WebDriverErrorResponse::unsupported_operation(
+                    "Native input only supports mouse pointers",
+                ));
+            }
+            self.executor
+                .dispatch_native_pointer_event(event_type, x, y, button, pointer)
+                .await
+        } else {
+            self.executor
+                .dispatch_pointer_event(event_type, x, y, button, pointer)
+                .await
+        }
+    }
+
+    async fn scroll(&self, scroll: &Scroll) -> Result<(), WebDriverErrorResponse> {
+        let Scroll {
+            x,
+            y,
+            delta_x,
+            delta_y,
+            ..
+        } = *scroll;
+        if self.native {
+            self.executor
+                .dispatch_native_scroll_event(x, y, delta_x, delta_y)
+                .await
+        } else {
+            self.executor
+                .dispatch_scroll_event(x, y, delta_x, delta_y)
+                .await
+        }
+    }
+
+    /// Update hover state; the OS does this itself for native input
+    async fn hover(&self, source_id: &str, x: i32, y: i32) -> Result<(), WebDriverErrorResponse> {
+        if self.native {
+            Ok(())
+        } else {
+            self.executor.update_hover(source_id, x, y).await
+        }
+    }
 }
 
 /// POST `/session/{session_id}/actions` - Perform actions
-#[allow(clippy::too_many_lines)]
 pub async fn perform<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
     Json(request): Json<ActionsRequest>,
 ) -> WebDriverResult {
-    // Get session info and executor first
-    let (current_window, timeouts, frame_context) = {
-        let sessions = state.sessions.read().await;
-        let session = sessions.get(&session_id)?;
+    // Register input sources and take the session's input state
+    let (current_window, timeouts, frame_context, native, hidden, mut input_state) = {
+        let mut sessions = state.sessions.write().await;
+        let session = sessions.get_mut(&session_id)?;
+        let mut seen = HashSet::new();
+        for sequence in &request.actions {
+            if !seen.insert(sequence.id()) {
+                return Err(WebDriverErrorResponse::invalid_argument(&format!(
+                    "Duplicate input source id: {}",
+                    sequence.id()
+                )));
+            }
+            session
+                .action_state
+                .register_source(sequence.id(), sequence.source_type())?;
+        }
         (
             session.current_window.clone(),
             session.timeouts.clone(),
             session.frame_context.clone(),
+            session.native_input,
+            session.hidden_windows,
+            session.action_state.clone(),
         )
     };
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let mut pointer_state = PointerState { x: 0, y: 0 };
-    let mut modifier_state = ModifierState::default();
-
-    for action_seq in &request.actions {
-        match action_seq {
-            ActionSequence::Key { _id: _, actions } => {
-                for action in actions {
-                    match action {
-                        KeyAction::KeyDown { value } => {
-                            modifier_state.update(value, true);
-                            executor
-                                .dispatch_key_event(value, true, &modifier_state)
-                                .await?;
-                            // Track pressed key
-                            let mut sessions = state.sessions.write().await;
-                            if let Ok(session) = sessions.get_mut(&session_id) {
-                                session.action_state.pressed_keys.insert(value.clone());
-                            }
-                        }
-                        KeyAction::KeyUp { value } => {
-                            executor
-                                .dispatch_key_event(value, false, &modifier_state)
-                                .await?;
-                            modifier_state.update(value, false);
-                            // Remove from tracked keys
-                            let mut sessions = state.sessions.write().await;
-                            if let Ok(session) = sessions.get_mut(&session_id) {
-                                session.action_state.pressed_keys.remove(value);
-                            }
-                        }
-                        KeyAction::Pause { duration } => {
-                            if let Some(ms) = duration {
-                                tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                            }
-                        }
-                    }
-                }
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+
+    // Synthesized input on an unfocused window is dropped on some platforms
+    if !hidden {
+        if let Err(e) = executor.focus_window().await {
+            tracing::warn!("Failed to focus window before actions: {}", e.message);
+        }
+    }
+
+    let input = InputBackend {
+        executor: executor.as_ref(),
+        native,
+    };
+    let result = dispatch_ticks(
+        &state,
+        &session_id,
+        &input,
+        &request.actions,
+        &mut input_state,
+    )
+    .await;
+
+    // Keep the input state even when a dispatch failed, so Release Actions can undo it
+    let mut sessions = state.sessions.write().await;
+    if let Ok(session) = sessions.get_mut(&session_id) {
+        session.action_state = input_state;
+    }
+
+    result.map(|()| WebDriverResponse::null())
+}
+
+/// Dispatch action sequences tick by tick. Each tick runs one action from every source
+/// and lasts as long as its longest action, so sources stay synchronized.
+async fn dispatch_ticks<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    input: &InputBackend<'_, R>,
+    sequences: &[ActionSequence],
+    input_state: &mut ActionState,
+) -> Result<(), WebDriverErrorResponse> {
+    let tick_count = sequences.iter().map(ActionSequence::len).max().unwrap_or(0);
+
+    for tick in 0..tick_count {
+        let started = Instant::now();
+        let actions: Vec<(&str, TickAction<'_>)> = sequences
+            .iter()
+            .filter_map(|sequence| {
+                sequence
+                    .tick_action(tick)
+                    .map(|action| (sequence.id(), action))
+            })
+            .collect();
+        let tick_duration = actions.iter().map(|(_, action)| action.duration()).max();
+
+        // Key and button presses happen at the start of the tick
+        let (timed, immediate): (Vec<_>, Vec<_>) = actions
+            .into_iter()
+            .partition(|(_, action)| action.is_timed());
+        for (source_id, action) in immediate {
+            dispatch_action(input, source_id, &action, input_state).await?;
+        }
+
+        // Pointer moves start from the position at the beginning of the tick
+        let mut paths = Vec::new();
+        let mut scrolls = Vec::new();
+        for (source_id, action) in timed {
+            if let TickAction::Pointer(PointerAction::PointerMove {
+                x,
+                y,
+                origin,
+                duration,
+                properties,
+            }) = action
+            {
+                let target = resolve_move_target(
+                    state,
+                    session_id,
+                    input.executor,
+                    source_id,
+                    (*x, *y),
+                    origin.as_ref(),
+                    input_state,
+                )
+                .await?;
+                paths.push(PointerPath {
+                    source_id,
+                    start: pointer_position(input_state, source_id),
+                    target,
+                    duration: duration.unwrap_or(0),
+                    properties: *properties,
+                });
+            } else if let TickAction::Wheel(WheelAction::Scroll {
+                x,
+                y,
+                delta_x,
+                delta_y,
+                duration,
+                origin,
+            }) = action
+            {
+                let (origin_x, origin_y) =
+                    resolve_scroll_origin(state, session_id, input.executor, origin.as_ref())
+                        .await?;
+                scrolls.push(Scroll {
+                    x: origin_x + *x,
+                    y: origin_y + *y,
+                    delta_x: *delta_x,
+                    delta_y: *delta_y,
+                    duration: duration.unwrap_or(0),
+                });
             }
-            ActionSequence::Pointer { id, actions } => {
-                for action in actions {
-                    match action {
-                        PointerAction::PointerDown { button } => {
-                            executor
-                                .dispatch_pointer_event(
-                                    PointerEventType::Down,
-                                    pointer_state.x,
-                                    pointer_state.y,
-                                    *button,
-                                )
-                                .await?;
-                            // Track pressed button
-                            let mut sessions = state.sessions.write().await;
-                            if let Ok(session) = sessions.get_mut(&session_id) {
-                                session
-                                    .action_state
-                                    .pressed_buttons
-                                    .entry(id.clone())
-                                    .or_default()
-                                    .insert(*button);
-                            }
-                        }
-                        PointerAction::PointerUp { button } => {
-                            executor
-                                .dispatch_pointer_event(
-                                    PointerEventType::Up,
-                                    pointer_state.x,
-                                    pointer_state.y,
-                                    *button,
-                                )
-                                .await?;
-                            // Remove from tracked buttons
-                            let mut sessions = state.sessions.write().await;
-                            if let Ok(session) = sessions.get_mut(&session_id) {
-                                if let Some(buttons) =
-                                    session.action_state.pressed_buttons.get_mut(id)
-                                {
-                                    buttons.remove(button);
-                                }
-                            }
-                        }
-                        PointerAction::PointerMove { x, y, duration } => {
-                            pointer_state.x = *x;
-                            pointer_state.y = *y;
-                            if let Some(ms) = duration {
-                                if *ms > 0 {
-                                    tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                                }
-                            }
-                            executor
-                                .dispatch_pointer_event(
-                                    PointerEventType::Move,
-                                    pointer_state.x,
-                                    pointer_state.y,
-                                    0,
-                                )
-                                .await?;
-                        }
-                        PointerAction::Pause { duration } => {
-                            if let Some(ms) = duration {
-                                tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                            }
-                        }
-                    }
+        }
+        scrolls.sort_by_key(|scroll| scroll.duration);
+
+        // Step every moving pointer along its path, and dispatch scrolls as their
+        // durations elapse, until all timed actions of the tick are done
+        loop {
+            let elapsed = started.elapsed();
+            for path in &paths {
+                let (x, y) = path.position_at(elapsed);
+                if pointer_position(input_state, path.source_id) != (x, y) {
+                    move_pointer(input, path.source_id, x, y, path.properties, input_state).await?;
                 }
             }
-            ActionSequence::Wheel { _id: _, actions } => {
-                for action in actions {
-                    match action {
-                        WheelAction::Scroll {
-                            x,
-                            y,
-                            delta_x,
-                            delta_y,
-                            duration,
-                        } => {
-                            if let Some(ms) = duration {
-                                if *ms > 0 {
-                                    tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                                }
-                            }
-                            executor
-                                .dispatch_scroll_event(*x, *y, *delta_x, *delta_y)
-                                .await?;
-                        }
-                        WheelAction::Pause { duration } => {
-                            if let Some(ms) = duration {
-                                tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                            }
-                        }
-                    }
+            paths.retain(|path| elapsed < Duration::from_millis(path.duration));
+
+            while let Some(scroll) = scrolls.first() {
+                if elapsed < Duration::from_millis(scroll.duration) {
+                    break;
                 }
+                input.scroll(scroll).await?;
+                scrolls.remove(0);
             }
-            ActionSequence::None { _id: _, actions } => {
-                for action in actions {
-                    match action {
-                        PauseAction::Pause { duration } => {
-                            if let Some(ms) = duration {
-                                tokio::time::sleep(std::time::Duration::from_millis(*ms)).await;
-                            }
-                        }
-                    }
-                }
+
+            if paths.is_empty() && scrolls.is_empty() {
+                break;
             }
+            tokio::time::sleep(Duration::from_millis(POINTER_MOVE_INTERVAL_MS)).await;
+        }
+
+        if let Some(ms) = tick_duration {
+            sleep_until(started + Duration::from_millis(ms)).await;
         }
     }
 
-    Ok(WebDriverResponse::null())
+    Ok(())
+}
+
+/// Dispatch a single action and update the input state accordingly
+async fn dispatch_action<R: Runtime + 'static>(
+    input: &InputBackend<'_, R>,
+    source_id: &str,
+    action: &TickAction<'_>,
+    input_state: &mut ActionState,
+) -> Result<(), WebDriverErrorResponse> {
+    match action {
+        TickAction::Key(KeyAction::KeyDown { value }) => {
+            input_state.modifiers.update(value, true);
+            input.key(value, true, &input_state.modifiers).await?;
+            input_state.pressed_keys.insert(value.clone());
+        }
+        TickAction::Key(KeyAction::KeyUp { value }) => {
+            input.key(value, false, &input_state.modifiers).await?;
+            input_state.modifiers.update(value, false);
+            input_state.pressed_keys.remove(value);
+        }
+        TickAction::Pointer(PointerAction::PointerDown { button, properties }) => {
+            let (x, y) = pointer_position(input_state, source_id);
+            input_state.register_press(source_id, *button, (x, y));
+            let pointer = pointer_input(input_state, source_id, *properties);
+            input
+                .pointer(PointerEventType::Down, (x, y), *button, &pointer)
+                .await?;
+            input_state
+                .pressed_buttons
+                .entry(source_id.to_string())
+                .or_default()
+                .insert(*button);
+        }
+        TickAction::Pointer(PointerAction::PointerUp { button, properties }) => {
+            let (x, y) = pointer_position(input_state, source_id);
+            let pointer = pointer_input(input_state, source_id, *properties);
+            input
+                .pointer(PointerEventType::Up, (x, y), *button, &pointer)
+                .await?;
+            if let Some(buttons) = input_state.pressed_buttons.get_mut(source_id) {
+                buttons.remove(button);
+            }
+        }
+        // Moves and scrolls are dispatched by the tick loop, pauses only contribute their duration
+        TickAction::Pointer(PointerAction::PointerMove { .. } | PointerAction::Pause { .. })
+        | TickAction::Key(KeyAction::Pause { .. })
+        | TickAction::Wheel(WheelAction::Scroll { .. } | WheelAction::Pause { .. })
+        | TickAction::Pause(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Resolve the viewport coordinates a pointer move ends at, which must be in view
+async fn resolve_move_target<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    executor: &dyn PlatformExecutor<R>,
+    source_id: &str,
+    (x, y): (i32, i32),
+    origin: Option<&PointerOrigin>,
+    input_state: &ActionState,
+) -> Result<(i32, i32), WebDriverErrorResponse> {
+    let (origin_x, origin_y) = match origin {
+        Some(PointerOrigin::Element { id }) => {
+            element_origin(state, session_id, executor, id).await?
+        }
+        Some(PointerOrigin::Named(name)) if name == "pointer" => {
+            pointer_position(input_state, source_id)
+        }
+        Some(PointerOrigin::Named(name)) if name != "viewport" => {
+            return Err(WebDriverErrorResponse::invalid_argument(&format!(
+                "Unknown pointer origin: {name}"
+            )));
+        }
+        _ => (0, 0),
+    };
+    let (target_x, target_y) = (origin_x + x, origin_y + y);
+
+    let (width, height) = executor.get_viewport_size().await?;
+    if target_x < 0 || target_y < 0 || target_x >= width || target_y >= height {
+        return Err(WebDriverErrorResponse::move_target_out_of_bounds(&format!(
+            "Move target ({target_x}, {target_y}) is outside the viewport"
+        )));
+    }
+    Ok((target_x, target_y))
+}
+
+/// Resolve the viewport coordinates scroll offsets are relative to
+async fn resolve_scroll_origin<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    executor: &dyn PlatformExecutor<R>,
+    origin: Option<&PointerOrigin>,
+) -> Result<(i32, i32), WebDriverErrorResponse> {
+    match origin {
+        Some(PointerOrigin::Element { id }) => {
+            element_origin(state, session_id, executor, id).await
+        }
+        Some(PointerOrigin::Named(name)) if name != "viewport" => Err(
+            WebDriverErrorResponse::invalid_argument(&format!("Unknown scroll origin: {name}")),
+        ),
+        _ => Ok((0, 0)),
+    }
+}
+
+/// In-view center point of an element used as an action origin
+async fn element_origin<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    executor: &dyn PlatformExecutor<R>,
+    element_id: &str,
+) -> Result<(i32, i32), WebDriverErrorResponse> {
+    let js_var = {
+        let sessions = state.sessions.read().await;
+        let session = sessions.get(session_id)?;
+        session
+            .elements
+            .lookup(element_id, &session.frame_context)?
+            .id
+            .clone()
+    };
+    executor.get_element_in_view_center(&js_var).await
+}
+
+/// Move a pointer source to a viewport position, updating hover state
+async fn move_pointer<R: Runtime + 'static>(
+    input: &InputBackend<'_, R>,
+    source_id: &str,
+    x: i32,
+    y: i32,
+    properties: PointerProperties,
+    input_state: &mut ActionState,
+) -> Result<(), WebDriverErrorResponse> {
+    input_state
+        .pointer_positions
+        .insert(source_id.to_string(), (x, y));
+    let pointer = pointer_input(input_state, source_id, properties);
+    // Touch points only exist while in contact, so they never hover
+    if pointer.pointer_type != PointerType::Touch {
+        input.hover(source_id, x, y).await?;
+    }
+    input
+        .pointer(PointerEventType::Move, (x, y), 0, &pointer)
+        .await
+}
+
+/// Pointer state of a source for dispatching an event with the given properties
+fn pointer_input(
+    input_state: &ActionState,
+    source_id: &str,
+    properties: PointerProperties,
+) -> PointerInput {
+    PointerInput {
+        pointer_id: input_state
+            .pointer_ids
+            .get(source_id)
+            .copied()
+            .unwrap_or_default(),
+        pointer_type: input_state.pointer_type(source_id),
+        properties,
+        click_count: input_state.click_count(source_id),
+        modifiers: input_state.modifiers,
+        buttons: input_state
+            .pressed_buttons
+            .get(source_id)
+            .map_or(0, |buttons| {
+                buttons.iter().fold(0, |mask, button| mask | 1 << button)
+            }),
+    }
+}
+
+/// Current position of a pointer source, starting at the viewport origin
+fn pointer_position(input_state: &ActionState, source_id: &str) -> (i32, i32) {
+    input_state
+        .pointer_positions
+        .get(source_id)
+        .copied()
+        .unwrap_or((0, 0))
 }
 
 /// DELETE `/session/{session_id}/actions` - Release actions
@@ -274,39 +683,54 @@ pub async fn release<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
-    // Get session state and clear tracked actions
-    let (current_window, timeouts, frame_context, pressed_keys, pressed_buttons) = {
+    // Reset the session's input state, keeping the old one to undo
+    let (current_window, timeouts, frame_context, native, mut input_state) = {
         let mut sessions = state.sessions.write().await;
         let session = sessions.get_mut(&session_id)?;
-        let pressed_keys: Vec<String> = session.action_state.pressed_keys.drain().collect();
-        let pressed_buttons = std::mem::take(&mut session.action_state.pressed_buttons);
         (
             session.current_window.clone(),
             session.timeouts.clone(),
             session.frame_context.clone(),
-            pressed_keys,
-            pressed_buttons,
+            session.native_input,
+            std::mem::take(&mut session.action_state),
         )
     };
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let modifier_state = ModifierState::default();
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let input = InputBackend {
+        executor: executor.as_ref(),
+        native,
+    };
 
-    // Release all pressed keys (keyUp events)
-    for key in pressed_keys {
-        executor
-            .dispatch_key_event(&key, false, &modifier_state)
-            .await?;
+    // Release ordinary keys while modifiers are still held, then the modifiers
+    let (modifiers, keys): (Vec<String>, Vec<String>) =
+        input_state.pressed_keys.drain().partition(|key| {
+            let mut chars = key.chars();
+            matches!((chars.next(), chars.next()), (Some(ch), None) if is_modifier_key(ch))
+        });
+    let mut first_error = None;
+    for key in keys.into_iter().chain(modifiers) {
+        let result = input.key(&key, false, &input_state.modifiers).await;
+        input_state.modifiers.update(&key, false);
+        first_error = first_error.or(result.err());
     }
 
-    // Release all pressed pointer buttons (pointerUp events)
-    for (_source_id, buttons) in pressed_buttons {
+    // Release pressed pointer buttons where each pointer currently is
+    for (source_id, buttons) in &input_state.pressed_buttons {
+        let (x, y) = pointer_position(&input_state, source_id);
+        let pointer = pointer_input(&input_state, source_id, PointerProperties::default());
         for button in buttons {
-            executor
-                .dispatch_pointer_event(PointerEventType::Up, 0, 0, button)
-                .await?;
+            let result = input
+                .pointer(PointerEventType::Up, (x, y), *button, &pointer)
+                .await;
+            first_error = first_error.or(result.err());
         }
     }
 
-    Ok(WebDriverResponse::null())
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(WebDriverResponse::null()),
+    }
 }
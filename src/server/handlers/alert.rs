@@ -25,7 +25,7 @@ pub async fn dismiss<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state.get_executor_during_prompt(&current_window, timeouts, frame_context)?;
     executor.dismiss_alert().await?;
 
     Ok(WebDriverResponse::null())
@@ -43,7 +43,7 @@ pub async fn accept<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state.get_executor_during_prompt(&current_window, timeouts, frame_context)?;
     executor.accept_alert().await?;
 
     Ok(WebDriverResponse::null())
@@ -61,7 +61,7 @@ pub async fn get_text<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state.get_executor_during_prompt(&current_window, timeouts, frame_context)?;
     let text: String = executor.get_alert_text().await?;
 
     Ok(WebDriverResponse::success(text))
@@ -80,7 +80,7 @@ pub async fn send_text<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state.get_executor_during_prompt(&current_window, timeouts, frame_context)?;
     executor.send_alert_text(&request.text).await?;
 
     Ok(WebDriverResponse::null())
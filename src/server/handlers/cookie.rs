@@ -1,17 +1,18 @@
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
 use tauri::Runtime;
 
-use crate::platform::Cookie;
 use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::cookie::{validate_cookie, NewCookie};
 
 #[derive(Debug, Deserialize)]
 pub struct AddCookieRequest {
-    pub cookie: Cookie,
+    pub cookie: NewCookie,
 }
 
 /// GET `/session/{session_id}/cookie` - Get all cookies
@@ -26,7 +27,9 @@ pub async fn get_all<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let cookies = executor.get_all_cookies().await?;
 
     Ok(WebDriverResponse::success(cookies))
@@ -44,7 +47,9 @@ pub async fn get<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let cookie = executor.get_cookie(&name).await?;
 
     match cookie {
@@ -66,8 +71,19 @@ pub async fn add<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    executor.add_cookie(request.cookie).await?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let url = executor.webview().url().ok();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let cookie = validate_cookie(
+        request.cookie,
+        url.as_ref().and_then(|url| url.host_str()),
+        now,
+    )?;
+    executor.add_cookie(cookie).await?;
 
     Ok(WebDriverResponse::null())
 }
@@ -84,7 +100,9 @@ pub async fn delete<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.delete_cookie(&name).await?;
 
     Ok(WebDriverResponse::null())
@@ -102,7 +120,9 @@ pub async fn delete_all<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.delete_all_cookies().await?;
 
     Ok(WebDriverResponse::null())
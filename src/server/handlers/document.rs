@@ -18,7 +18,9 @@ pub async fn get_source<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let source = executor.get_source().await?;
     Ok(WebDriverResponse::success(source))
 }
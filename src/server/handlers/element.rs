@@ -3,12 +3,15 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
-use serde_json::json;
+use serde_json::{json, Value};
 use tauri::Runtime;
 
+use crate::server::handlers::screenshot::to_css_pixels;
 use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::element::OptionSelector;
 use crate::webdriver::locator::LocatorStrategy;
+use crate::webdriver::session::Session;
 
 #[derive(Debug, Deserialize)]
 pub struct FindElementRequest {
@@ -21,42 +24,49 @@ pub struct SendKeysRequest {
     pub text: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SelectOptionRequest {
+    pub value: Option<String>,
+    pub label: Option<String>,
+    pub index: Option<usize>,
+}
+
 /// POST `/session/{session_id}/element` - Find element
 pub async fn find<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
     Json(request): Json<FindElementRequest>,
 ) -> WebDriverResult {
-    let mut sessions = state.sessions.write().await;
-    let session = sessions.get_mut(&session_id)?;
-
-    let strategy = LocatorStrategy::from_string(&request.using).ok_or_else(|| {
-        WebDriverErrorResponse::invalid_argument(&format!(
-            "Unknown locator strategy: {}",
-            request.using
-        ))
-    })?;
-
-    // Store element reference and get ID
-    let element_ref = session.elements.store();
-    let js_var = element_ref.js_ref.clone();
-    let element_id = element_ref.id.clone();
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
+    let strategy =
+        LocatorStrategy::from_string(&request.using, &state.locators).ok_or_else(|| {
+            WebDriverErrorResponse::invalid_argument(&format!(
+                "Unknown locator strategy: {}",
+                request.using
+            ))
+        })?;
+
     let strategy_js = strategy.to_selector_js(&request.value);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let found = executor.find_element(&strategy_js, &js_var).await?;
-    if !found {
-        return Err(WebDriverErrorResponse::no_such_element());
-    }
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let id = executor
+        .find_element(&strategy_js)
+        .await?
+        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
 
-    Ok(WebDriverResponse::success(json!({
-        "element-6066-11e4-a52e-4f735466cecf": element_id
-    })))
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    let mut elements = adopt_found_elements(session, &[id]);
+
+    Ok(WebDriverResponse::success(elements.remove(0)))
 }
 
 /// POST `/session/{session_id}/elements` - Find multiple elements
@@ -72,44 +82,40 @@ pub async fn find_all<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let strategy = LocatorStrategy::from_string(&request.using).ok_or_else(|| {
-        WebDriverErrorResponse::invalid_argument(&format!(
-            "Unknown locator strategy: {}",
-            request.using
-        ))
-    })?;
+    let strategy =
+        LocatorStrategy::from_string(&request.using, &state.locators).ok_or_else(|| {
+            WebDriverErrorResponse::invalid_argument(&format!(
+                "Unknown locator strategy: {}",
+                request.using
+            ))
+        })?;
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let strategy_js = strategy.to_selector_js_multiple(&request.value);
 
-    // Use a temporary prefix for the trait method
-    let temp_prefix = "__wd_temp_";
-    let count = executor.find_elements(&strategy_js, temp_prefix).await?;
+    let ids = executor.find_elements(&strategy_js).await?;
 
-    // Now store each element with proper references
-    let mut elements = Vec::new();
     let mut sessions = state.sessions.write().await;
     let session = sessions.get_mut(&session_id)?;
-
-    for i in 0..count {
-        let element_ref = session.elements.store();
-        let js_var = element_ref.js_ref.clone();
-        let element_id = element_ref.id.clone();
-
-        // Copy from temp storage to element's js_ref
-        let copy_script = format!(
-            "(function() {{ window.{js_var} = window['{temp_prefix}{i}'];  return true; }})()"
-        );
-        let _ = executor.evaluate_js(&copy_script).await;
-
-        elements.push(json!({
-            "element-6066-11e4-a52e-4f735466cecf": element_id
-        }));
-    }
+    let elements = adopt_found_elements(session, &ids);
 
     Ok(WebDriverResponse::success(elements))
 }
 
+/// Register elements a find script stored under `ids` and build their web element references
+pub fn adopt_found_elements(session: &mut Session, ids: &[String]) -> Vec<Value> {
+    ids.iter()
+        .map(|id| {
+            session.elements.adopt(id, &session.frame_context);
+            json!({
+                "element-6066-11e4-a52e-4f735466cecf": id
+            })
+        })
+        .collect()
+}
+
 /// POST `/session/{session_id}/element/{element_id}/click` - Click element
 pub async fn click<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
@@ -120,16 +126,17 @@ pub async fn click<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.click_element(&js_var).await?;
 
     Ok(WebDriverResponse::null())
@@ -145,16 +152,17 @@ pub async fn clear<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.clear_element(&js_var).await?;
 
     Ok(WebDriverResponse::null())
@@ -171,16 +179,17 @@ pub async fn send_keys<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor
         .send_keys_to_element(&js_var, &request.text)
         .await?;
@@ -188,6 +197,45 @@ pub async fn send_keys<R: Runtime + 'static>(
     Ok(WebDriverResponse::null())
 }
 
+/// POST `/session/{session_id}/element/{element_id}/tauri/select` - Select an option of a
+/// `<select>` (extension)
+pub async fn select_option<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, element_id)): Path<(String, String)>,
+    Json(request): Json<SelectOptionRequest>,
+) -> WebDriverResult {
+    let selector = match (request.value, request.label, request.index) {
+        (Some(value), None, None) => OptionSelector::Value(value),
+        (None, Some(label), None) => OptionSelector::Label(label),
+        (None, None, Some(index)) => OptionSelector::Index(index),
+        _ => {
+            return Err(WebDriverErrorResponse::invalid_argument(
+                "Exactly one of value, label or index must be given",
+            ))
+        }
+    };
+
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+
+    let element = session
+        .elements
+        .lookup(&element_id, &session.frame_context)?;
+
+    let js_var = element.id.clone();
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    executor.select_option(&js_var, &selector).await?;
+
+    Ok(WebDriverResponse::null())
+}
+
 /// GET `/session/{session_id}/element/{element_id}/text` - Get element text
 pub async fn get_text<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
@@ -198,16 +246,17 @@ pub async fn get_text<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let text = executor.get_element_text(&js_var).await?;
     Ok(WebDriverResponse::success(text))
 }
@@ -222,16 +271,17 @@ pub async fn get_tag_name<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let tag_name = executor.get_element_tag_name(&js_var).await?;
     Ok(WebDriverResponse::success(tag_name))
 }
@@ -246,16 +296,17 @@ pub async fn get_attribute<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let attr = executor.get_element_attribute(&js_var, &name).await?;
     Ok(WebDriverResponse::success(attr))
 }
@@ -270,16 +321,17 @@ pub async fn get_property<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let prop = executor.get_element_property(&js_var, &name).await?;
     Ok(WebDriverResponse::success(prop))
 }
@@ -294,16 +346,17 @@ pub async fn is_displayed<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let displayed = executor.is_element_displayed(&js_var).await?;
     Ok(WebDriverResponse::success(displayed))
 }
@@ -318,16 +371,17 @@ pub async fn is_enabled<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let enabled = executor.is_element_enabled(&js_var).await?;
     Ok(WebDriverResponse::success(enabled))
 }
@@ -337,27 +391,26 @@ pub async fn get_active<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
-    let mut sessions = state.sessions.write().await;
-    let session = sessions.get_mut(&session_id)?;
-
-    // Store element reference for the active element
-    let element_ref = session.elements.store();
-    let js_var = element_ref.js_ref.clone();
-    let element_id = element_ref.id.clone();
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let found = executor.get_active_element(&js_var).await?;
-    if !found {
-        return Err(WebDriverErrorResponse::no_such_element());
-    }
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let id = executor
+        .get_active_element()
+        .await?
+        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
 
-    Ok(WebDriverResponse::success(json!({
-        "element-6066-11e4-a52e-4f735466cecf": element_id
-    })))
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    let mut elements = adopt_found_elements(session, &[id]);
+
+    Ok(WebDriverResponse::success(elements.remove(0)))
 }
 
 /// POST `/session/{session_id}/element/{element_id}/element` - Find element from element
@@ -366,26 +419,22 @@ pub async fn find_from_element<R: Runtime + 'static>(
     Path((session_id, parent_element_id)): Path<(String, String)>,
     Json(request): Json<FindElementRequest>,
 ) -> WebDriverResult {
-    let mut sessions = state.sessions.write().await;
-    let session = sessions.get_mut(&session_id)?;
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
 
     let parent_element = session
         .elements
-        .get(&parent_element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
-    let parent_js_var = parent_element.js_ref.clone();
-
-    let strategy = LocatorStrategy::from_string(&request.using).ok_or_else(|| {
-        WebDriverErrorResponse::invalid_argument(&format!(
-            "Unknown locator strategy: {}",
-            request.using
-        ))
-    })?;
-
-    // Store element reference and get ID
-    let element_ref = session.elements.store();
-    let js_var = element_ref.js_ref.clone();
-    let element_id = element_ref.id.clone();
+        .lookup(&parent_element_id, &session.frame_context)?;
+    let parent_js_var = parent_element.id.clone();
+
+    let strategy =
+        LocatorStrategy::from_string(&request.using, &state.locators).ok_or_else(|| {
+            WebDriverErrorResponse::invalid_argument(&format!(
+                "Unknown locator strategy: {}",
+                request.using
+            ))
+        })?;
+
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
@@ -394,17 +443,19 @@ pub async fn find_from_element<R: Runtime + 'static>(
     // Use the locator method that generates expressions expecting `parent` to be defined
     let strategy_js = strategy.to_selector_js_single_from_element(&request.value);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let found = executor
-        .find_element_from_element(&parent_js_var, &strategy_js, &js_var)
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
         .await?;
-    if !found {
-        return Err(WebDriverErrorResponse::no_such_element());
-    }
+    let id = executor
+        .find_element_from_element(&parent_js_var, &strategy_js)
+        .await?
+        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
 
-    Ok(WebDriverResponse::success(json!({
-        "element-6066-11e4-a52e-4f735466cecf": element_id
-    })))
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    let mut elements = adopt_found_elements(session, &[id]);
+
+    Ok(WebDriverResponse::success(elements.remove(0)))
 }
 
 /// POST `/session/{session_id}/element/{element_id}/elements` - Find elements from element
@@ -418,50 +469,33 @@ pub async fn find_all_from_element<R: Runtime + 'static>(
 
     let parent_element = session
         .elements
-        .get(&parent_element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
-    let parent_js_var = parent_element.js_ref.clone();
+        .lookup(&parent_element_id, &session.frame_context)?;
+    let parent_js_var = parent_element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let strategy = LocatorStrategy::from_string(&request.using).ok_or_else(|| {
-        WebDriverErrorResponse::invalid_argument(&format!(
-            "Unknown locator strategy: {}",
-            request.using
-        ))
-    })?;
+    let strategy =
+        LocatorStrategy::from_string(&request.using, &state.locators).ok_or_else(|| {
+            WebDriverErrorResponse::invalid_argument(&format!(
+                "Unknown locator strategy: {}",
+                request.using
+            ))
+        })?;
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let strategy_js = strategy.to_selector_js_from_element(&request.value);
 
-    // Use a temporary prefix for the trait method
-    let temp_prefix = "__wd_temp_";
-    let count = executor
-        .find_elements_from_element(&parent_js_var, &strategy_js, temp_prefix)
+    let ids = executor
+        .find_elements_from_element(&parent_js_var, &strategy_js)
         .await?;
 
-    // Now store each element with proper references
-    let mut elements = Vec::new();
     let mut sessions = state.sessions.write().await;
     let session = sessions.get_mut(&session_id)?;
-
-    for i in 0..count {
-        let element_ref = session.elements.store();
-        let js_var = element_ref.js_ref.clone();
-        let element_id = element_ref.id.clone();
-
-        // Copy from temp storage to element's js_ref
-        let copy_script = format!(
-            "(function() {{ window.{js_var} = window['{temp_prefix}{i}'];  return true; }})()"
-        );
-        let _ = executor.evaluate_js(&copy_script).await;
-
-        elements.push(json!({
-            "element-6066-11e4-a52e-4f735466cecf": element_id
-        }));
-    }
+    let elements = adopt_found_elements(session, &ids);
 
     Ok(WebDriverResponse::success(elements))
 }
@@ -476,16 +510,17 @@ pub async fn is_selected<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let selected = executor.is_element_selected(&js_var).await?;
     Ok(WebDriverResponse::success(selected))
 }
@@ -500,16 +535,17 @@ pub async fn get_css_value<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let value = executor
         .get_element_css_value(&js_var, &property_name)
         .await?;
@@ -526,16 +562,17 @@ pub async fn get_rect<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let rect = executor.get_element_rect(&js_var).await?;
     Ok(WebDriverResponse::success(json!({
         "x": rect.x,
@@ -555,16 +592,17 @@ pub async fn get_computed_role<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let role = executor.get_element_computed_role(&js_var).await?;
     Ok(WebDriverResponse::success(role))
 }
@@ -579,16 +617,17 @@ pub async fn get_computed_label<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let label = executor.get_element_computed_label(&js_var).await?;
     Ok(WebDriverResponse::success(label))
 }
@@ -603,16 +642,30 @@ pub async fn take_screenshot<R: Runtime + 'static>(
 
     let element = session
         .elements
-        .get(&element_id)
-        .ok_or_else(WebDriverErrorResponse::no_such_element)?;
+        .lookup(&element_id, &session.frame_context)?;
 
-    let js_var = element.js_ref.clone();
+    let js_var = element.id.clone();
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
+    let css_pixels = session.css_pixel_screenshots;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let screenshot = executor.take_element_screenshot(&js_var).await?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let mut screenshot = executor.take_element_screenshot(&js_var).await?;
+    if css_pixels {
+        screenshot = to_css_pixels(executor.as_ref(), screenshot).await?;
+    }
     Ok(WebDriverResponse::success(screenshot))
 }
+
+/// DELETE `/session/{session_id}/tauri/elements` - Forget element references that left the DOM
+pub async fn collect<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let removed = state.collect_elements(&session_id).await?;
+    Ok(WebDriverResponse::success(json!({ "removed": removed })))
+}
@@ -2,17 +2,27 @@ use std::sync::Arc;
 
 use axum::extract::{Path, State};
 use axum::Json;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use tauri::Runtime;
 
 use crate::platform::FrameId;
 use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::session::Session;
 
 #[derive(Debug, Deserialize)]
 pub struct SwitchFrameRequest {
-    pub id: Value,
+    /// `None` when the field is missing, as opposed to an explicit `null`
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub id: Option<Value>,
+}
+
+/// Deserialize a field that is present, keeping an explicit `null` as `Some(Value::Null)`
+fn deserialize_present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
 }
 
 /// POST `/session/{session_id}/frame` - Switch to frame
@@ -31,61 +41,27 @@ pub async fn switch_to_frame<R: Runtime + 'static>(
     let current_frame_context = session.frame_context.clone();
 
     // Parse the frame ID to determine what we're switching to
-    let (frame_id, js_var_for_element) = match &request.id {
-        Value::Null => {
-            // Switch to top-level context - no validation needed
-            drop(sessions);
-
-            // Update session: clear frame context
-            let mut sessions = state.sessions.write().await;
-            let session = sessions.get_mut(&session_id)?;
-            session.frame_context.clear();
-
-            return Ok(WebDriverResponse::null());
-        }
-        Value::Number(n) => {
-            let index = n.as_u64().ok_or_else(|| {
-                WebDriverErrorResponse::invalid_argument(
-                    "Frame index must be a non-negative integer",
-                )
-            })?;
-            let index = u32::try_from(index)
-                .map_err(|_| WebDriverErrorResponse::invalid_argument("Frame index too large"))?;
-
-            (FrameId::Index(index), None)
-        }
-        Value::Object(obj) => {
-            // W3C element reference format
-            if let Some(element_id) = obj.get("element-6066-11e4-a52e-4f735466cecf") {
-                let element_id = element_id.as_str().ok_or_else(|| {
-                    WebDriverErrorResponse::invalid_argument("Element reference must be a string")
-                })?;
-
-                // Look up the element's js_var
-                let element = session
-                    .elements
-                    .get(element_id)
-                    .ok_or_else(WebDriverErrorResponse::no_such_element)?;
-
-                let js_var = element.js_ref.clone();
-                (FrameId::Element(js_var.clone()), Some(js_var))
-            } else {
-                return Err(WebDriverErrorResponse::invalid_argument(
-                    "Invalid frame identifier object",
-                ));
-            }
-        }
-        _ => {
-            return Err(WebDriverErrorResponse::invalid_argument(
-                "Frame ID must be null, a number, or an element reference",
-            ));
-        }
-    };
+    let id = request
+        .id
+        .ok_or_else(|| WebDriverErrorResponse::invalid_argument("Missing 'id' parameter"))?;
+    if id.is_null() {
+        // Switch to top-level context - no validation needed
+        drop(sessions);
+
+        // Update session: clear frame context
+        let mut sessions = state.sessions.write().await;
+        let session = sessions.get_mut(&session_id)?;
+        session.frame_context.clear();
+
+        return Ok(WebDriverResponse::null());
+    }
+    let frame_id = parse_frame_id(session, &id, &current_frame_context)?;
     drop(sessions);
 
     // Create executor with CURRENT frame context (not the new one) to validate
-    let executor =
-        state.get_executor_for_window(&current_window, timeouts, current_frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, current_frame_context)
+        .await?;
 
     // Validate the frame exists from current context
     executor.switch_to_frame(frame_id.clone()).await?;
@@ -94,18 +70,51 @@ pub async fn switch_to_frame<R: Runtime + 'static>(
     let mut sessions = state.sessions.write().await;
     let session = sessions.get_mut(&session_id)?;
 
-    match &frame_id {
-        FrameId::Index(_) => {
-            session.frame_context.push(frame_id);
+    session.frame_context.push(frame_id);
+
+    Ok(WebDriverResponse::null())
+}
+
+/// Parse a frame index, or a reference to a frame element found in `frame_context`
+pub(crate) fn parse_frame_id(
+    session: &Session,
+    id: &Value,
+    frame_context: &[FrameId],
+) -> Result<FrameId, WebDriverErrorResponse> {
+    match id {
+        Value::Number(n) => {
+            // Per spec, the index must be an integer in the range 0 to 2^16 - 1
+            let index = n
+                .as_u64()
+                .and_then(|index| u16::try_from(index).ok())
+                .ok_or_else(|| {
+                    WebDriverErrorResponse::invalid_argument(
+                        "Frame index must be an integer between 0 and 65535",
+                    )
+                })?;
+
+            Ok(FrameId::Index(u32::from(index)))
         }
-        FrameId::Element(_) => {
-            if let Some(js_var) = js_var_for_element {
-                session.frame_context.push(FrameId::Element(js_var));
-            }
+        Value::Object(obj) => {
+            // W3C element reference format
+            let Some(element_id) = obj.get("element-6066-11e4-a52e-4f735466cecf") else {
+                return Err(WebDriverErrorResponse::invalid_argument(
+                    "Invalid frame identifier object",
+                ));
+            };
+            let element_id = element_id.as_str().ok_or_else(|| {
+                WebDriverErrorResponse::invalid_argument("Element reference must be a string")
+            })?;
+
+            // Look up the element's js_var
+            let element = session.elements.lookup(element_id, frame_context)?;
+
+            Ok(FrameId::Element(element.id.clone()))
         }
+        _ => Err(WebDriverErrorResponse::invalid_argument(
+            "Frame ID must be null, a number, or an element reference",
+        )),
     }
-
-    Ok(WebDriverResponse::null())
 }
 
 /// POST `/session/{session_id}/frame/parent` - Switch to parent frame
@@ -113,19 +122,29 @@ pub async fn switch_to_parent_frame<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
-    let mut sessions = state.sessions.write().await;
-    let session = sessions.get_mut(&session_id)?;
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
 
-    // Pop one level from frame context
-    session.frame_context.pop();
+    // Switching to the parent of the top-level context is a no-op
+    if session.frame_context.is_empty() {
+        return Ok(WebDriverResponse::null());
+    }
 
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+    let mut parent_context = session.frame_context.clone();
+    parent_context.pop();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    // Validate the parent frame is still reachable before committing the switch
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, parent_context)
+        .await?;
     executor.switch_to_parent_frame().await?;
 
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    session.frame_context.pop();
+
     Ok(WebDriverResponse::null())
 }
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use serde::Deserialize;
+use tauri::Runtime;
+
+use crate::platform::FrameContext;
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
+use crate::server::AppState;
+
+/// Log type holding the page's buffered entries
+const BROWSER_LOG: &str = "browser";
+
+/// GET `/session/{session_id}/se/log/types` - List the available log types
+pub async fn get_log_types<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    state.sessions.read().await.get(&session_id)?;
+    Ok(WebDriverResponse::success([BROWSER_LOG]))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetLogRequest {
+    #[serde(rename = "type")]
+    pub log_type: String,
+}
+
+/// POST `/session/{session_id}/se/log` - Drain buffered log entries of the current webview.
+/// Entries are only recorded for sessions created with the `captureLogs` option.
+pub async fn get_log<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<GetLogRequest>,
+) -> WebDriverResult {
+    if request.log_type != BROWSER_LOG {
+        return Err(WebDriverErrorResponse::invalid_argument(&format!(
+            "Unknown log type: {}",
+            request.log_type
+        )));
+    }
+
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, FrameContext::default())
+        .await?;
+    let entries = executor.take_logs().await?;
+    Ok(WebDriverResponse::success(entries))
+}
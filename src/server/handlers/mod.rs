@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
 use axum::extract::State;
+use axum::http::{Method, Uri};
 use serde_json::json;
 use tauri::Runtime;
 
-use super::response::{WebDriverResponse, WebDriverResult};
+use super::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use super::AppState;
 
 pub mod actions;
@@ -13,12 +14,14 @@ pub mod cookie;
 pub mod document;
 pub mod element;
 pub mod frame;
+pub mod log;
 pub mod navigation;
 pub mod print;
 pub mod screenshot;
 pub mod script;
 pub mod session;
 pub mod shadow;
+pub mod storage;
 pub mod timeouts;
 pub mod window;
 
@@ -29,3 +32,8 @@ pub async fn status<R: Runtime>(_state: State<Arc<AppState<R>>>) -> WebDriverRes
         "message": "tauri-plugin-webdriver is ready"
     })))
 }
+
+/// Fallback for unrecognized routes - `unknown command`
+pub async fn unknown_command(method: Method, uri: Uri) -> WebDriverErrorResponse {
+    WebDriverErrorResponse::unknown_command(&format!("Unknown command: {method} {uri}"))
+}
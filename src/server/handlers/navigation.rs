@@ -5,7 +5,8 @@ use axum::Json;
 use serde::Deserialize;
 use tauri::Runtime;
 
-use crate::server::response::{WebDriverResponse, WebDriverResult};
+use crate::platform::FrameContext;
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
 use crate::webdriver::ActionState;
 
@@ -24,15 +25,46 @@ pub async fn navigate<R: Runtime + 'static>(
     let session = sessions.get_mut(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+    // Navigation always targets the top-level browsing context
+    session.reset_frame_context();
+    let frame_context = FrameContext::default();
     // Clear action state on navigation
     session.action_state = ActionState::default();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    executor.navigate(&request.url).await?;
-
-    Ok(WebDriverResponse::null())
+    let url = tauri::Url::parse(&request.url).map_err(|e| {
+        WebDriverErrorResponse::invalid_argument(&format!("Invalid URL {:?}: {e}", request.url))
+    })?;
+    let page_load = std::time::Duration::from_millis(timeouts.page_load_ms);
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+
+    // Moving to a fragment of the current document doesn't load a page
+    let without_fragment = |url: &tauri::Url| {
+        let mut url = url.clone();
+        url.set_fragment(None);
+        url
+    };
+    let same_document = url.fragment().is_some()
+        && executor
+            .webview()
+            .url()
+            .is_ok_and(|current| without_fragment(&current) == without_fragment(&url));
+    if same_document {
+        executor.navigate(url.as_str()).await?;
+        return Ok(WebDriverResponse::null());
+    }
+
+    let loaded = state.expect_page_load(&current_window.webview, url.clone());
+    executor.navigate(url.as_str()).await?;
+    match tokio::time::timeout(page_load, loaded).await {
+        Ok(Ok(())) => Ok(WebDriverResponse::null()),
+        Ok(Err(_)) => Err(WebDriverErrorResponse::unknown_error("Channel closed")),
+        Err(_) => Err(WebDriverErrorResponse::timeout(
+            "Navigation did not finish within the page load timeout",
+        )),
+    }
 }
 
 /// GET `/session/{session_id}/url` - Get current URL
@@ -47,7 +79,9 @@ pub async fn get_url<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let url = executor.get_url().await?;
     Ok(WebDriverResponse::success(url))
 }
@@ -64,7 +98,9 @@ pub async fn get_title<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     let title = executor.get_title().await?;
     Ok(WebDriverResponse::success(title))
 }
@@ -78,12 +114,16 @@ pub async fn back<R: Runtime + 'static>(
     let session = sessions.get_mut(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+    // Navigation always targets the top-level browsing context
+    session.reset_frame_context();
+    let frame_context = FrameContext::default();
     // Clear action state on navigation
     session.action_state = ActionState::default();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.go_back().await?;
     Ok(WebDriverResponse::null())
 }
@@ -97,12 +137,16 @@ pub async fn forward<R: Runtime + 'static>(
     let session = sessions.get_mut(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+    // Navigation always targets the top-level browsing context
+    session.reset_frame_context();
+    let frame_context = FrameContext::default();
     // Clear action state on navigation
     session.action_state = ActionState::default();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.go_forward().await?;
     Ok(WebDriverResponse::null())
 }
@@ -116,12 +160,16 @@ pub async fn refresh<R: Runtime + 'static>(
     let session = sessions.get_mut(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+    // Navigation always targets the top-level browsing context
+    session.reset_frame_context();
+    let frame_context = FrameContext::default();
     // Clear action state on navigation
     session.action_state = ActionState::default();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.refresh().await?;
     Ok(WebDriverResponse::null())
 }
@@ -3,11 +3,13 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
+use serde_json::Value;
 use tauri::Runtime;
 
 use crate::platform::PrintOptions;
-use crate::server::response::{WebDriverResponse, WebDriverResult};
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::print::parse_page_ranges;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct PrintRequest {
@@ -32,12 +34,21 @@ pub struct PrintRequest {
     #[serde(default, rename = "shrinkToFit")]
     pub shrink_to_fit: Option<bool>,
     #[serde(default, rename = "pageRanges")]
-    pub page_ranges: Option<Vec<String>>,
+    pub page_ranges: Option<Vec<Value>>,
 }
 
-impl From<PrintRequest> for PrintOptions {
-    fn from(req: PrintRequest) -> Self {
-        PrintOptions {
+impl TryFrom<PrintRequest> for PrintOptions {
+    type Error = WebDriverErrorResponse;
+
+    fn try_from(req: PrintRequest) -> Result<Self, Self::Error> {
+        // Validate up front and hand platforms normalized `start-end` strings
+        let page_ranges = req
+            .page_ranges
+            .map(|ranges| parse_page_ranges(&ranges))
+            .transpose()?
+            .map(|ranges| ranges.iter().map(ToString::to_string).collect());
+
+        Ok(PrintOptions {
             orientation: req.orientation,
             scale: req.scale,
             background: req.background,
@@ -48,8 +59,8 @@ impl From<PrintRequest> for PrintOptions {
             margin_left: req.margin_left,
             margin_right: req.margin_right,
             shrink_to_fit: req.shrink_to_fit,
-            page_ranges: req.page_ranges,
-        }
+            page_ranges,
+        })
     }
 }
 
@@ -59,6 +70,8 @@ pub async fn print<R: Runtime + 'static>(
     Path(session_id): Path<String>,
     Json(request): Json<PrintRequest>,
 ) -> WebDriverResult {
+    let options = PrintOptions::try_from(request)?;
+
     let sessions = state.sessions.read().await;
     let session = sessions.get(&session_id)?;
     let current_window = session.current_window.clone();
@@ -66,8 +79,10 @@ pub async fn print<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let pdf_base64 = executor.print_page(request.into()).await?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let pdf_base64 = executor.print_page(options).await?;
 
     Ok(WebDriverResponse::success(pdf_base64))
 }
@@ -1,15 +1,48 @@
 use std::sync::Arc;
 
 use axum::extract::{Path, State};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
 use tauri::Runtime;
 
-use crate::server::response::{WebDriverResponse, WebDriverResult};
+use crate::platform::{FrameContext, PlatformExecutor};
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::image::Image;
+use crate::webdriver::{Timeouts, WebviewTarget};
 
 /// GET `/session/{session_id}/screenshot` - Take screenshot
 pub async fn take<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let screenshot = capture(&state, &session_id).await?;
+    Ok(WebDriverResponse::success(screenshot))
+}
+
+/// GET `/session/{session_id}/tauri/screenshot/full` - Take screenshot of the whole
+/// scrollable document (extension)
+pub async fn take_full_page<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let css_pixels = session.css_pixel_screenshots;
+    drop(sessions);
+
+    let screenshot = take_full_page_of(&state, &current_window, timeouts, css_pixels).await?;
+    Ok(WebDriverResponse::success(screenshot))
+}
+
+/// GET `/session/{session_id}/tauri/screenshot/window` - Take screenshot of the native window
+/// including OS chrome such as the titlebar and menus (extension)
+pub async fn take_window<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
 ) -> WebDriverResult {
     let sessions = state.sessions.read().await;
     let session = sessions.get(&session_id)?;
@@ -18,7 +51,250 @@ pub async fn take<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let screenshot = executor.take_screenshot().await?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let screenshot = executor.take_window_screenshot().await?;
     Ok(WebDriverResponse::success(screenshot))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct RegionRequest {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// POST `/session/{session_id}/tauri/screenshot/region` - Take screenshot of an area of the
+/// top-level viewport, given in CSS pixels (extension)
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub async fn take_region<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<RegionRequest>,
+) -> WebDriverResult {
+    let RegionRequest {
+        x,
+        y,
+        width,
+        height,
+    } = request;
+    if [x, y, width, height]
+        .iter()
+        .any(|v| !v.is_finite() || *v < 0.0)
+    {
+        return Err(WebDriverErrorResponse::invalid_argument(
+            "Region must be finite and non-negative",
+        ));
+    }
+    if width < 1.0 || height < 1.0 {
+        return Err(WebDriverErrorResponse::invalid_argument(
+            "Region must be at least 1x1",
+        ));
+    }
+
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let css_pixels = session.css_pixel_screenshots;
+    drop(sessions);
+
+    // Coordinates are relative to the top-level viewport, whichever frame is selected
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, FrameContext::default())
+        .await?;
+    let screenshot = executor.take_screenshot().await?;
+    let ratio = executor.get_device_pixel_ratio().await?;
+    let device = move |v: f64| (v * ratio).round() as u32;
+
+    let region = blocking(move || {
+        let screenshot = Image::from_base64_png(&screenshot)?;
+        let mut region = screenshot.cropped(device(x), device(y), device(width), device(height));
+        if region.width == 0 || region.height == 0 {
+            return Err(WebDriverErrorResponse::invalid_argument(
+                "Region is outside the viewport",
+            ));
+        }
+        if css_pixels {
+            let css = |v: u32| ((f64::from(v) / ratio).round() as u32).max(1);
+            region = region.resized(css(region.width), css(region.height));
+        }
+        region.to_base64_png()
+    })
+    .await?;
+    Ok(WebDriverResponse::success(region))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BaselineRequest {
+    /// Base64 PNG to store; captured from the current page when omitted
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+/// POST `/session/{session_id}/tauri/visual/baseline/{name}` - Store a named baseline for
+/// visual comparison (extension)
+pub async fn set_baseline<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, name)): Path<(String, String)>,
+    Json(request): Json<BaselineRequest>,
+) -> WebDriverResult {
+    let image = match request.image {
+        Some(image) => image,
+        None => capture(&state, &session_id).await?,
+    };
+    let baseline = blocking(move || Image::from_base64_png(&image)).await?;
+
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    session.visual_baselines.insert(name, baseline);
+    Ok(WebDriverResponse::null())
+}
+
+/// DELETE `/session/{session_id}/tauri/visual/baseline/{name}` - Remove a named baseline
+/// (extension)
+pub async fn delete_baseline<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, name)): Path<(String, String)>,
+) -> WebDriverResult {
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    session.visual_baselines.remove(&name);
+    Ok(WebDriverResponse::null())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareRequest {
+    /// Name of a stored baseline
+    #[serde(default)]
+    pub baseline: Option<String>,
+    /// Base64 PNG baseline, instead of a stored one
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Largest per-channel difference still treated as equal
+    #[serde(default)]
+    pub tolerance: u8,
+}
+
+/// POST `/session/{session_id}/tauri/visual/compare` - Capture a screenshot and compare it
+/// against a baseline on the device (extension)
+pub async fn compare<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<CompareRequest>,
+) -> WebDriverResult {
+    let baseline = match (request.baseline, request.image) {
+        (Some(name), None) => {
+            let sessions = state.sessions.read().await;
+            let session = sessions.get(&session_id)?;
+            session
+                .visual_baselines
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| {
+                    WebDriverErrorResponse::invalid_argument(&format!("No baseline named {name:?}"))
+                })?
+        }
+        (None, Some(image)) => blocking(move || Image::from_base64_png(&image)).await?,
+        _ => {
+            return Err(WebDriverErrorResponse::invalid_argument(
+                "Exactly one of baseline or image is required",
+            ))
+        }
+    };
+
+    let screenshot = capture(&state, &session_id).await?;
+    let tolerance = request.tolerance;
+    let result = blocking(move || {
+        let screenshot = Image::from_base64_png(&screenshot)?;
+        let diff = screenshot.diff(&baseline, tolerance);
+        Ok(json!({
+            "diffPercentage": diff.percentage(),
+            "diffPixels": diff.different_pixels,
+            "totalPixels": diff.total_pixels,
+            "sizeMatches": screenshot.width == baseline.width && screenshot.height == baseline.height,
+            "screenshot": screenshot.to_base64_png()?,
+            "diff": diff.image.to_base64_png()?,
+        }))
+    })
+    .await?;
+    Ok(WebDriverResponse::success(result))
+}
+
+/// Take Screenshot as configured by the session's screenshot options
+async fn capture<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+) -> Result<String, WebDriverErrorResponse> {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    let full_page = session.full_page_screenshots;
+    let css_pixels = session.css_pixel_screenshots;
+    drop(sessions);
+
+    if full_page {
+        return take_full_page_of(state, &current_window, timeouts, css_pixels).await;
+    }
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let mut screenshot = executor.take_screenshot().await?;
+    if css_pixels {
+        screenshot = to_css_pixels(executor.as_ref(), screenshot).await?;
+    }
+    Ok(screenshot)
+}
+
+/// Full-page screenshot of the top-level document, whichever frame is selected
+async fn take_full_page_of<R: Runtime + 'static>(
+    state: &AppState<R>,
+    target: &WebviewTarget,
+    timeouts: Timeouts,
+    css_pixels: bool,
+) -> Result<String, WebDriverErrorResponse> {
+    let executor = state
+        .get_executor_for_window(target, timeouts, FrameContext::default())
+        .await?;
+    let mut screenshot = executor.take_full_page_screenshot().await?;
+    if css_pixels {
+        screenshot = to_css_pixels(executor.as_ref(), screenshot).await?;
+    }
+    Ok(screenshot)
+}
+
+/// Scale a screenshot from device pixels to CSS pixels, so images captured on displays
+/// with different pixel ratios line up
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) async fn to_css_pixels<R: Runtime>(
+    executor: &dyn PlatformExecutor<R>,
+    screenshot: String,
+) -> Result<String, WebDriverErrorResponse> {
+    let ratio = executor.get_device_pixel_ratio().await?;
+    if (ratio - 1.0).abs() < f64::EPSILON {
+        return Ok(screenshot);
+    }
+
+    blocking(move || {
+        let image = Image::from_base64_png(&screenshot)?;
+        let width = (f64::from(image.width) / ratio).round() as u32;
+        let height = (f64::from(image.height) / ratio).round() as u32;
+        image.resized(width, height).to_base64_png()
+    })
+    .await
+}
+
+/// Run image decoding and encoding on the blocking pool, as large captures take long
+/// enough to stall the server's other requests
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, WebDriverErrorResponse> + Send + 'static,
+) -> Result<T, WebDriverErrorResponse> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?
+}
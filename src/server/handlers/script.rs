@@ -3,17 +3,39 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tauri::Runtime;
 
-use crate::server::response::{WebDriverResponse, WebDriverResult};
+use crate::platform::{FrameContext, FrameId, PlatformExecutor};
+use crate::server::handlers::frame::parse_frame_id;
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::element::element_ids_in;
+use crate::webdriver::session::Session;
+use crate::webdriver::window::{resolve_current_window, window_handles_in};
+use crate::webdriver::{Timeouts, WebviewTarget};
 
 #[derive(Debug, Deserialize)]
 pub struct ExecuteScriptRequest {
     pub script: String,
     #[serde(default)]
     pub args: Vec<Value>,
+    /// Run in another webview or frame without switching the session to it (extension)
+    #[serde(default, rename = "tauri:target")]
+    pub target: Option<ScriptTarget>,
+    /// Run in a fresh isolated world with pristine globals and built-ins (extension)
+    #[serde(default, rename = "tauri:newRealm")]
+    pub new_realm: bool,
+}
+
+/// Browsing context named by the `tauri:target` script parameter
+#[derive(Debug, Deserialize)]
+pub struct ScriptTarget {
+    /// Label of the webview to run in, starting from its top-level browsing context.
+    /// Defaults to the session's current browsing context.
+    pub webview: Option<String>,
+    /// Index or element reference of a child frame of that browsing context
+    pub frame: Option<Value>,
 }
 
 /// POST `/session/{session_id}/execute/sync` - Execute synchronous script
@@ -21,19 +43,65 @@ pub async fn execute_sync<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
     Json(request): Json<ExecuteScriptRequest>,
+) -> WebDriverResult {
+    let (target, timeouts, frame_context) = script_context(&state, &session_id, &request).await?;
+
+    let executor = state
+        .get_executor_for_window(&target, timeouts, frame_context.clone())
+        .await?;
+    let mut result = run_script(executor.as_ref(), &request, false).await?;
+    adopt_result_references(&state, &session_id, &mut result, &target, &frame_context).await?;
+    Ok(WebDriverResponse::success(result))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreloadScriptRequest {
+    pub script: String,
+}
+
+/// POST `/session/{session_id}/tauri/preload-script` - Register a script that runs in every
+/// new document of the current webview, before page scripts (extension)
+pub async fn add_preload_script<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<PreloadScriptRequest>,
 ) -> WebDriverResult {
     let sessions = state.sessions.read().await;
     let session = sessions.get(&session_id)?;
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let result = executor
-        .execute_script(&request.script, &request.args)
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, FrameContext::default())
         .await?;
-    Ok(WebDriverResponse::success(result))
+    let id = executor.add_preload_script(&request.script).await?;
+
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    session.preload_scripts.insert(id.clone(), current_window);
+    Ok(WebDriverResponse::success(json!({ "script": id })))
+}
+
+/// DELETE `/session/{session_id}/tauri/preload-script/{script_id}` - Unregister a preload
+/// script (extension)
+pub async fn remove_preload_script<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, script_id)): Path<(String, String)>,
+) -> WebDriverResult {
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+    let target = session.preload_scripts.remove(&script_id).ok_or_else(|| {
+        WebDriverErrorResponse::invalid_argument(&format!("No preload script {script_id:?}"))
+    })?;
+    let timeouts = session.timeouts.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&target, timeouts, FrameContext::default())
+        .await?;
+    executor.remove_preload_script(&script_id).await?;
+    Ok(WebDriverResponse::null())
 }
 
 /// POST `/session/{session_id}/execute/async` - Execute asynchronous script
@@ -42,16 +110,154 @@ pub async fn execute_async<R: Runtime + 'static>(
     Path(session_id): Path<String>,
     Json(request): Json<ExecuteScriptRequest>,
 ) -> WebDriverResult {
+    let (target, timeouts, frame_context) = script_context(&state, &session_id, &request).await?;
+
+    let executor = state
+        .get_executor_for_window(&target, timeouts, frame_context.clone())
+        .await?;
+    let mut result = run_script(executor.as_ref(), &request, true).await?;
+    adopt_result_references(&state, &session_id, &mut result, &target, &frame_context).await?;
+    Ok(WebDriverResponse::success(result))
+}
+
+/// Run `request` in the current or a fresh realm. Element references can't cross into or
+/// out of a new realm, as they belong to the page's world.
+async fn run_script<R: Runtime>(
+    executor: &dyn PlatformExecutor<R>,
+    request: &ExecuteScriptRequest,
+    is_async: bool,
+) -> Result<Value, WebDriverErrorResponse> {
+    if !request.new_realm {
+        return if is_async {
+            executor
+                .execute_async_script(&request.script, &request.args)
+                .await
+        } else {
+            executor
+                .execute_script(&request.script, &request.args)
+                .await
+        };
+    }
+    if request
+        .args
+        .iter()
+        .any(|arg| !element_ids_in(arg).is_empty())
+    {
+        return Err(WebDriverErrorResponse::invalid_argument(
+            "Element, shadow root and frame references can't be passed to a new realm",
+        ));
+    }
+    let result = executor
+        .execute_script_in_new_realm(&request.script, &request.args, is_async)
+        .await?;
+    if !element_ids_in(&result).is_empty() {
+        return Err(WebDriverErrorResponse::javascript_error(
+            "Elements, shadow roots and frames can't be returned from a new realm",
+            None,
+        ));
+    }
+    Ok(result)
+}
+
+/// Resolve the browsing context a script runs in: the session's current one, or the one
+/// named by the request's `tauri:target`, whose frame is verified to exist
+async fn script_context<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    request: &ExecuteScriptRequest,
+) -> Result<(WebviewTarget, Timeouts, FrameContext), WebDriverErrorResponse> {
     let sessions = state.sessions.read().await;
-    let session = sessions.get(&session_id)?;
-    let current_window = session.current_window.clone();
+    let session = sessions.get(session_id)?;
     let timeouts = session.timeouts.clone();
-    let frame_context = session.frame_context.clone();
+
+    let (target, mut frame_context) = match request
+        .target
+        .as_ref()
+        .and_then(|target| target.webview.as_ref())
+    {
+        Some(label) => {
+            let webview = state
+                .get_webview(label)
+                .ok_or_else(WebDriverErrorResponse::no_such_window)?;
+            (
+                WebviewTarget::new(webview.window().label(), label.as_str()),
+                FrameContext::default(),
+            )
+        }
+        None => (
+            session.current_window.clone(),
+            session.frame_context.clone(),
+        ),
+    };
+    check_window_references(session, &target, &request.args)?;
+
+    let frame = request
+        .target
+        .as_ref()
+        .and_then(|target| target.frame.as_ref());
+    let Some(frame) = frame else {
+        return Ok((target, timeouts, frame_context));
+    };
+    // The element store doesn't tell webviews apart, so frame elements are only looked up
+    // in the current one
+    if frame.is_object() && target.webview != session.current_window.webview {
+        return Err(WebDriverErrorResponse::invalid_argument(
+            "Frame element references can only target the current webview",
+        ));
+    }
+    let frame_id = parse_frame_id(session, frame, &frame_context)?;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
-    let result = executor
-        .execute_async_script(&request.script, &request.args)
+    let executor = state
+        .get_executor_for_window(&target, timeouts.clone(), frame_context.clone())
         .await?;
-    Ok(WebDriverResponse::success(result))
+    executor.switch_to_frame(frame_id.clone()).await?;
+    frame_context.push(frame_id);
+    Ok((target, timeouts, frame_context))
+}
+
+/// Window references in script arguments must refer to the top-level window the script
+/// runs in
+fn check_window_references(
+    session: &Session,
+    target: &WebviewTarget,
+    args: &[Value],
+) -> Result<(), WebDriverErrorResponse> {
+    for handle in args.iter().flat_map(window_handles_in) {
+        if session.window_handles.label_for(&handle) != Some(target.webview.as_str()) {
+            return Err(WebDriverErrorResponse::no_such_window());
+        }
+    }
+    Ok(())
+}
+
+/// Register elements, shadow roots and frames returned by a script, which the page already
+/// stored, with the session and resolve current window references to the handle of the
+/// window the script ran in. Elements of other webviews than the current one aren't
+/// registered, as commands couldn't reach them.
+async fn adopt_result_references<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    result: &mut Value,
+    target: &WebviewTarget,
+    frame_context: &[FrameId],
+) -> Result<(), WebDriverErrorResponse> {
+    let ids = element_ids_in(result);
+    let has_windows = !window_handles_in(result).is_empty();
+    if ids.is_empty() && !has_windows {
+        return Ok(());
+    }
+
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(session_id)?;
+    if session.current_window == *target {
+        for id in &ids {
+            session.elements.adopt(id, frame_context);
+        }
+    }
+    if has_windows {
+        let handle = session.window_handles.handle_for(&target.webview);
+        resolve_current_window(result, &handle);
+    }
+    Ok(())
 }
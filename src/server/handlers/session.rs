@@ -4,42 +4,72 @@ use axum::extract::{Path, State};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tauri::Runtime;
+use tauri::{Manager, Runtime};
 
+use crate::platform::{FrameContext, PlatformExecutor, LOG_CAPTURE_JS};
 use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
-use crate::webdriver::Timeouts;
+use crate::webdriver::{AlertStateManager, DialogTimeout, Timeouts, WebviewTarget};
 
 /// Wait for a window to become available, polling with timeout
-async fn wait_for_window<R: Runtime>(
+async fn wait_for_window<R: Runtime + 'static>(
     state: &AppState<R>,
     timeout_ms: u64,
-) -> Result<String, WebDriverErrorResponse> {
+) -> Result<WebviewTarget, WebDriverErrorResponse> {
     let start = std::time::Instant::now();
     let timeout = std::time::Duration::from_millis(timeout_ms);
     let poll_interval = std::time::Duration::from_millis(100);
 
     loop {
-        let window_labels = state.get_window_labels();
-        if let Some(label) = window_labels.first().cloned() {
-            return Ok(label);
+        let targets = state.get_webview_targets();
+        if let Some(target) = targets.into_iter().next() {
+            return Ok(target);
         }
 
         if start.elapsed() >= timeout {
-            return Err(WebDriverErrorResponse::no_such_window());
+            return Err(WebDriverErrorResponse::session_not_created(
+                "No webview window became available",
+            ));
         }
 
         tokio::time::sleep(poll_interval).await;
     }
 }
 
-/// W3C `WebDriver` session request (capabilities are accepted but not processed)
+/// W3C `WebDriver` session request
 #[derive(Debug, Deserialize)]
 pub struct CreateSessionRequest {
-    #[allow(dead_code)] // Accepted for protocol compliance but not processed
+    #[serde(default)]
     pub capabilities: Value,
 }
 
+/// Resolve a capability from `alwaysMatch`, falling back to the first `firstMatch` entry
+fn capability<'a>(capabilities: &'a Value, name: &str) -> Option<&'a Value> {
+    capabilities
+        .get("alwaysMatch")
+        .and_then(|always| always.get(name))
+        .or_else(|| {
+            capabilities
+                .get("firstMatch")
+                .and_then(Value::as_array)
+                .and_then(|first| first.first())
+                .and_then(|first| first.get(name))
+        })
+}
+
+/// Whether an `unhandledPromptBehavior` capability (a string, or a map with a `default`
+/// entry) closes prompts by accepting them. `ignore` and unknown values give `None`.
+fn prompt_behavior_accepts(behavior: &Value) -> Option<bool> {
+    let behavior = behavior
+        .as_str()
+        .or_else(|| behavior.get("default")?.as_str())?;
+    match behavior {
+        "accept" | "accept and notify" => Some(true),
+        "dismiss" | "dismiss and notify" => Some(false),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionResponse {
@@ -109,17 +139,73 @@ fn parse_user_agent(user_agent: &str) -> (String, String) {
     ("webview".to_string(), "unknown".to_string())
 }
 
+/// Capture logs in the current document right away and in every document loaded later.
+/// Returns the preload script id, or `None` when the platform has no preload scripts and
+/// only the current document is covered.
+async fn install_log_capture<R: Runtime>(executor: &dyn PlatformExecutor<R>) -> Option<String> {
+    let _ = executor.evaluate_js(LOG_CAPTURE_JS).await;
+    match executor.add_preload_script(LOG_CAPTURE_JS).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!("Log capture limited to the current document: {e:?}");
+            None
+        }
+    }
+}
+
 /// POST `/session` - Create a new session
+#[allow(clippy::too_many_lines)]
 pub async fn create<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
-    Json(_request): Json<CreateSessionRequest>,
+    Json(request): Json<CreateSessionRequest>,
 ) -> WebDriverResult {
+    let tauri_options = capability(&request.capabilities, "tauri:options")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let option = |name: &str| {
+        tauri_options
+            .get(name)
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+    let physical_window_rect = option("physicalWindowRect");
+    let hidden_windows = option("hiddenWindows");
+    let closed_shadow_roots = option("closedShadowRoots");
+    let native_input = option("nativeInput");
+    let full_page = option("fullPage");
+    let css_pixel_screenshots = option("cssPixelScreenshots");
+    let capture_logs = option("captureLogs");
+
+    // Closed shadow roots are only reachable through the patch the builder opts into
+    if closed_shadow_roots && !state.closed_shadow_roots_exposed {
+        return Err(WebDriverErrorResponse::invalid_argument(
+            "closedShadowRoots requires Builder::expose_closed_shadow_roots",
+        ));
+    }
+
+    // Dialogs nobody responds to close on their own, so a stray prompt can't hang the app
+    let unhandled_prompt_behavior = capability(&request.capabilities, "unhandledPromptBehavior");
+    let mut dialog_timeout = DialogTimeout::default();
+    if let Some(ms) = tauri_options
+        .get("dialogTimeout")
+        .and_then(Value::as_u64)
+        .filter(|ms| *ms > 0)
+    {
+        dialog_timeout.duration = std::time::Duration::from_millis(ms);
+    }
+    if let Some(accept) = unhandled_prompt_behavior.and_then(prompt_behavior_accepts) {
+        dialog_timeout.accept = accept;
+    }
+
     // Wait for a window to become available (up to 10 seconds)
     let initial_window = wait_for_window(&state, 10_000).await?;
 
     // Query the webview for its user agent to get browser info
-    let executor =
-        state.get_executor_for_window(&initial_window, Timeouts::default(), Vec::new())?;
+    let executor = state.get_executor_during_prompt(
+        &initial_window,
+        Timeouts::default(),
+        FrameContext::default(),
+    )?;
     let user_agent_result = executor
         .evaluate_js("(function() { return navigator.userAgent; })()")
         .await;
@@ -132,10 +218,29 @@ pub async fn create<R: Runtime + 'static>(
         Err(_) => ("webview".to_string(), "unknown".to_string()),
     };
 
+    let log_preload_script = if capture_logs {
+        install_log_capture(executor.as_ref()).await
+    } else {
+        None
+    };
+
     let mut sessions = state.sessions.write().await;
 
     // Create session with initial window
-    let session = sessions.create(initial_window);
+    let session = sessions.create(initial_window.clone());
+    session.physical_window_rect = physical_window_rect;
+    session.hidden_windows = hidden_windows;
+    session.closed_shadow_roots = closed_shadow_roots;
+    session.native_input = native_input;
+    session.full_page_screenshots = full_page;
+    session.css_pixel_screenshots = css_pixel_screenshots;
+    if let Some(id) = log_preload_script {
+        session.preload_scripts.insert(id, initial_window);
+    }
+    state
+        .app
+        .state::<AlertStateManager>()
+        .set_timeout(dialog_timeout);
 
     // Mobile platforms don't support window rect manipulation
     #[cfg(mobile)]
@@ -143,7 +248,7 @@ pub async fn create<R: Runtime + 'static>(
     #[cfg(desktop)]
     let set_window_rect = true;
 
-    let response = SessionResponse {
+    let mut response = SessionResponse {
         session_id: session.id.clone(),
         capabilities: json!({
             "browserName": browser_name,
@@ -152,6 +257,16 @@ pub async fn create<R: Runtime + 'static>(
             "acceptInsecureCerts": false,
             "pageLoadStrategy": "normal",
             "setWindowRect": set_window_rect,
+            "tauri:options": {
+                "physicalWindowRect": physical_window_rect,
+                "hiddenWindows": hidden_windows,
+                "closedShadowRoots": closed_shadow_roots,
+                "nativeInput": native_input,
+                "fullPage": full_page,
+                "cssPixelScreenshots": css_pixel_screenshots,
+                "captureLogs": capture_logs,
+                "dialogTimeout": dialog_timeout.duration.as_millis()
+            },
             "timeouts": {
                 "implicit": session.timeouts.implicit_ms,
                 "pageLoad": session.timeouts.page_load_ms,
@@ -159,18 +274,57 @@ pub async fn create<R: Runtime + 'static>(
             }
         }),
     };
+    if let Some(behavior) = unhandled_prompt_behavior {
+        response.capabilities["unhandledPromptBehavior"] = behavior.clone();
+    }
 
     Ok(WebDriverResponse::success(response))
 }
 
 /// DELETE `/session/{session_id}` - Delete a session
-pub async fn delete<R: Runtime>(
+pub async fn delete<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
     let mut sessions = state.sessions.write().await;
+    let preload_scripts = sessions
+        .get(&session_id)
+        .ok()
+        .map(|session| (session.preload_scripts.clone(), session.timeouts.clone()));
 
     if sessions.delete(&session_id) {
+        let last_session = sessions.ids().is_empty();
+        drop(sessions);
+        state
+            .app
+            .state::<AlertStateManager>()
+            .set_timeout(DialogTimeout::default());
+
+        // Preload scripts don't outlive the session; closed webviews took theirs along
+        if let Some((scripts, timeouts)) = preload_scripts {
+            for (id, target) in scripts {
+                if let Ok(executor) = state
+                    .get_executor_for_window(&target, timeouts.clone(), FrameContext::default())
+                    .await
+                {
+                    let _ = executor.remove_preload_script(&id).await;
+                }
+            }
+        }
+
+        // Page state is shared between sessions, so it is only removed with the last one
+        if last_session {
+            for target in state.get_webview_targets() {
+                if let Ok(executor) = state
+                    .get_executor_for_window(&target, Timeouts::default(), FrameContext::default())
+                    .await
+                {
+                    if let Err(e) = executor.clear_injected_state().await {
+                        tracing::warn!("Failed to clean up {}: {}", target.webview, e.message);
+                    }
+                }
+            }
+        }
         Ok(WebDriverResponse::null())
     } else {
         Err(WebDriverErrorResponse::invalid_session_id(&session_id))
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+use tauri::Runtime;
+
+use crate::platform::{PlatformExecutor, StorageArea};
+use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
+use crate::server::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct SetItemRequest {
+    pub key: String,
+    pub value: String,
+}
+
+/// GET `/session/{session_id}/tauri/local-storage` - List localStorage keys (extension)
+pub async fn get_local_keys<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    get_keys(&state, &session_id, StorageArea::Local).await
+}
+
+/// GET `/session/{session_id}/tauri/local-storage/{key}` - Get a localStorage item (extension)
+pub async fn get_local_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, key)): Path<(String, String)>,
+) -> WebDriverResult {
+    get_item(&state, &session_id, StorageArea::Local, &key).await
+}
+
+/// POST `/session/{session_id}/tauri/local-storage` - Set a localStorage item (extension)
+pub async fn set_local_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<SetItemRequest>,
+) -> WebDriverResult {
+    set_item(&state, &session_id, StorageArea::Local, &request).await
+}
+
+/// DELETE `/session/{session_id}/tauri/local-storage/{key}` - Remove a localStorage item
+/// (extension)
+pub async fn remove_local_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, key)): Path<(String, String)>,
+) -> WebDriverResult {
+    remove_item(&state, &session_id, StorageArea::Local, &key).await
+}
+
+/// DELETE `/session/{session_id}/tauri/local-storage` - Clear localStorage (extension)
+pub async fn clear_local<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    clear(&state, &session_id, StorageArea::Local).await
+}
+
+/// GET `/session/{session_id}/tauri/session-storage` - List sessionStorage keys (extension)
+pub async fn get_session_keys<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    get_keys(&state, &session_id, StorageArea::Session).await
+}
+
+/// GET `/session/{session_id}/tauri/session-storage/{key}` - Get a sessionStorage item (extension)
+pub async fn get_session_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, key)): Path<(String, String)>,
+) -> WebDriverResult {
+    get_item(&state, &session_id, StorageArea::Session, &key).await
+}
+
+/// POST `/session/{session_id}/tauri/session-storage` - Set a sessionStorage item (extension)
+pub async fn set_session_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<SetItemRequest>,
+) -> WebDriverResult {
+    set_item(&state, &session_id, StorageArea::Session, &request).await
+}
+
+/// DELETE `/session/{session_id}/tauri/session-storage/{key}` - Remove a sessionStorage
+/// item (extension)
+pub async fn remove_session_item<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, key)): Path<(String, String)>,
+) -> WebDriverResult {
+    remove_item(&state, &session_id, StorageArea::Session, &key).await
+}
+
+/// DELETE `/session/{session_id}/tauri/session-storage` - Clear sessionStorage (extension)
+pub async fn clear_session<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    clear(&state, &session_id, StorageArea::Session).await
+}
+
+/// GET `/session/{session_id}/tauri/indexed-db` - List `IndexedDB` databases (extension)
+pub async fn get_indexed_databases<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let executor = executor_for_session(&state, &session_id).await?;
+    let databases = executor.get_indexed_databases().await?;
+    Ok(WebDriverResponse::success(databases))
+}
+
+/// DELETE `/session/{session_id}/tauri/indexed-db/{name}` - Delete an `IndexedDB` database
+/// (extension)
+pub async fn delete_indexed_database<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path((session_id, name)): Path<(String, String)>,
+) -> WebDriverResult {
+    let executor = executor_for_session(&state, &session_id).await?;
+    executor.delete_indexed_database(&name).await?;
+    Ok(WebDriverResponse::null())
+}
+
+/// DELETE `/session/{session_id}/tauri/site-data` - Clear all site data (extension)
+pub async fn clear_site_data<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let executor = executor_for_session(&state, &session_id).await?;
+    executor.clear_site_data().await?;
+    Ok(WebDriverResponse::null())
+}
+
+/// GET `/session/{session_id}/tauri/storage-partition` - Describe the data store of the
+/// current webview (extension)
+pub async fn get_storage_partition<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    drop(sessions);
+
+    let webview = state.resolve_webview(&current_window)?;
+    let origin = webview
+        .url()
+        .ok()
+        .map(|url| url.origin().ascii_serialization());
+
+    // Webviews built at runtime don't expose their data store settings, so only
+    // windows declared in the app config can be described
+    let config = state
+        .app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == current_window.webview);
+
+    Ok(WebDriverResponse::success(json!({
+        "window": current_window.window,
+        "webview": current_window.webview,
+        "origin": origin,
+        "configured": config.is_some(),
+        "incognito": config.map(|config| config.incognito),
+        "dataDirectory": config.and_then(|config| config.data_directory.clone()),
+        "dataStoreIdentifier": config.and_then(|config| config.data_store_identifier),
+    })))
+}
+
+async fn get_keys<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    area: StorageArea,
+) -> WebDriverResult {
+    let executor = executor_for_session(state, session_id).await?;
+    let keys = executor.get_storage_keys(area).await?;
+    Ok(WebDriverResponse::success(keys))
+}
+
+async fn get_item<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    area: StorageArea,
+    key: &str,
+) -> WebDriverResult {
+    let executor = executor_for_session(state, session_id).await?;
+    let value = executor.get_storage_item(area, key).await?;
+    Ok(WebDriverResponse::success(value))
+}
+
+async fn set_item<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    area: StorageArea,
+    request: &SetItemRequest,
+) -> WebDriverResult {
+    let executor = executor_for_session(state, session_id).await?;
+    executor
+        .set_storage_item(area, &request.key, &request.value)
+        .await?;
+    Ok(WebDriverResponse::null())
+}
+
+async fn remove_item<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    area: StorageArea,
+    key: &str,
+) -> WebDriverResult {
+    let executor = executor_for_session(state, session_id).await?;
+    executor.remove_storage_item(area, key).await?;
+    Ok(WebDriverResponse::null())
+}
+
+async fn clear<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+    area: StorageArea,
+) -> WebDriverResult {
+    let executor = executor_for_session(state, session_id).await?;
+    executor.clear_storage(area).await?;
+    Ok(WebDriverResponse::null())
+}
+
+/// Executor for the session's current browsing context
+async fn executor_for_session<R: Runtime + 'static>(
+    state: &AppState<R>,
+    session_id: &str,
+) -> Result<Arc<dyn PlatformExecutor<R>>, WebDriverErrorResponse> {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await
+}
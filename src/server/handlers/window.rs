@@ -4,11 +4,12 @@ use axum::extract::{Path, State};
 use axum::Json;
 use serde::Deserialize;
 use serde_json::json;
-use tauri::{Manager, Runtime};
+use tauri::Runtime;
 
-use crate::platform::WindowRect;
+use crate::platform::{create_executor, FrameContext, PlatformExecutor, WindowRect};
 use crate::server::response::{WebDriverErrorResponse, WebDriverResponse, WebDriverResult};
 use crate::server::AppState;
+use crate::webdriver::{Timeouts, WebviewTarget};
 
 #[derive(Debug, Deserialize)]
 pub struct SwitchWindowRequest {
@@ -17,11 +18,16 @@ pub struct SwitchWindowRequest {
 
 #[derive(Debug, Deserialize)]
 pub struct NewWindowRequest {
-    #[allow(dead_code)] // Part of W3C protocol but not used in current implementation
+    #[cfg_attr(mobile, allow(dead_code))]
     #[serde(rename = "type", default)]
     pub window_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WindowToggleRequest {
+    pub enabled: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WindowRectRequest {
     #[serde(default)]
@@ -34,18 +40,43 @@ pub struct WindowRectRequest {
     pub height: Option<u32>,
 }
 
+/// Scale factor between the physical pixels used by Tauri and the units reported to the client.
+/// Rects are reported in CSS pixels like browsers do, unless the session opted into
+/// physical pixels.
+fn rect_scale_factor<R: Runtime>(executor: &dyn PlatformExecutor<R>, physical: bool) -> f64 {
+    #[cfg(desktop)]
+    if !physical {
+        return executor.window().scale_factor().unwrap_or(1.0);
+    }
+    let _ = (executor, physical);
+    1.0
+}
+
+/// Serialize a physical window rect in the units reported to the client
+fn rect_response(rect: &WindowRect, scale_factor: f64) -> WebDriverResponse {
+    let rect = rect.to_logical(scale_factor);
+    WebDriverResponse::success(json!({
+        "x": rect.x,
+        "y": rect.y,
+        "width": rect.width,
+        "height": rect.height
+    }))
+}
+
 /// GET `/session/{session_id}/window` - Get current window handle
 pub async fn get_window_handle<R: Runtime>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
-    let sessions = state.sessions.read().await;
-    let session = sessions.get(&session_id)?;
-    let current_window = session.current_window.clone();
-    drop(sessions);
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
+
+    state.resolve_webview(&session.current_window)?;
 
-    // Return the session's current window handle
-    Ok(WebDriverResponse::success(current_window))
+    let handle = session
+        .window_handles
+        .handle_for(&session.current_window.webview);
+    Ok(WebDriverResponse::success(handle))
 }
 
 /// GET `/session/{session_id}/window/handles` - Get all window handles
@@ -53,12 +84,19 @@ pub async fn get_window_handles<R: Runtime>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
 ) -> WebDriverResult {
-    let sessions = state.sessions.read().await;
-    let _session = sessions.get(&session_id)?;
-    drop(sessions);
+    let mut sessions = state.sessions.write().await;
+    let session = sessions.get_mut(&session_id)?;
 
-    // Return all window labels as handles
-    let handles: Vec<String> = state.app.webview_windows().keys().cloned().collect();
+    let labels: Vec<String> = state
+        .get_webview_targets()
+        .into_iter()
+        .map(|target| target.webview)
+        .collect();
+    session.window_handles.retain_labels(&labels);
+    let handles: Vec<String> = labels
+        .iter()
+        .map(|label| session.window_handles.handle_for(label))
+        .collect();
 
     Ok(WebDriverResponse::success(handles))
 }
@@ -80,28 +118,48 @@ pub async fn close_window<R: Runtime>(
     #[cfg(desktop)]
     {
         let sessions = state.sessions.read().await;
-        let session = sessions.get(&session_id)?;
-        let current_window = session.current_window.clone();
+        let current_window = sessions.get(&session_id)?.current_window.clone();
         drop(sessions);
 
-        // Close the current window
-        if let Some(window) = state.app.webview_windows().get(&current_window).cloned() {
-            window
-                .destroy()
-                .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
-
-            // Return remaining window handles
-            let handles: Vec<String> = state.app.webview_windows().keys().cloned().collect();
-
-            Ok(WebDriverResponse::success(handles))
+        // Close the current webview, or its whole window if it is the only webview in it.
+        // No lock is held, as closing runs plugin hooks that use the sessions.
+        let webview = state.resolve_webview(&current_window)?;
+        let window = webview.window();
+        let result = if window.webviews().len() > 1 {
+            webview.close()
         } else {
-            Err(WebDriverErrorResponse::no_such_window())
+            window.destroy()
+        };
+        result.map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        // Return remaining window handles
+        let labels: Vec<String> = state
+            .get_webview_targets()
+            .into_iter()
+            .map(|target| target.webview)
+            .filter(|label| *label != current_window.webview)
+            .collect();
+
+        let mut sessions = state.sessions.write().await;
+        let session = sessions.get_mut(&session_id)?;
+        session.window_handles.remove_label(&current_window.webview);
+        session.window_handles.retain_labels(&labels);
+        let handles: Vec<String> = labels
+            .iter()
+            .map(|label| session.window_handles.handle_for(label))
+            .collect();
+
+        // Closing the last window ends the session
+        if handles.is_empty() {
+            sessions.delete(&session_id);
         }
+
+        Ok(WebDriverResponse::success(handles))
     }
 }
 
 /// POST `/session/{session_id}/window` - Switch to window
-pub async fn switch_to_window<R: Runtime>(
+pub async fn switch_to_window<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
     Json(request): Json<SwitchWindowRequest>,
@@ -109,13 +167,30 @@ pub async fn switch_to_window<R: Runtime>(
     let mut sessions = state.sessions.write().await;
     let session = sessions.get_mut(&session_id)?;
 
-    // Verify the window exists
-    if !state.app.webview_windows().contains_key(&request.handle) {
+    // Resolve the handle and verify the window still exists
+    let label = session
+        .window_handles
+        .label_for(&request.handle)
+        .map(String::from)
+        .ok_or_else(WebDriverErrorResponse::no_such_window)?;
+    let Some(webview) = state.get_webview(&label) else {
+        session.window_handles.remove_label(&label);
         return Err(WebDriverErrorResponse::no_such_window());
-    }
+    };
+
+    // Subsequent commands target the new window's top-level browsing context
+    session.current_window = WebviewTarget::new(webview.window().label(), label);
+    session.frame_context.clear();
+    let hidden = session.hidden_windows;
+    drop(sessions);
 
-    // Update session's current window
-    session.current_window = request.handle;
+    // Bring the window to the foreground so native input lands in it
+    if !hidden {
+        let executor = create_executor(webview, Timeouts::default(), FrameContext::default());
+        if let Err(e) = executor.focus_window().await {
+            tracing::warn!("Failed to focus window: {}", e.message);
+        }
+    }
 
     Ok(WebDriverResponse::null())
 }
@@ -124,17 +199,50 @@ pub async fn switch_to_window<R: Runtime>(
 pub async fn new_window<R: Runtime + 'static>(
     State(state): State<Arc<AppState<R>>>,
     Path(session_id): Path<String>,
-    Json(_request): Json<NewWindowRequest>,
+    Json(request): Json<NewWindowRequest>,
 ) -> WebDriverResult {
-    let sessions = state.sessions.read().await;
-    let _session = sessions.get(&session_id)?;
-    drop(sessions);
+    // Mobile platforms only support a single window
+    #[cfg(mobile)]
+    {
+        let _ = (state, session_id, request);
+        Err(WebDriverErrorResponse::unsupported_operation(
+            "Creating new windows is not supported on mobile platforms",
+        ))
+    }
+
+    #[cfg(desktop)]
+    {
+        use tauri::{WebviewUrl, WebviewWindowBuilder};
 
-    // Note: Creating new windows in Tauri requires app-specific logic
-    // This is a stub that returns an error - apps should handle this via commands
-    Err(WebDriverErrorResponse::unsupported_operation(
-        "Creating new windows is not supported in this context",
-    ))
+        let sessions = state.sessions.read().await;
+        let hidden = sessions.get(&session_id)?.hidden_windows;
+        drop(sessions);
+
+        // Tauri has no tabs, so both "tab" and "window" create a top-level window
+        tracing::debug!(
+            "Creating new window (requested type: {:?})",
+            request.window_type
+        );
+
+        let label = format!("webdriver-{}", uuid::Uuid::new_v4().simple());
+        let url = tauri::Url::parse("about:blank")
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+        WebviewWindowBuilder::new(&state.app, &label, WebviewUrl::External(url))
+            .title(&label)
+            .visible(!hidden)
+            .focused(!hidden)
+            .build()
+            .map_err(|e| WebDriverErrorResponse::unknown_error(&e.to_string()))?;
+
+        let mut sessions = state.sessions.write().await;
+        let session = sessions.get_mut(&session_id)?;
+        let handle = session.window_handles.handle_for(&label);
+
+        Ok(WebDriverResponse::success(json!({
+            "handle": handle,
+            "type": "window"
+        })))
+    }
 }
 
 /// GET `/session/{session_id}/window/rect` - Get window rect
@@ -147,17 +255,16 @@ pub async fn get_rect<R: Runtime + 'static>(
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
+    let physical = session.physical_window_rect;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let scale_factor = rect_scale_factor(executor.as_ref(), physical);
     let rect = executor.get_window_rect().await?;
 
-    Ok(WebDriverResponse::success(json!({
-        "x": rect.x,
-        "y": rect.y,
-        "width": rect.width,
-        "height": rect.height
-    })))
+    Ok(rect_response(&rect, scale_factor))
 }
 
 /// POST `/session/{session_id}/window/rect` - Set window rect
@@ -171,28 +278,35 @@ pub async fn set_rect<R: Runtime + 'static>(
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
+    let physical = session.physical_window_rect;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let scale_factor = rect_scale_factor(executor.as_ref(), physical);
 
-    // Get current rect to fill in missing values
+    // Only requested fields are converted; missing ones keep their current physical
+    // value, which a round trip through CSS pixels could shift by a pixel
     let current = executor.get_window_rect().await?;
+    let requested = WindowRect {
+        x: request.x.unwrap_or_default(),
+        y: request.y.unwrap_or_default(),
+        width: request.width.unwrap_or_default(),
+        height: request.height.unwrap_or_default(),
+    }
+    .to_physical(scale_factor);
 
     let new_rect = WindowRect {
-        x: request.x.unwrap_or(current.x),
-        y: request.y.unwrap_or(current.y),
-        width: request.width.unwrap_or(current.width),
-        height: request.height.unwrap_or(current.height),
+        x: request.x.map_or(current.x, |_| requested.x),
+        y: request.y.map_or(current.y, |_| requested.y),
+        width: request.width.map_or(current.width, |_| requested.width),
+        height: request.height.map_or(current.height, |_| requested.height),
     };
 
     let rect = executor.set_window_rect(new_rect).await?;
 
-    Ok(WebDriverResponse::success(json!({
-        "x": rect.x,
-        "y": rect.y,
-        "width": rect.width,
-        "height": rect.height
-    })))
+    Ok(rect_response(&rect, scale_factor))
 }
 
 /// POST `/session/{session_id}/window/maximize` - Maximize window
@@ -205,17 +319,16 @@ pub async fn maximize<R: Runtime + 'static>(
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
+    let physical = session.physical_window_rect;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let scale_factor = rect_scale_factor(executor.as_ref(), physical);
     let rect = executor.maximize_window().await?;
 
-    Ok(WebDriverResponse::success(json!({
-        "x": rect.x,
-        "y": rect.y,
-        "width": rect.width,
-        "height": rect.height
-    })))
+    Ok(rect_response(&rect, scale_factor))
 }
 
 /// POST `/session/{session_id}/window/minimize` - Minimize window
@@ -230,7 +343,9 @@ pub async fn minimize<R: Runtime + 'static>(
     let frame_context = session.frame_context.clone();
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
     executor.minimize_window().await?;
 
     // Return null per W3C spec (minimized window has no meaningful rect)
@@ -247,15 +362,120 @@ pub async fn fullscreen<R: Runtime + 'static>(
     let current_window = session.current_window.clone();
     let timeouts = session.timeouts.clone();
     let frame_context = session.frame_context.clone();
+    let physical = session.physical_window_rect;
     drop(sessions);
 
-    let executor = state.get_executor_for_window(&current_window, timeouts, frame_context)?;
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let scale_factor = rect_scale_factor(executor.as_ref(), physical);
     let rect = executor.fullscreen_window().await?;
 
-    Ok(WebDriverResponse::success(json!({
-        "x": rect.x,
-        "y": rect.y,
-        "width": rect.width,
-        "height": rect.height
-    })))
+    Ok(rect_response(&rect, scale_factor))
+}
+
+/// POST `/session/{session_id}/tauri/window/restore` - Restore window from maximized,
+/// minimized or fullscreen state
+pub async fn restore<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    let physical = session.physical_window_rect;
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let scale_factor = rect_scale_factor(executor.as_ref(), physical);
+    executor.restore_window().await?;
+    let rect = executor.get_window_rect().await?;
+
+    Ok(rect_response(&rect, scale_factor))
+}
+
+/// GET `/session/{session_id}/tauri/window/state` - Get native window state flags
+pub async fn get_state<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    let window_state = executor.get_window_state().await?;
+
+    Ok(WebDriverResponse::success(window_state))
+}
+
+/// POST `/session/{session_id}/tauri/window/focus` - Focus the current window
+pub async fn focus<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    executor.focus_window().await?;
+
+    Ok(WebDriverResponse::null())
+}
+
+/// POST `/session/{session_id}/tauri/window/always-on-top` - Keep the window above other windows
+pub async fn set_always_on_top<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<WindowToggleRequest>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    executor.set_always_on_top(request.enabled).await?;
+
+    Ok(WebDriverResponse::null())
+}
+
+/// POST `/session/{session_id}/tauri/window/decorations` - Show or hide native window decorations
+pub async fn set_decorations<R: Runtime + 'static>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(session_id): Path<String>,
+    Json(request): Json<WindowToggleRequest>,
+) -> WebDriverResult {
+    let sessions = state.sessions.read().await;
+    let session = sessions.get(&session_id)?;
+    let current_window = session.current_window.clone();
+    let timeouts = session.timeouts.clone();
+    let frame_context = session.frame_context.clone();
+    drop(sessions);
+
+    let executor = state
+        .get_executor_for_window(&current_window, timeouts, frame_context)
+        .await?;
+    executor.set_decorations(request.enabled).await?;
+
+    Ok(WebDriverResponse::null())
 }
@@ -1,60 +1,248 @@
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, Url, Webview};
 use tokio::runtime::Runtime as TokioRuntime;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 
 pub mod handlers;
 pub mod response;
 pub mod router;
 
-use crate::platform::{create_executor, FrameId, PlatformExecutor};
+use crate::platform::{create_executor, FrameContext, PlatformExecutor, WebviewRegistry};
 use crate::server::response::WebDriverErrorResponse;
-use crate::webdriver::{SessionManager, Timeouts};
+use crate::webdriver::locator::LocatorRegistry;
+use crate::webdriver::{SessionManager, Timeouts, WebviewTarget};
+
+/// How often stale element references are collected in the background
+const ELEMENT_GC_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Shared state for the `WebDriver` server
 pub struct AppState<R: Runtime> {
     pub app: AppHandle<R>,
     pub sessions: RwLock<SessionManager>,
+    pub locators: LocatorRegistry,
+    /// Whether `Builder::expose_closed_shadow_roots` installed the `attachShadow` patch
+    pub closed_shadow_roots_exposed: bool,
+    /// Navigations waiting for their page to finish loading
+    page_loads: Mutex<Vec<PageLoadWaiter>>,
+}
+
+/// Navigation of a webview waiting for the page load hook to report its page finished
+struct PageLoadWaiter {
+    webview: String,
+    url: Url,
+    /// Whether loading `url` started, after which a redirect may finish on another URL
+    started: bool,
+    done: oneshot::Sender<()>,
 }
 
 impl<R: Runtime + 'static> AppState<R> {
-    pub fn new(app: AppHandle<R>) -> Self {
+    pub fn new(
+        app: AppHandle<R>,
+        locators: LocatorRegistry,
+        closed_shadow_roots_exposed: bool,
+    ) -> Self {
         Self {
             app,
             sessions: RwLock::new(SessionManager::new()),
+            locators,
+            closed_shadow_roots_exposed,
+            page_loads: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get notified when `webview` finishes loading `url`. Register before starting the
+    /// navigation, so a fast load isn't missed.
+    pub fn expect_page_load(&self, webview: &str, url: Url) -> oneshot::Receiver<()> {
+        let (done, receiver) = oneshot::channel();
+        if let Ok(mut waiters) = self.page_loads.lock() {
+            waiters.push(PageLoadWaiter {
+                webview: webview.to_string(),
+                url,
+                started: false,
+                done,
+            });
         }
+        receiver
     }
 
-    /// Get a platform executor for a specific window by label
-    pub fn get_executor_for_window(
+    /// Get a platform executor for a specific webview within a window.
+    /// Fails with `unexpected alert open` while a user prompt is showing in the webview,
+    /// as the page can't be reached behind it.
+    pub async fn get_executor_for_window(
         &self,
-        window_label: &str,
+        target: &WebviewTarget,
         timeouts: Timeouts,
-        frame_context: Vec<FrameId>,
+        frame_context: FrameContext,
     ) -> Result<Arc<dyn PlatformExecutor<R>>, WebDriverErrorResponse> {
-        self.app
-            .webview_windows()
-            .get(window_label)
-            .cloned()
-            .map(|window| create_executor(window, timeouts, frame_context))
+        let executor = self.get_executor_during_prompt(target, timeouts, frame_context)?;
+        executor.check_no_alert().await?;
+        Ok(executor)
+    }
+
+    /// Get a platform executor even while a user prompt is showing, for the commands that
+    /// handle the prompt themselves
+    pub fn get_executor_during_prompt(
+        &self,
+        target: &WebviewTarget,
+        timeouts: Timeouts,
+        frame_context: FrameContext,
+    ) -> Result<Arc<dyn PlatformExecutor<R>>, WebDriverErrorResponse> {
+        let webview = self.resolve_webview(target)?;
+        Ok(create_executor(webview, timeouts, frame_context))
+    }
+
+    /// Resolve a target to its live webview, verifying it is still hosted by the expected window
+    pub fn resolve_webview(
+        &self,
+        target: &WebviewTarget,
+    ) -> Result<Webview<R>, WebDriverErrorResponse> {
+        self.get_webview(&target.webview)
+            .filter(|webview| webview.window().label() == target.window)
             .ok_or_else(WebDriverErrorResponse::no_such_window)
     }
 
-    /// Get all window labels
-    pub fn get_window_labels(&self) -> Vec<String> {
-        self.app.webview_windows().keys().cloned().collect()
+    /// Mark a session's element references whose nodes left the document as stale.
+    /// Only the selected frame is inspected, as other frames' stores aren't reachable from it,
+    /// and nothing is inspected while a user prompt is open.
+    /// Returns the number of references collected.
+    pub async fn collect_elements(
+        &self,
+        session_id: &str,
+    ) -> Result<usize, WebDriverErrorResponse> {
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(session_id)?;
+        let js_refs = session.elements.js_refs_in_frame(&session.frame_context);
+        let current_window = session.current_window.clone();
+        let timeouts = session.timeouts.clone();
+        let frame_context = session.frame_context.clone();
+        drop(sessions);
+
+        if js_refs.is_empty() {
+            return Ok(0);
+        }
+
+        // Leaves the page alone while a user prompt is open
+        let executor = self
+            .get_executor_for_window(&current_window, timeouts, frame_context)
+            .await?;
+        let collected = executor.collect_element_refs(&js_refs).await?;
+
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id)?;
+        session.elements.remove_js_refs(&collected);
+        Ok(collected.len())
+    }
+
+    /// Get a live webview by label, including child webviews of multi-webview windows
+    pub fn get_webview(&self, label: &str) -> Option<Webview<R>> {
+        self.app.state::<WebviewRegistry<R>>().get(label)
+    }
+
+    /// Get all live webviews as targets, each of which is exposed as a window handle
+    pub fn get_webview_targets(&self) -> Vec<WebviewTarget> {
+        self.app
+            .state::<WebviewRegistry<R>>()
+            .webviews()
+            .iter()
+            .map(|webview| WebviewTarget::new(webview.window().label(), webview.label()))
+            .collect()
+    }
+}
+
+/// Reset the frame context of sessions targeting a webview whose page started loading.
+/// Called from the plugin's page load hook, so it also covers app-initiated navigations.
+pub fn reset_frames_on_navigation<R: Runtime + 'static>(webview: &Webview<R>) {
+    let Some(state) = webview.try_state::<Arc<AppState<R>>>() else {
+        return;
+    };
+    let state = Arc::clone(&state);
+    let label = webview.label().to_string();
+
+    // Never block the main thread on the session lock
+    tauri::async_runtime::spawn(async move {
+        state
+            .sessions
+            .write()
+            .await
+            .reset_frame_context_for_webview(&label);
+    });
+}
+
+/// Resolve navigations waiting on a webview whose page started or finished loading.
+/// Called from the plugin's page load hook.
+pub fn track_page_load<R: Runtime + 'static>(
+    webview: &Webview<R>,
+    event: PageLoadEvent,
+    url: &Url,
+) {
+    let Some(state) = webview.try_state::<Arc<AppState<R>>>() else {
+        return;
+    };
+    let Ok(mut waiters) = state.page_loads.lock() else {
+        return;
+    };
+
+    // Waiters whose navigation gave up are dropped along the way
+    let label = webview.label();
+    let pending = std::mem::take(&mut *waiters);
+    for mut waiter in pending {
+        if waiter.done.is_closed() {
+            continue;
+        }
+        if waiter.webview != label {
+            waiters.push(waiter);
+            continue;
+        }
+        match event {
+            PageLoadEvent::Started => {
+                waiter.started |= waiter.url == *url;
+                waiters.push(waiter);
+            }
+            PageLoadEvent::Finished if waiter.started || waiter.url == *url => {
+                let _ = waiter.done.send(());
+            }
+            PageLoadEvent::Finished => waiters.push(waiter),
+        }
+    }
+}
+
+/// Collect element references of every session in the background
+async fn collect_elements_periodically<R: Runtime + 'static>(state: Arc<AppState<R>>) {
+    let mut interval = tokio::time::interval(ELEMENT_GC_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let session_ids = state.sessions.read().await.ids();
+        for session_id in session_ids {
+            match state.collect_elements(&session_id).await {
+                Ok(0) | Err(_) => {}
+                Ok(count) => {
+                    tracing::debug!("Collected {count} stale element references in {session_id}");
+                }
+            }
+        }
     }
 }
 
 /// Start the `WebDriver` HTTP server on the specified port
-pub fn start<R: Runtime + 'static>(app: AppHandle<R>, port: u16) {
+pub fn start<R: Runtime + 'static>(
+    app: AppHandle<R>,
+    port: u16,
+    locators: LocatorRegistry,
+    closed_shadow_roots_exposed: bool,
+) {
+    let state = Arc::new(AppState::new(app, locators, closed_shadow_roots_exposed));
+    state.app.manage(Arc::clone(&state));
+
     std::thread::spawn(move || {
         let rt = TokioRuntime::new().expect("Failed to create Tokio runtime");
 
         rt.block_on(async {
-            let state = Arc::new(AppState::new(app));
+            tokio::spawn(collect_elements_periodically(Arc::clone(&state)));
             let router = router::create_router(state);
 
             // On Android, bind to all interfaces for WiFi accessibility
@@ -35,6 +35,9 @@ impl IntoResponse for WebDriverResponse {
     }
 }
 
+/// Prefix of the messages of errors the driver's injected scripts throw on purpose
+pub const JS_ERROR_SENTINEL: &str = "__wd:";
+
 /// W3C `WebDriver` error response
 #[derive(Debug)]
 pub struct WebDriverErrorResponse {
@@ -42,6 +45,8 @@ pub struct WebDriverErrorResponse {
     pub error: String,
     pub message: String,
     pub stacktrace: Option<String>,
+    /// Optional additional error data (e.g. alert text for `unexpected alert open`)
+    pub data: Option<Value>,
 }
 
 impl WebDriverErrorResponse {
@@ -51,6 +56,56 @@ impl WebDriverErrorResponse {
             error: error.to_string(),
             message: message.to_string(),
             stacktrace,
+            data: None,
+        }
+    }
+
+    /// Attach additional error data to the response
+    #[must_use]
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Map an error message raised by injected JavaScript to the matching spec error.
+    ///
+    /// Injected scripts throw `Error`s whose message is [`JS_ERROR_SENTINEL`] followed by
+    /// the spec error code (e.g. `__wd:stale element reference`). Engines may put their
+    /// own prefix in front of it. Anything else, including page errors that merely
+    /// mention a code, is reported as `javascript error`.
+    pub fn from_js_error(message: &str, stacktrace: Option<String>) -> Self {
+        const CODES: &[&str] = &[
+            "stale element reference",
+            "detached shadow root",
+            "no such shadow root",
+            "no such frame",
+            "element is not a frame",
+            "no such element",
+            "invalid selector",
+            "element not interactable",
+            "invalid element state",
+            "element click intercepted",
+            "move target out of bounds",
+            "unsupported operation",
+        ];
+
+        let Some(start) = message.find(JS_ERROR_SENTINEL) else {
+            return Self::javascript_error(message, stacktrace);
+        };
+        let message = &message[start + JS_ERROR_SENTINEL.len()..];
+        match CODES.iter().find(|code| message.starts_with(*code)) {
+            Some(&"stale element reference") => Self::stale_element_reference(),
+            Some(&"detached shadow root") => Self::detached_shadow_root(),
+            Some(&"no such shadow root") => Self::no_such_shadow_root(),
+            Some(&("no such frame" | "element is not a frame")) => Self::no_such_frame(),
+            Some(&"no such element") => Self::no_such_element(),
+            Some(&"invalid selector") => Self::invalid_selector(message),
+            Some(&"element not interactable") => Self::element_not_interactable(message),
+            Some(&"invalid element state") => Self::invalid_element_state(message),
+            Some(&"element click intercepted") => Self::element_click_intercepted(message),
+            Some(&"move target out of bounds") => Self::move_target_out_of_bounds(message),
+            Some(&"unsupported operation") => Self::unsupported_operation(message),
+            _ => Self::javascript_error(message, stacktrace),
         }
     }
 
@@ -139,6 +194,10 @@ impl WebDriverErrorResponse {
         )
     }
 
+    pub fn timeout(message: &str) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "timeout", message, None)
+    }
+
     pub fn no_such_cookie(name: &str) -> Self {
         Self::new(
             StatusCode::NOT_FOUND,
@@ -165,17 +224,109 @@ impl WebDriverErrorResponse {
             None,
         )
     }
+
+    pub fn stale_element_reference() -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "stale element reference",
+            "The element reference is stale; either the element is no longer attached to the DOM or the document has been refreshed",
+            None,
+        )
+    }
+
+    pub fn detached_shadow_root() -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "detached shadow root",
+            "The shadow root is no longer attached to the DOM",
+            None,
+        )
+    }
+
+    pub fn element_click_intercepted(message: &str) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "element click intercepted",
+            message,
+            None,
+        )
+    }
+
+    pub fn invalid_element_state(message: &str) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid element state",
+            message,
+            None,
+        )
+    }
+
+    pub fn invalid_selector(message: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid selector", message, None)
+    }
+
+    pub fn invalid_cookie_domain(message: &str) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid cookie domain",
+            message,
+            None,
+        )
+    }
+
+    pub fn unable_to_set_cookie(message: &str) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to set cookie",
+            message,
+            None,
+        )
+    }
+
+    pub fn move_target_out_of_bounds(message: &str) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "move target out of bounds",
+            message,
+            None,
+        )
+    }
+
+    pub fn session_not_created(message: &str) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "session not created",
+            message,
+            None,
+        )
+    }
+
+    pub fn unexpected_alert_open(text: &str) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unexpected alert open",
+            &format!("Unexpected alert open: {text}"),
+            None,
+        )
+        .with_data(json!({ "text": text }))
+    }
+
+    pub fn unknown_command(message: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "unknown command", message, None)
+    }
 }
 
 impl IntoResponse for WebDriverErrorResponse {
     fn into_response(self) -> Response {
-        let body = json!({
-            "value": {
-                "error": self.error,
-                "message": self.message,
-                "stacktrace": self.stacktrace.unwrap_or_default()
-            }
+        let mut value = json!({
+            "error": self.error,
+            "message": self.message,
+            "stacktrace": self.stacktrace.unwrap_or_default()
         });
+        if let (Some(data), Some(obj)) = (self.data, value.as_object_mut()) {
+            obj.insert("data".to_string(), data);
+        }
+        let body = json!({ "value": value });
 
         (
             self.status,
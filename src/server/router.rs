@@ -56,6 +56,10 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/elements",
             post(handlers::element::find_all::<R>),
         )
+        .route(
+            "/session/{session_id}/tauri/elements",
+            delete(handlers::element::collect::<R>),
+        )
         .route(
             "/session/{session_id}/element/active",
             get(handlers::element::get_active::<R>),
@@ -80,6 +84,10 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/element/{element_id}/value",
             post(handlers::element::send_keys::<R>),
         )
+        .route(
+            "/session/{session_id}/element/{element_id}/tauri/select",
+            post(handlers::element::select_option::<R>),
+        )
         .route(
             "/session/{session_id}/element/{element_id}/text",
             get(handlers::element::get_text::<R>),
@@ -150,11 +158,40 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/execute/async",
             post(handlers::script::execute_async::<R>),
         )
+        .route(
+            "/session/{session_id}/tauri/preload-script",
+            post(handlers::script::add_preload_script::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/preload-script/{script_id}",
+            delete(handlers::script::remove_preload_script::<R>),
+        )
         // Screenshot
         .route(
             "/session/{session_id}/screenshot",
             get(handlers::screenshot::take::<R>),
         )
+        .route(
+            "/session/{session_id}/tauri/screenshot/window",
+            get(handlers::screenshot::take_window::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/screenshot/full",
+            get(handlers::screenshot::take_full_page::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/screenshot/region",
+            post(handlers::screenshot::take_region::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/visual/baseline/{name}",
+            post(handlers::screenshot::set_baseline::<R>)
+                .delete(handlers::screenshot::delete_baseline::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/visual/compare",
+            post(handlers::screenshot::compare::<R>),
+        )
         // Document
         .route(
             "/session/{session_id}/source",
@@ -191,6 +228,26 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/window/fullscreen",
             post(handlers::window::fullscreen::<R>),
         )
+        .route(
+            "/session/{session_id}/tauri/window/state",
+            get(handlers::window::get_state::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/window/focus",
+            post(handlers::window::focus::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/window/always-on-top",
+            post(handlers::window::set_always_on_top::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/window/decorations",
+            post(handlers::window::set_decorations::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/window/restore",
+            post(handlers::window::restore::<R>),
+        )
         // Frames
         .route(
             "/session/{session_id}/frame",
@@ -216,6 +273,45 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/cookie/{name}",
             get(handlers::cookie::get::<R>).delete(handlers::cookie::delete::<R>),
         )
+        // Web Storage
+        .route(
+            "/session/{session_id}/tauri/local-storage",
+            get(handlers::storage::get_local_keys::<R>)
+                .post(handlers::storage::set_local_item::<R>)
+                .delete(handlers::storage::clear_local::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/local-storage/{key}",
+            get(handlers::storage::get_local_item::<R>)
+                .delete(handlers::storage::remove_local_item::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/session-storage",
+            get(handlers::storage::get_session_keys::<R>)
+                .post(handlers::storage::set_session_item::<R>)
+                .delete(handlers::storage::clear_session::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/session-storage/{key}",
+            get(handlers::storage::get_session_item::<R>)
+                .delete(handlers::storage::remove_session_item::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/indexed-db",
+            get(handlers::storage::get_indexed_databases::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/indexed-db/{name}",
+            delete(handlers::storage::delete_indexed_database::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/site-data",
+            delete(handlers::storage::clear_site_data::<R>),
+        )
+        .route(
+            "/session/{session_id}/tauri/storage-partition",
+            get(handlers::storage::get_storage_partition::<R>),
+        )
         // Alerts
         .route(
             "/session/{session_id}/alert/dismiss",
@@ -234,5 +330,15 @@ pub fn create_router<R: Runtime + 'static>(state: Arc<AppState<R>>) -> Router {
             "/session/{session_id}/print",
             post(handlers::print::print::<R>),
         )
+        // Logs
+        .route(
+            "/session/{session_id}/se/log",
+            post(handlers::log::get_log::<R>),
+        )
+        .route(
+            "/session/{session_id}/se/log/types",
+            get(handlers::log::get_log_types::<R>),
+        )
+        .fallback(handlers::unknown_command)
         .with_state(state)
 }
@@ -0,0 +1,353 @@
+//! Cross-platform alert state management for `WebDriver`.
+//!
+//! This module provides per-window state for handling JavaScript alert/confirm/prompt dialogs
+//! across different platform implementations.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often a waiting dialog re-reads its timeout, so session changes reach it
+const TIMEOUT_RECHECK: Duration = Duration::from_millis(100);
+
+/// Type of pending alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertType {
+    Alert,
+    Confirm,
+    Prompt,
+}
+
+/// Response from `WebDriver` for an alert
+#[derive(Debug, Clone)]
+pub struct AlertResponse {
+    pub accepted: bool,
+    pub prompt_text: Option<String>,
+}
+
+/// How long a dialog waits for a `WebDriver` response, and how it closes if none arrives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialogTimeout {
+    pub duration: Duration,
+    /// Accept (rather than dismiss) the dialog when the timeout elapses
+    pub accept: bool,
+}
+
+impl Default for DialogTimeout {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(30),
+            accept: true,
+        }
+    }
+}
+
+/// Pending alert waiting for `WebDriver` response
+pub struct PendingAlert {
+    pub message: String,
+    pub default_text: Option<String>,
+    pub alert_type: AlertType,
+    /// Sender to signal `WebDriver`'s accept/dismiss response
+    pub responder: std::sync::mpsc::Sender<AlertResponse>,
+}
+
+/// Per-window alert state for coordinating between UI delegate and `WebDriver` commands.
+/// Dialogs are queued in the order they opened; commands act on the oldest one.
+pub struct AlertState {
+    pending: Mutex<VecDeque<(u64, PendingAlert)>>,
+    /// Text input for the current prompt dialog (set by `sendAlertText`)
+    prompt_input: Mutex<Option<String>>,
+    next_id: AtomicU64,
+    /// Shared with the manager, so session settings reach existing windows
+    timeout: Arc<Mutex<DialogTimeout>>,
+}
+
+impl AlertState {
+    /// Create a new empty alert state
+    pub fn new() -> Self {
+        Self::with_timeout(Arc::default())
+    }
+
+    fn with_timeout(timeout: Arc<Mutex<DialogTimeout>>) -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            prompt_input: Mutex::new(None),
+            next_id: AtomicU64::new(0),
+            timeout,
+        }
+    }
+
+    /// How long dialogs wait for a response before closing on their own
+    pub fn timeout(&self) -> DialogTimeout {
+        self.timeout.lock().map(|guard| *guard).unwrap_or_default()
+    }
+
+    /// Queue an alert behind any already pending, returning an id for [`Self::remove`]
+    pub fn set_pending(&self, alert: PendingAlert) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut guard) = self.pending.lock() {
+            if guard.is_empty() {
+                self.clear_prompt_input();
+            }
+            guard.push_back((id, alert));
+        }
+        id
+    }
+
+    /// Drop an alert the platform already closed on its own (e.g. after a timeout)
+    pub fn remove(&self, id: u64) {
+        if let Ok(mut guard) = self.pending.lock() {
+            if guard.front().is_some_and(|(front, _)| *front == id) {
+                self.clear_prompt_input();
+            }
+            guard.retain(|(pending, _)| *pending != id);
+        }
+    }
+
+    /// Block until `WebDriver` responds to alert `id`. If the configured timeout elapses
+    /// first, the alert is dropped and closed with the configured fallback. The timeout
+    /// is measured from when the wait started and re-read while waiting.
+    pub fn wait_for_response(&self, id: u64, responses: &Receiver<AlertResponse>) -> AlertResponse {
+        let started = Instant::now();
+        loop {
+            let timeout = self.timeout();
+            let remaining = timeout.duration.saturating_sub(started.elapsed());
+            let closed = remaining.is_zero()
+                || match responses.recv_timeout(remaining.min(TIMEOUT_RECHECK)) {
+                    Ok(response) => return response,
+                    Err(RecvTimeoutError::Timeout) => false,
+                    Err(RecvTimeoutError::Disconnected) => true,
+                };
+            if closed {
+                self.remove(id);
+                return AlertResponse {
+                    accepted: timeout.accept,
+                    prompt_text: None,
+                };
+            }
+        }
+    }
+
+    fn clear_prompt_input(&self) {
+        if let Ok(mut guard) = self.prompt_input.lock() {
+            *guard = None;
+        }
+    }
+
+    /// Set the prompt input text (only valid for Prompt type alerts)
+    pub fn set_prompt_input(&self, text: String) -> bool {
+        // Only allow setting if there's a pending prompt
+        if !matches!(self.get_alert_type(), Some(AlertType::Prompt)) {
+            return false;
+        }
+        if let Ok(mut guard) = self.prompt_input.lock() {
+            *guard = Some(text);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the current prompt input text
+    pub fn get_prompt_input(&self) -> Option<String> {
+        if let Ok(guard) = self.prompt_input.lock() {
+            guard.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Get the message of the oldest pending alert
+    pub fn get_message(&self) -> Option<String> {
+        if let Ok(guard) = self.pending.lock() {
+            guard.front().map(|(_, a)| a.message.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get the type of the oldest pending alert
+    pub fn get_alert_type(&self) -> Option<AlertType> {
+        if let Ok(guard) = self.pending.lock() {
+            guard.front().map(|(_, a)| a.alert_type)
+        } else {
+            None
+        }
+    }
+
+    /// Get the default text for prompt dialogs
+    pub fn get_default_text(&self) -> Option<String> {
+        if let Ok(guard) = self.pending.lock() {
+            guard.front().and_then(|(_, a)| a.default_text.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Send response to the oldest pending alert and dequeue it
+    pub fn respond(&self, accepted: bool, prompt_text: Option<String>) -> bool {
+        if let Ok(mut guard) = self.pending.lock() {
+            if let Some((_, alert)) = guard.pop_front() {
+                self.clear_prompt_input();
+                let _ = alert.responder.send(AlertResponse {
+                    accepted,
+                    prompt_text,
+                });
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for AlertState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Manager for per-window alert states
+pub struct AlertStateManager {
+    states: Mutex<HashMap<String, Arc<AlertState>>>,
+    timeout: Arc<Mutex<DialogTimeout>>,
+}
+
+impl AlertStateManager {
+    /// Create a new alert state manager
+    pub fn new() -> Self {
+        Self {
+            states: Mutex::new(HashMap::new()),
+            timeout: Arc::default(),
+        }
+    }
+
+    /// Set how dialogs of every window time out, including ones already waiting
+    pub fn set_timeout(&self, timeout: DialogTimeout) {
+        if let Ok(mut guard) = self.timeout.lock() {
+            *guard = timeout;
+        }
+    }
+
+    /// Get or create alert state for a window
+    pub fn get_or_create(&self, window_label: &str) -> Arc<AlertState> {
+        let mut states = self.states.lock().expect("AlertStateManager lock poisoned");
+        states
+            .entry(window_label.to_string())
+            .or_insert_with(|| Arc::new(AlertState::with_timeout(self.timeout.clone())))
+            .clone()
+    }
+
+    /// Replace the alert state for a window with a fresh one (used when a webview is created)
+    pub fn reset(&self, window_label: &str) -> Arc<AlertState> {
+        let mut states = self.states.lock().expect("AlertStateManager lock poisoned");
+        let state = Arc::new(AlertState::with_timeout(self.timeout.clone()));
+        states.insert(window_label.to_string(), state.clone());
+        state
+    }
+}
+
+impl Default for AlertStateManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{channel, TryRecvError};
+
+    fn open(
+        state: &AlertState,
+        message: &str,
+        alert_type: AlertType,
+    ) -> (u64, Receiver<AlertResponse>) {
+        let (responder, responses) = channel();
+        let id = state.set_pending(PendingAlert {
+            message: message.to_string(),
+            default_text: None,
+            alert_type,
+            responder,
+        });
+        (id, responses)
+    }
+
+    #[test]
+    fn test_alerts_are_answered_oldest_first() {
+        let state = AlertState::new();
+        let (_, first) = open(&state, "first", AlertType::Alert);
+        let (middle_id, middle) = open(&state, "middle", AlertType::Confirm);
+        let (_, last) = open(&state, "last", AlertType::Prompt);
+
+        // A middle dialog closing on its own leaves the queue order intact
+        state.remove(middle_id);
+        assert_eq!(middle.try_recv().err(), Some(TryRecvError::Disconnected));
+        assert_eq!(state.get_message().as_deref(), Some("first"));
+        assert!(!state.set_prompt_input("ignored".to_string()));
+
+        assert!(state.respond(true, None));
+        assert!(first.try_recv().expect("first response").accepted);
+
+        assert_eq!(state.get_message().as_deref(), Some("last"));
+        assert_eq!(state.get_alert_type(), Some(AlertType::Prompt));
+        assert!(state.set_prompt_input("typed".to_string()));
+        assert!(state.respond(true, state.get_prompt_input()));
+        let response = last.try_recv().expect("last response");
+        assert_eq!(response.prompt_text.as_deref(), Some("typed"));
+
+        assert_eq!(state.get_message(), None);
+        assert!(!state.respond(true, None));
+    }
+
+    #[test]
+    fn test_prompt_input_resets_with_the_front_dialog() {
+        let state = AlertState::new();
+        let (first_id, _first) = open(&state, "first", AlertType::Prompt);
+        let (_, _second) = open(&state, "second", AlertType::Prompt);
+
+        assert!(state.set_prompt_input("typed".to_string()));
+        state.remove(first_id);
+        assert_eq!(state.get_prompt_input(), None);
+        assert_eq!(state.get_message().as_deref(), Some("second"));
+
+        assert!(state.set_prompt_input("typed".to_string()));
+        assert!(state.respond(false, None));
+        assert_eq!(state.get_prompt_input(), None);
+    }
+
+    #[test]
+    fn test_unanswered_dialog_times_out() {
+        let manager = AlertStateManager::new();
+        manager.set_timeout(DialogTimeout {
+            duration: Duration::from_millis(10),
+            accept: false,
+        });
+        let state = manager.get_or_create("main");
+        let (id, responses) = open(&state, "ignored", AlertType::Confirm);
+
+        let response = state.wait_for_response(id, &responses);
+        assert!(!response.accepted);
+        assert_eq!(state.get_message(), None);
+    }
+
+    #[test]
+    fn test_timeout_change_reaches_waiting_dialog() {
+        let manager = AlertStateManager::new();
+        let state = manager.get_or_create("main");
+        let (id, responses) = open(&state, "waiting", AlertType::Alert);
+
+        let waiting = state.clone();
+        let waiter = std::thread::spawn(move || waiting.wait_for_response(id, &responses));
+        manager.set_timeout(DialogTimeout {
+            duration: Duration::ZERO,
+            accept: true,
+        });
+
+        let started = Instant::now();
+        assert!(waiter.join().expect("waiting thread").accepted);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(state.get_message(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::platform::Cookie;
+use crate::server::response::WebDriverErrorResponse;
+
+/// Largest cookie expiry `WebDriver` accepts (2^53 - 1, the largest safe JSON integer)
+const MAX_COOKIE_EXPIRY: u64 = (1 << 53) - 1;
+
+/// Cookie sent to Add Cookie. The expiry is kept as sent, so that negative or
+/// fractional values fail validation with `invalid argument` rather than deserialization.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewCookie {
+    #[serde(flatten)]
+    pub cookie: Cookie,
+    #[serde(default)]
+    pub expiry: Option<Value>,
+}
+
+/// Check a cookie from Add Cookie against the current document's host before it is
+/// written, since native stores silently drop cookies they won't accept
+pub fn validate_cookie(
+    new_cookie: NewCookie,
+    host: Option<&str>,
+    now: u64,
+) -> Result<Cookie, WebDriverErrorResponse> {
+    let mut cookie = new_cookie.cookie;
+    cookie.expiry = new_cookie
+        .expiry
+        .map(|expiry| {
+            expiry
+                .as_u64()
+                .filter(|expiry| *expiry <= MAX_COOKIE_EXPIRY)
+                .ok_or_else(|| {
+                    WebDriverErrorResponse::invalid_argument(
+                        "Cookie expiry must be an integer between 0 and 2^53 - 1",
+                    )
+                })
+        })
+        .transpose()?;
+
+    if let Some(same_site) = &cookie.same_site {
+        if !matches!(same_site.as_str(), "Strict" | "Lax" | "None") {
+            return Err(WebDriverErrorResponse::invalid_argument(&format!(
+                "Invalid sameSite value: {same_site}"
+            )));
+        }
+        if same_site == "None" && !cookie.secure {
+            return Err(WebDriverErrorResponse::unable_to_set_cookie(
+                "Cookies with sameSite None must be secure",
+            ));
+        }
+    }
+
+    if let Some(expiry) = cookie.expiry {
+        if expiry < now {
+            return Err(WebDriverErrorResponse::unable_to_set_cookie(&format!(
+                "Cookie expiry {expiry} is in the past"
+            )));
+        }
+    }
+
+    if cookie.name.contains([';', '=']) || cookie.name.trim().is_empty() {
+        return Err(WebDriverErrorResponse::unable_to_set_cookie(&format!(
+            "Invalid cookie name: {:?}",
+            cookie.name
+        )));
+    }
+    if cookie.value.contains(';') {
+        return Err(WebDriverErrorResponse::unable_to_set_cookie(
+            "Cookie value must not contain ';'",
+        ));
+    }
+
+    if let (Some(domain), Some(host)) = (&cookie.domain, host) {
+        if !domain_matches(host, domain) {
+            return Err(WebDriverErrorResponse::invalid_cookie_domain(&format!(
+                "Cookie domain {domain} does not match the current document ({host})"
+            )));
+        }
+    }
+
+    Ok(cookie)
+}
+
+/// Whether a cookie for `domain` may be set by a document on `host`: the same host,
+/// or a parent domain of it. A leading dot on the domain is ignored.
+pub fn domain_matches(host: &str, domain: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+    host == domain
+        || host
+            .strip_suffix(&domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cookie(name: &str) -> NewCookie {
+        NewCookie {
+            cookie: Cookie {
+                name: name.to_string(),
+                value: "value".to_string(),
+                path: None,
+                domain: None,
+                secure: false,
+                http_only: false,
+                expiry: None,
+                same_site: None,
+            },
+            expiry: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_cookie() {
+        let host = Some("app.example.com");
+        assert!(validate_cookie(cookie("session"), host, 100).is_ok());
+
+        let mut parent_domain = cookie("session");
+        parent_domain.cookie.domain = Some(".example.com".to_string());
+        assert!(validate_cookie(parent_domain, host, 100).is_ok());
+
+        let mut other_domain = cookie("session");
+        other_domain.cookie.domain = Some("other.com".to_string());
+        let error = validate_cookie(other_domain, host, 100).err();
+        assert_eq!(
+            error.map(|e| e.error),
+            Some("invalid cookie domain".to_string())
+        );
+
+        let mut same_site = cookie("session");
+        same_site.cookie.same_site = Some("lax".to_string());
+        let error = validate_cookie(same_site, host, 100).err();
+        assert_eq!(error.map(|e| e.error), Some("invalid argument".to_string()));
+
+        let mut expired = cookie("session");
+        expired.expiry = Some(json!(99));
+        let error = validate_cookie(expired, host, 100).err();
+        assert_eq!(
+            error.map(|e| e.error),
+            Some("unable to set cookie".to_string())
+        );
+
+        assert!(validate_cookie(cookie("a=b"), host, 100).is_err());
+    }
+
+    #[test]
+    fn test_validate_cookie_expiry() {
+        let parse = |cookie: Value| -> NewCookie {
+            serde_json::from_value(cookie).expect("cookie deserializes")
+        };
+
+        let valid = parse(json!({ "name": "session", "value": "value", "expiry": 200 }));
+        let valid = validate_cookie(valid, None, 100).expect("valid expiry");
+        assert_eq!(valid.expiry, Some(200));
+
+        for expiry in [json!(-1), json!(150.5), json!("200"), json!(1_u64 << 53)] {
+            let invalid = parse(json!({ "name": "session", "value": "value", "expiry": expiry }));
+            let error = validate_cookie(invalid, None, 100).err();
+            assert_eq!(error.map(|e| e.error), Some("invalid argument".to_string()));
+        }
+
+        let session = parse(json!({ "name": "session", "value": "value", "expiry": null }));
+        let session = validate_cookie(session, None, 100).expect("session cookie");
+        assert_eq!(session.expiry, None);
+    }
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches("www.Example.com", ".example.com"));
+        assert!(!domain_matches("badexample.com", "example.com"));
+        assert!(!domain_matches("example.com", "www.example.com"));
+    }
+}
@@ -1,48 +1,215 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use uuid::Uuid;
+use serde_json::Value;
+
+use crate::platform::FrameId;
+use crate::server::response::WebDriverErrorResponse;
+
+/// JSON key identifying a web element reference
+pub const ELEMENT_KEY: &str = "element-6066-11e4-a52e-4f735466cecf";
+
+/// JSON key identifying a shadow root reference
+pub const SHADOW_KEY: &str = "shadow-6066-11e4-a52e-4f735466cecf";
+
+/// JSON key identifying a frame window reference
+pub const FRAME_KEY: &str = "frame-075b-4da1-b6ba-e579c2d3230a";
+
+/// Criterion for picking an option of a `<select>` element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionSelector {
+    /// Option whose `value` matches
+    Value(String),
+    /// Option whose visible label matches, ignoring whitespace differences
+    Label(String),
+    /// Option at a zero-based index
+    Index(usize),
+}
+
+impl OptionSelector {
+    /// JavaScript predicate over `(option, index)` matching the selected option
+    pub fn matcher_js(&self) -> String {
+        let quote = |text: &str| Value::from(text).to_string();
+        match self {
+            Self::Value(value) => {
+                format!(
+                    "function(option) {{ return option.value === {}; }}",
+                    quote(value)
+                )
+            }
+            Self::Label(label) => {
+                let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+                format!(
+                    "function(option) {{ return option.label.replace(/\\s+/g, ' ').trim() === {}; }}",
+                    quote(&label)
+                )
+            }
+            Self::Index(index) => {
+                format!("function(option, index) {{ return index === {index}; }}")
+            }
+        }
+    }
+}
+
+/// Number of collected IDs kept to report them as stale. Older ones are forgotten and
+/// then fail with `no such element`, so long sessions don't grow the store without bound.
+const STALE_ID_LIMIT: usize = 10_000;
 
 /// Represents a `WebDriver` element reference
 #[derive(Debug, Clone)]
 pub struct ElementRef {
-    /// `WebDriver` element ID (returned to client)
+    /// `WebDriver` element ID (returned to client), which is also the key of the element
+    /// in the page's element reference store
     pub id: String,
-    /// JavaScript variable name holding the element reference
-    pub js_ref: String,
+    /// Frame context (browsing context) the element was found in. The reference lives
+    /// in that frame's store, so it is only valid while the frame is selected.
+    pub frame_context: Vec<FrameId>,
 }
 
 /// Storage for element references within a session
 #[derive(Debug, Default)]
 pub struct ElementStore {
     elements: HashMap<String, ElementRef>,
+    /// IDs of collected elements, which stay known so lookups report them as stale
+    stale: HashSet<String>,
+    /// The same IDs, oldest first, to forget beyond `STALE_ID_LIMIT`
+    stale_order: VecDeque<String>,
 }
 
 impl ElementStore {
     pub fn new() -> Self {
         Self {
             elements: HashMap::new(),
+            stale: HashSet::new(),
+            stale_order: VecDeque::new(),
+        }
+    }
+
+    /// Register an element the page stored under `id` in `frame_context`. The page keeps
+    /// the key of an element it stored before, so the same element always has the same ID.
+    pub fn adopt(&mut self, id: &str, frame_context: &[FrameId]) {
+        self.elements
+            .entry(id.to_string())
+            .or_insert_with(|| ElementRef {
+                id: id.to_string(),
+                frame_context: frame_context.to_vec(),
+            });
+    }
+
+    /// Mark elements found inside frames as stale, as their documents are gone after a
+    /// navigation
+    pub fn remove_frame_elements(&mut self) {
+        self.remove_where(|element| !element.frame_context.is_empty());
+    }
+
+    /// Page-side keys of the elements found in `frame_context`
+    pub fn js_refs_in_frame(&self, frame_context: &[FrameId]) -> Vec<String> {
+        self.elements
+            .values()
+            .filter(|element| element.frame_context == frame_context)
+            .map(|element| element.id.clone())
+            .collect()
+    }
+
+    /// Mark the elements whose page-side references were collected as stale
+    pub fn remove_js_refs(&mut self, js_refs: &[String]) {
+        let js_refs: HashSet<&str> = js_refs.iter().map(String::as_str).collect();
+        self.remove_where(|element| js_refs.contains(element.id.as_str()));
+    }
+
+    /// Remove the elements matching `collected` and remember their IDs as stale
+    fn remove_where(&mut self, collected: impl Fn(&ElementRef) -> bool) {
+        let ids: Vec<String> = self
+            .elements
+            .values()
+            .filter(|element| collected(element))
+            .map(|element| element.id.clone())
+            .collect();
+        for id in ids {
+            self.elements.remove(&id);
+            if self.stale.insert(id.clone()) {
+                self.stale_order.push_back(id);
+            }
+        }
+        while self.stale_order.len() > STALE_ID_LIMIT {
+            if let Some(id) = self.stale_order.pop_front() {
+                self.stale.remove(&id);
+            }
         }
     }
 
-    /// Store a new element and return its reference
-    pub fn store(&mut self) -> ElementRef {
-        let id = Uuid::new_v4().to_string();
-        // Remove hyphens from UUID for valid JS variable name
-        let id_no_hyphens = id.replace('-', "");
-        let js_ref = format!("__wd_el_{id_no_hyphens}");
+    /// Get element by `WebDriver` ID, if it belongs to the given frame context.
+    /// Per spec, elements of other browsing contexts are unknown to the current one.
+    pub fn get(&self, id: &str, frame_context: &[FrameId]) -> Option<&ElementRef> {
+        self.elements
+            .get(id)
+            .filter(|element| element.frame_context == frame_context)
+    }
 
-        let elem_ref = ElementRef {
-            id: id.clone(),
-            js_ref,
-        };
+    /// Whether `id` referred to an element that has since been collected
+    pub fn is_stale(&self, id: &str) -> bool {
+        self.stale.contains(id)
+    }
 
-        self.elements.insert(id, elem_ref.clone());
-        elem_ref
+    /// Get element by `WebDriver` ID, failing with `stale element reference` for collected
+    /// elements and `no such element` for unknown ones
+    pub fn lookup(
+        &self,
+        id: &str,
+        frame_context: &[FrameId],
+    ) -> Result<&ElementRef, WebDriverErrorResponse> {
+        self.get(id, frame_context).ok_or_else(|| {
+            if self.is_stale(id) {
+                WebDriverErrorResponse::stale_element_reference()
+            } else {
+                WebDriverErrorResponse::no_such_element()
+            }
+        })
     }
 
-    /// Get element by `WebDriver` ID
-    pub fn get(&self, id: &str) -> Option<&ElementRef> {
-        self.elements.get(id)
+    /// Get shadow root by `WebDriver` ID, failing with `detached shadow root` for collected
+    /// roots and `no such shadow root` for unknown ones
+    pub fn lookup_shadow_root(
+        &self,
+        id: &str,
+        frame_context: &[FrameId],
+    ) -> Result<&ElementRef, WebDriverErrorResponse> {
+        self.get(id, frame_context).ok_or_else(|| {
+            if self.is_stale(id) {
+                WebDriverErrorResponse::detached_shadow_root()
+            } else {
+                WebDriverErrorResponse::no_such_shadow_root()
+            }
+        })
+    }
+}
+
+/// Collect the IDs of all element, shadow root and frame references in serialized script data
+pub fn element_ids_in(value: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    collect_reference_ids(value, &[ELEMENT_KEY, SHADOW_KEY, FRAME_KEY], &mut ids);
+    ids
+}
+
+fn collect_reference_ids(value: &Value, keys: &[&str], ids: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_reference_ids(item, keys, ids);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(id) = keys
+                .iter()
+                .find_map(|key| map.get(*key).and_then(Value::as_str))
+            {
+                ids.push(id.to_string());
+                return;
+            }
+            for item in map.values() {
+                collect_reference_ids(item, keys, ids);
+            }
+        }
+        _ => {}
     }
 }
 
@@ -51,40 +218,127 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_store_element() {
+    fn test_adopt_element() {
         let mut store = ElementStore::new();
-        let elem = store.store();
+        store.adopt("a", &[]);
 
-        assert!(!elem.id.is_empty());
-        assert!(elem.js_ref.starts_with("__wd_el_"));
-        // js_ref uses ID without hyphens
-        assert!(elem.js_ref.contains(&elem.id.replace('-', "")));
+        let retrieved = store.get("a", &[]).expect("element should exist");
+        assert_eq!(retrieved.id, "a");
     }
 
     #[test]
-    fn test_get_element() {
+    fn test_adopt_keeps_first_frame() {
         let mut store = ElementStore::new();
-        let elem = store.store();
-        let id = elem.id.clone();
+        let frame = vec![FrameId::Index(0)];
+        store.adopt("a", &frame);
 
-        let retrieved = store.get(&id).expect("element should exist");
-        assert_eq!(retrieved.id, id);
+        // Finding the element again doesn't move it to another frame
+        store.adopt("a", &[]);
+        assert!(store.get("a", &frame).is_some());
+        assert!(store.get("a", &[]).is_none());
     }
 
     #[test]
-    fn test_js_ref_uses_id_without_hyphens() {
+    fn test_remove_collected_elements() {
         let mut store = ElementStore::new();
-        let elem1 = store.store();
-        let elem2 = store.store();
+        let frame = vec![FrameId::Index(0)];
+        store.adopt("top", &[]);
+        store.adopt("nested", &frame);
+
+        assert_eq!(store.js_refs_in_frame(&[]), vec!["top".to_string()]);
+
+        store.remove_js_refs(&["top".to_string()]);
+        assert!(store.get("top", &[]).is_none());
+        assert!(store.get("nested", &frame).is_some());
 
-        // js_ref should use ID with hyphens removed for valid JS variable name
+        // Collected IDs stay known as stale, unlike IDs never handed out
+        let error = store
+            .lookup("top", &[])
+            .expect_err("collected element is stale");
+        assert_eq!(error.error, "stale element reference");
+        let error = store.lookup("unknown", &[]).expect_err("unknown element");
+        assert_eq!(error.error, "no such element");
+        let error = store
+            .lookup_shadow_root("top", &[])
+            .expect_err("collected shadow root is detached");
+        assert_eq!(error.error, "detached shadow root");
+    }
+
+    #[test]
+    fn test_stale_ids_are_bounded() {
+        let mut store = ElementStore::new();
+        let ids: Vec<String> = (0..=STALE_ID_LIMIT).map(|i| i.to_string()).collect();
+        for id in &ids {
+            store.adopt(id, &[]);
+        }
+
+        store.remove_js_refs(&ids[..1]);
+        store.remove_js_refs(&ids[1..]);
+
+        // The oldest collected ID is forgotten, the newer ones still report as stale
+        let error = store
+            .lookup(&ids[0], &[])
+            .expect_err("forgotten element is unknown");
+        assert_eq!(error.error, "no such element");
+        let error = store
+            .lookup(&ids[1], &[])
+            .expect_err("collected element is stale");
+        assert_eq!(error.error, "stale element reference");
+    }
+
+    #[test]
+    fn test_script_result_element_ids() {
+        let result = serde_json::json!({
+            "button": { ELEMENT_KEY: "a" },
+            "items": [{ ELEMENT_KEY: "b" }, 1, "text"],
+            "root": { SHADOW_KEY: "c" },
+        });
+        let mut ids = element_ids_in(&result);
+        ids.sort();
+        assert_eq!(ids, vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_element_is_scoped_to_its_frame() {
+        let mut store = ElementStore::new();
+        let frame = vec![FrameId::Index(0)];
+        store.adopt("a", &frame);
+
+        assert!(store.get("a", &frame).is_some());
+        // Not visible from the top-level context or another frame
+        assert!(store.get("a", &[]).is_none());
+        assert!(store.get("a", &[FrameId::Index(1)]).is_none());
+    }
+
+    #[test]
+    fn test_remove_frame_elements_keeps_top_level() {
+        let mut store = ElementStore::new();
+        store.adopt("top", &[]);
+        store.adopt("framed", &[FrameId::Index(0)]);
+
+        store.remove_frame_elements();
+        assert!(store.get("top", &[]).is_some());
+        assert!(store.get("framed", &[FrameId::Index(0)]).is_none());
+        let error = store
+            .lookup("framed", &[FrameId::Index(0)])
+            .expect_err("framed element should be gone");
+        assert_eq!(error.error, "stale element reference");
+    }
+
+    #[test]
+    fn test_option_selector_matcher() {
+        assert_eq!(
+            OptionSelector::Index(2).matcher_js(),
+            "function(option, index) { return index === 2; }"
+        );
         assert_eq!(
-            elem1.js_ref,
-            format!("__wd_el_{}", elem1.id.replace('-', ""))
+            OptionSelector::Value("it's \"quoted\"".to_string()).matcher_js(),
+            r#"function(option) { return option.value === "it's \"quoted\""; }"#
         );
+        // Labels compare with collapsed whitespace, like the rendered label
         assert_eq!(
-            elem2.js_ref,
-            format!("__wd_el_{}", elem2.id.replace('-', ""))
+            OptionSelector::Label("  Two\n words ".to_string()).matcher_js(),
+            r#"function(option) { return option.label.replace(/\s+/g, ' ').trim() === "Two words"; }"#
         );
     }
 }
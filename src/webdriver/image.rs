@@ -0,0 +1,290 @@
+use std::io::Cursor;
+
+use base64::prelude::*;
+
+use crate::server::response::WebDriverErrorResponse;
+
+/// Screenshot decoded to 8-bit RGBA, for post-processing that platforms don't do natively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major RGBA pixels
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Decode a PNG of any color type and bit depth. The decoder's allocation limit is
+    /// sized from the header, as full-page captures easily exceed the 64 MiB default.
+    pub fn from_png(data: &[u8]) -> Result<Self, WebDriverErrorResponse> {
+        let invalid = |e: png::DecodingError| {
+            WebDriverErrorResponse::unknown_error(&format!("Invalid PNG: {e}"))
+        };
+
+        let default_limit = png::Limits::default().bytes;
+        let mut header = png::Decoder::new(Cursor::new(data));
+        let raw_bytes = header.read_header_info().map_err(invalid)?.raw_bytes();
+        let limits = png::Limits {
+            bytes: raw_bytes.saturating_mul(2).max(default_limit),
+        };
+
+        let mut decoder = png::Decoder::new_with_limits(Cursor::new(data), limits);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(invalid)?;
+        let size = reader
+            .output_buffer_size()
+            .ok_or_else(|| WebDriverErrorResponse::unknown_error("PNG is too large"))?;
+        let mut buffer = vec![0; size];
+        let info = reader.next_frame(&mut buffer).map_err(invalid)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], u8::MAX])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&v| [v, v, v, u8::MAX]).collect(),
+            png::ColorType::Indexed => {
+                return Err(WebDriverErrorResponse::unknown_error(
+                    "Indexed PNG was not expanded",
+                ))
+            }
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    /// Decode a base64-encoded PNG, as returned by the screenshot commands
+    pub fn from_base64_png(data: &str) -> Result<Self, WebDriverErrorResponse> {
+        let bytes = BASE64_STANDARD.decode(data).map_err(|e| {
+            WebDriverErrorResponse::invalid_argument(&format!("Invalid base64: {e}"))
+        })?;
+        Self::from_png(&bytes)
+    }
+
+    /// Encode as an RGBA PNG
+    pub fn to_png(&self) -> Result<Vec<u8>, WebDriverErrorResponse> {
+        let failed = |e: png::EncodingError| WebDriverErrorResponse::unknown_error(&format!("{e}"));
+
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(failed)?;
+        writer.write_image_data(&self.pixels).map_err(failed)?;
+        writer.finish().map_err(failed)?;
+        Ok(data)
+    }
+
+    /// Encode as a base64 PNG, as returned by the screenshot commands
+    pub fn to_base64_png(&self) -> Result<String, WebDriverErrorResponse> {
+        Ok(BASE64_STANDARD.encode(self.to_png()?))
+    }
+
+    /// Copy of the `width` x `height` area at (`x`, `y`), clamped to the image bounds
+    #[must_use]
+    pub fn cropped(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let row_bytes = width as usize * 4;
+
+        let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+        for row in y..y + height {
+            let offset = (row as usize * self.width as usize + x as usize) * 4;
+            pixels.extend_from_slice(&self.pixels[offset..offset + row_bytes]);
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// RGBA channels of the pixel at (`x`, `y`), if it is inside the image
+    fn pixel(&self, x: u32, y: u32) -> Option<&[u8]> {
+        (x < self.width && y < self.height).then(|| {
+            let offset = (y as usize * self.width as usize + x as usize) * 4;
+            &self.pixels[offset..offset + 4]
+        })
+    }
+
+    /// Compare against `baseline` pixel by pixel. Pixels match when no RGBA channel
+    /// differs by more than `tolerance`; images of different sizes are compared over
+    /// the larger extent of both.
+    #[must_use]
+    pub fn diff(&self, baseline: &Image, tolerance: u8) -> ImageDiff {
+        const HIGHLIGHT: [u8; 4] = [255, 0, 0, 255];
+        let width = self.width.max(baseline.width);
+        let height = self.height.max(baseline.height);
+
+        let mut different_pixels = 0;
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                match (self.pixel(x, y), baseline.pixel(x, y)) {
+                    (Some(actual), Some(expected))
+                        if actual
+                            .iter()
+                            .zip(expected)
+                            .all(|(a, b)| a.abs_diff(*b) <= tolerance) =>
+                    {
+                        // Faded luma, so the highlighted pixels stand out
+                        let luma = (u32::from(actual[0]) * 3
+                            + u32::from(actual[1]) * 6
+                            + u32::from(actual[2]))
+                            / 10;
+                        let faded = u8::try_from(170 + luma / 3).unwrap_or(u8::MAX);
+                        pixels.extend([faded, faded, faded, u8::MAX]);
+                    }
+                    _ => {
+                        different_pixels += 1;
+                        pixels.extend(HIGHLIGHT);
+                    }
+                }
+            }
+        }
+
+        ImageDiff {
+            different_pixels,
+            total_pixels: u64::from(width) * u64::from(height),
+            image: Self {
+                width,
+                height,
+                pixels,
+            },
+        }
+    }
+
+    /// Resample to `width` x `height`, averaging the source pixels each target pixel covers
+    #[must_use]
+    pub fn resized(&self, width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let source_width = self.width as usize;
+        // Source pixels covered by target pixel `i` of `count`, out of `total`
+        let span = |i: usize, count: usize, total: usize| {
+            let start = i * total / count;
+            start..((i + 1) * total / count).max(start + 1).min(total)
+        };
+
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height as usize {
+            let rows = span(y, height as usize, self.height as usize);
+            for x in 0..width as usize {
+                let columns = span(x, width as usize, source_width);
+                let mut sum = [0u64; 4];
+                for row in rows.clone() {
+                    for column in columns.clone() {
+                        let offset = (row * source_width + column) * 4;
+                        for (total, value) in sum.iter_mut().zip(&self.pixels[offset..offset + 4]) {
+                            *total += u64::from(*value);
+                        }
+                    }
+                }
+                let count = (rows.len() * columns.len()).max(1) as u64;
+                pixels.extend(sum.map(|total| u8::try_from(total / count).unwrap_or(u8::MAX)));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Result of comparing a screenshot against a baseline
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    /// Pixels whose channels differ by more than the tolerance, including pixels only
+    /// one of the images covers
+    pub different_pixels: u64,
+    pub total_pixels: u64,
+    /// The compared image faded to grey, with differing pixels in red
+    pub image: Image,
+}
+
+impl ImageDiff {
+    /// Share of differing pixels, from 0 to 100
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percentage(&self) -> f64 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.different_pixels as f64 * 100.0 / self.total_pixels as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        // 4x2: black/white pairs on the top row, solid red on the bottom row
+        let black = [0, 0, 0, 255];
+        let white = [255, 255, 255, 255];
+        let red = [255, 0, 0, 255];
+        Image {
+            width: 4,
+            height: 2,
+            pixels: [black, white, black, white, red, red, red, red].concat(),
+        }
+    }
+
+    #[test]
+    fn test_png_round_trip_resize_and_crop() {
+        let image = checkerboard();
+        let encoded = image.to_base64_png().expect("image should encode");
+        let decoded = Image::from_base64_png(&encoded).expect("encoded image should decode");
+        assert_eq!(decoded, image);
+
+        let half = image.resized(2, 1);
+        assert_eq!((half.width, half.height), (2, 1));
+        // Each target pixel averages a black, a white and two red pixels
+        assert_eq!(&half.pixels[..4], &[191, 63, 63, 255]);
+
+        let same = image.resized(4, 2);
+        assert_eq!(same, image);
+
+        let corner = image.cropped(3, 0, 5, 5);
+        assert_eq!((corner.width, corner.height), (1, 2));
+        assert_eq!(corner.pixels, [255, 255, 255, 255, 255, 0, 0, 255]);
+        assert_eq!(image.cropped(9, 9, 1, 1).pixels, Vec::<u8>::new());
+
+        assert!(Image::from_png(b"not a png").is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let image = checkerboard();
+        let same = image.diff(&image, 0);
+        assert_eq!(same.different_pixels, 0);
+        assert!(same.percentage().abs() < f64::EPSILON);
+
+        let mut changed = image.clone();
+        changed.pixels[0] = 10;
+        assert_eq!(changed.diff(&image, 10).different_pixels, 0);
+        let diff = changed.diff(&image, 9);
+        assert_eq!(diff.different_pixels, 1);
+        assert!((diff.percentage() - 12.5).abs() < f64::EPSILON);
+        assert_eq!(&diff.image.pixels[..4], &[255, 0, 0, 255]);
+
+        // Pixels only one image covers count as different
+        let wider = image.diff(&image.cropped(0, 0, 2, 2), 0);
+        assert_eq!((wider.image.width, wider.image.height), (4, 2));
+        assert_eq!(wider.different_pixels, 4);
+    }
+}
@@ -0,0 +1,244 @@
+/// Unicode codepoint `WebDriver` uses for the Null key, which releases all modifiers
+pub const NULL_KEY: char = '\u{E000}';
+
+/// A segment of an Element Send Keys text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyInput {
+    /// A run of typeable characters
+    Text(String),
+    /// A run of characters entered through an input method (IME) composition
+    Composition(String),
+    /// A single normalized key from the Unicode Private Use Area
+    Special(char),
+}
+
+/// Whether `key` is a normalized `WebDriver` key codepoint (Unicode Private Use Area)
+pub fn is_special_key(key: char) -> bool {
+    ('\u{E000}'..='\u{F8FF}').contains(&key)
+}
+
+/// Whether `key` is a modifier key that toggles during Element Send Keys
+pub fn is_modifier_key(key: char) -> bool {
+    matches!(key, '\u{E008}' | '\u{E009}' | '\u{E00A}' | '\u{E03D}')
+}
+
+/// DOM `key` value of a normalized `WebDriver` key codepoint. Numpad keys map to the
+/// character they type and right-hand modifiers to their generic names.
+pub fn normalized_key(key: char) -> Option<&'static str> {
+    let name = match key {
+        '\u{E000}' => "Unidentified",
+        '\u{E001}' => "Cancel",
+        '\u{E002}' => "Help",
+        '\u{E003}' => "Backspace",
+        '\u{E004}' => "Tab",
+        '\u{E005}' => "Clear",
+        '\u{E006}' | '\u{E007}' => "Enter",
+        '\u{E008}' | '\u{E050}' => "Shift",
+        '\u{E009}' | '\u{E051}' => "Control",
+        '\u{E00A}' | '\u{E052}' => "Alt",
+        '\u{E00B}' => "Pause",
+        '\u{E00C}' => "Escape",
+        '\u{E00D}' => " ",
+        '\u{E00E}' | '\u{E054}' => "PageUp",
+        '\u{E00F}' | '\u{E055}' => "PageDown",
+        '\u{E010}' | '\u{E056}' => "End",
+        '\u{E011}' | '\u{E057}' => "Home",
+        '\u{E012}' | '\u{E058}' => "ArrowLeft",
+        '\u{E013}' | '\u{E059}' => "ArrowUp",
+        '\u{E014}' | '\u{E05A}' => "ArrowRight",
+        '\u{E015}' | '\u{E05B}' => "ArrowDown",
+        '\u{E016}' | '\u{E05C}' => "Insert",
+        '\u{E017}' | '\u{E05D}' => "Delete",
+        '\u{E018}' => ";",
+        '\u{E019}' => "=",
+        '\u{E01A}' => "0",
+        '\u{E01B}' => "1",
+        '\u{E01C}' => "2",
+        '\u{E01D}' => "3",
+        '\u{E01E}' => "4",
+        '\u{E01F}' => "5",
+        '\u{E020}' => "6",
+        '\u{E021}' => "7",
+        '\u{E022}' => "8",
+        '\u{E023}' => "9",
+        '\u{E024}' => "*",
+        '\u{E025}' => "+",
+        '\u{E026}' => ",",
+        '\u{E027}' => "-",
+        '\u{E028}' => ".",
+        '\u{E029}' => "/",
+        '\u{E031}' => "F1",
+        '\u{E032}' => "F2",
+        '\u{E033}' => "F3",
+        '\u{E034}' => "F4",
+        '\u{E035}' => "F5",
+        '\u{E036}' => "F6",
+        '\u{E037}' => "F7",
+        '\u{E038}' => "F8",
+        '\u{E039}' => "F9",
+        '\u{E03A}' => "F10",
+        '\u{E03B}' => "F11",
+        '\u{E03C}' => "F12",
+        '\u{E03D}' | '\u{E053}' => "Meta",
+        '\u{E040}' => "ZenkakuHankaku",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// DOM `code` of the key that types `key` on a US keyboard, from the `WebDriver`
+/// code table. Characters outside the table have no code.
+pub fn key_code(key: char) -> Option<&'static str> {
+    let code = match key {
+        'a' | 'A' => "KeyA",
+        'b' | 'B' => "KeyB",
+        'c' | 'C' => "KeyC",
+        'd' | 'D' => "KeyD",
+        'e' | 'E' => "KeyE",
+        'f' | 'F' => "KeyF",
+        'g' | 'G' => "KeyG",
+        'h' | 'H' => "KeyH",
+        'i' | 'I' => "KeyI",
+        'j' | 'J' => "KeyJ",
+        'k' | 'K' => "KeyK",
+        'l' | 'L' => "KeyL",
+        'm' | 'M' => "KeyM",
+        'n' | 'N' => "KeyN",
+        'o' | 'O' => "KeyO",
+        'p' | 'P' => "KeyP",
+        'q' | 'Q' => "KeyQ",
+        'r' | 'R' => "KeyR",
+        's' | 'S' => "KeyS",
+        't' | 'T' => "KeyT",
+        'u' | 'U' => "KeyU",
+        'v' | 'V' => "KeyV",
+        'w' | 'W' => "KeyW",
+        'x' | 'X' => "KeyX",
+        'y' | 'Y' => "KeyY",
+        'z' | 'Z' => "KeyZ",
+        '0' | ')' => "Digit0",
+        '1' | '!' => "Digit1",
+        '2' | '@' => "Digit2",
+        '3' | '#' => "Digit3",
+        '4' | '$' => "Digit4",
+        '5' | '%' => "Digit5",
+        '6' | '^' => "Digit6",
+        '7' | '&' => "Digit7",
+        '8' | '*' => "Digit8",
+        '9' | '(' => "Digit9",
+        '`' | '~' => "Backquote",
+        '-' | '_' => "Minus",
+        '=' | '+' => "Equal",
+        '[' | '{' => "BracketLeft",
+        ']' | '}' => "BracketRight",
+        '\\' | '|' => "Backslash",
+        ';' | ':' => "Semicolon",
+        '\'' | '"' => "Quote",
+        ',' | '<' => "Comma",
+        '.' | '>' => "Period",
+        '/' | '?' => "Slash",
+        ' ' => "Space",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Whether typing `key` on a US keyboard takes Shift, from the `WebDriver` shifted
+/// character table
+pub fn is_shifted_character(key: char) -> bool {
+    key.is_ascii_uppercase() || "~!@#$%^&*()_+{}|:\"<>?".contains(key)
+}
+
+/// Whether `key` is typically entered through an IME rather than typed directly:
+/// CJK ideographs and punctuation, kana, Hangul, Bopomofo and full-width forms
+pub fn is_composed_character(key: char) -> bool {
+    matches!(
+        key,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3000}'..='\u{318F}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// Split Element Send Keys text into typeable runs, IME compositions and special keys
+pub fn parse_key_sequence(text: &str) -> Vec<KeyInput> {
+    let mut inputs = Vec::new();
+    let mut run = String::new();
+    let mut composing = false;
+
+    let flush = |run: &mut String, composing: bool, inputs: &mut Vec<KeyInput>| {
+        if !run.is_empty() {
+            let run = std::mem::take(run);
+            inputs.push(if composing {
+                KeyInput::Composition(run)
+            } else {
+                KeyInput::Text(run)
+            });
+        }
+    };
+
+    for ch in text.chars() {
+        if is_special_key(ch) {
+            flush(&mut run, composing, &mut inputs);
+            inputs.push(KeyInput::Special(ch));
+        } else {
+            if is_composed_character(ch) != composing {
+                flush(&mut run, composing, &mut inputs);
+                composing = !composing;
+            }
+            run.push(ch);
+        }
+    }
+    flush(&mut run, composing, &mut inputs);
+
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_sequence() {
+        assert_eq!(
+            parse_key_sequence("user\u{E004}name\u{E007}"),
+            vec![
+                KeyInput::Text("user".to_string()),
+                KeyInput::Special('\u{E004}'),
+                KeyInput::Text("name".to_string()),
+                KeyInput::Special('\u{E007}'),
+            ]
+        );
+        assert_eq!(parse_key_sequence(""), Vec::new());
+        assert_eq!(
+            parse_key_sequence("a日本語b\u{E007}"),
+            vec![
+                KeyInput::Text("a".to_string()),
+                KeyInput::Composition("日本語".to_string()),
+                KeyInput::Text("b".to_string()),
+                KeyInput::Special('\u{E007}'),
+            ]
+        );
+        assert!(is_composed_character('한'));
+        assert!(!is_composed_character('é'));
+        assert!(is_modifier_key('\u{E009}'));
+        assert!(!is_modifier_key(NULL_KEY));
+        assert_eq!(normalized_key('\u{E007}'), Some("Enter"));
+        assert_eq!(normalized_key('\u{E050}'), Some("Shift"));
+        assert_eq!(normalized_key('\u{E01A}'), Some("0"));
+        assert_eq!(normalized_key('a'), None);
+        assert_eq!(key_code('!'), Some("Digit1"));
+        assert_eq!(key_code('A'), Some("KeyA"));
+        assert_eq!(key_code('"'), Some("Quote"));
+        assert_eq!(key_code('é'), None);
+        assert!(is_shifted_character('A'));
+        assert!(is_shifted_character('?'));
+        assert!(!is_shifted_character('a'));
+        assert!(!is_shifted_character('/'));
+    }
+}
@@ -1,28 +1,254 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::platform::{COMPUTED_LABEL_JS, COMPUTED_ROLE_JS};
+
+/// Generates a JavaScript expression for a custom locator strategy.
+///
+/// Called with the search root expression (`document`, `parent` or `shadow`) and the
+/// locator value as a quoted JavaScript string literal. The returned expression must
+/// evaluate to an array-like collection of matching elements in document order.
+pub type LocatorScriptFn = dyn Fn(&str, &str) -> String + Send + Sync;
+
+/// Default attribute matched by the `test id` strategy
+pub const DEFAULT_TEST_ID_ATTRIBUTE: &str = "data-testid";
+
+/// Locator configuration supplied through the plugin builder
+#[derive(Clone)]
+pub struct LocatorRegistry {
+    strategies: HashMap<String, Arc<LocatorScriptFn>>,
+    test_id_attribute: String,
+}
+
+impl Default for LocatorRegistry {
+    fn default() -> Self {
+        Self {
+            strategies: HashMap::new(),
+            test_id_attribute: DEFAULT_TEST_ID_ATTRIBUTE.to_string(),
+        }
+    }
+}
+
+impl LocatorRegistry {
+    /// Set the attribute matched by the `test id` strategy
+    pub fn set_test_id_attribute(&mut self, attribute: impl Into<String>) {
+        self.test_id_attribute = attribute.into();
+    }
+
+    /// Register a strategy under a `WebDriver` `using` name, replacing any previous one
+    pub fn register(&mut self, name: impl Into<String>, script: Arc<LocatorScriptFn>) {
+        self.strategies.insert(name.into(), script);
+    }
+
+    fn get(&self, name: &str) -> Option<CustomLocator> {
+        self.strategies.get(name).map(|script| CustomLocator {
+            name: name.to_string(),
+            script: Arc::clone(script),
+        })
+    }
+}
+
+impl fmt::Debug for LocatorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocatorRegistry")
+            .field("strategies", &self.strategies.keys().collect::<Vec<_>>())
+            .field("test_id_attribute", &self.test_id_attribute)
+            .finish()
+    }
+}
+
+/// A registered custom locator strategy
+#[derive(Clone)]
+pub struct CustomLocator {
+    name: String,
+    script: Arc<LocatorScriptFn>,
+}
+
+impl CustomLocator {
+    /// Expression evaluating to an array of all matches below `root`
+    fn all_js(&self, root: &str, value: &str) -> String {
+        let value = serde_json::Value::from(value).to_string();
+        format!("Array.from(({})({root}) || [])", self.wrap(&value))
+    }
+
+    /// Expression evaluating to the first match below `root`, or null
+    fn first_js(&self, root: &str, value: &str) -> String {
+        format!("({}[0] || null)", self.all_js(root, value))
+    }
+
+    /// Wrap the generated expression in a function of the root so it is evaluated lazily
+    fn wrap(&self, value: &str) -> String {
+        format!(
+            "(function(__root) {{ return {}; }})",
+            (self.script)("__root", value)
+        )
+    }
+}
+
+impl fmt::Debug for CustomLocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomLocator").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for CustomLocator {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomLocator {}
+
+/// Build a JavaScript expression for the attribute selector matching a test id.
+/// `escaped` must already be escaped for a single-quoted JavaScript string.
+fn test_id_selector(attribute: &str, escaped: &str) -> String {
+    let attribute = attribute.replace('\\', "\\\\").replace('\'', "\\'");
+    format!("'[' + CSS.escape('{attribute}') + '=\"' + CSS.escape('{escaped}') + '\"]'")
+}
+
+/// Parse an `aria` locator value of the form `role` or `role[name='Accessible name']`
+fn parse_aria_selector(value: &str) -> Option<(&str, Option<String>)> {
+    let value = value.trim();
+    let (role, rest) = match value.find('[') {
+        Some(index) => (value[..index].trim_end(), Some(&value[index..])),
+        None => (value, None),
+    };
+    if role.is_empty()
+        || !role
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    let Some(rest) = rest else {
+        return Some((role, None));
+    };
+
+    let rest = rest
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .trim()
+        .strip_prefix("name")?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start();
+    let mut chars = rest.chars();
+    let quote = chars.next().filter(|c| *c == '\'' || *c == '"')?;
+
+    let mut name = String::new();
+    let mut closed = false;
+    while let Some(c) = chars.next() {
+        if closed {
+            if !c.is_whitespace() {
+                return None;
+            }
+        } else if c == '\\' {
+            name.push(chars.next()?);
+        } else if c == quote {
+            closed = true;
+        } else {
+            name.push(c);
+        }
+    }
+    closed.then_some((role, Some(name)))
+}
+
+/// Generate JavaScript matching elements below `root` by computed role and accessible name
+fn aria_js(root: &str, value: &str, single: bool) -> String {
+    let Some((role, name)) = parse_aria_selector(value) else {
+        let message = serde_json::Value::from(format!("__wd:invalid selector: {value}"));
+        return format!("(function() {{ throw new Error({message}); }})()");
+    };
+    let role = serde_json::Value::from(role);
+    let name = serde_json::Value::from(name);
+    let pick = if single { "[0] || null" } else { "" };
+
+    format!(
+        r"(function() {{
+            var role = {role};
+            var name = {name};
+            var computeRole = {COMPUTED_ROLE_JS};
+            var computeName = {COMPUTED_LABEL_JS};
+            var normalize = function(text) {{ return (text || '').replace(/\s+/g, ' ').trim(); }};
+            return Array.from({root}.querySelectorAll('*')).filter(function(el) {{
+                var roles = (computeRole(el) || '').toLowerCase().split(/\s+/);
+                if (roles[0] !== role.toLowerCase()) return false;
+                return name === null || normalize(computeName(el, computeRole)) === normalize(name);
+            }});
+        }})(){pick}"
+    )
+}
+
+/// Generate JavaScript matching a CSS selector below `root` and inside every open shadow root
+/// beneath it. `>>>` chains selectors, each segment searching below the previous matches.
+fn deep_css_js(root: &str, value: &str, single: bool) -> String {
+    let segments = serde_json::Value::from(value.split(">>>").map(str::trim).collect::<Vec<_>>());
+    let pick = if single { "[0] || null" } else { "" };
+
+    format!(
+        r"(function() {{
+            var search = function(node, selector, out) {{
+                for (var child = node.firstElementChild; child; child = child.nextElementSibling) {{
+                    if (child.matches(selector) && out.indexOf(child) === -1) out.push(child);
+                    if (child.shadowRoot) search(child.shadowRoot, selector, out);
+                    search(child, selector, out);
+                }}
+            }};
+            var scopes = [{root}];
+            {segments}.forEach(function(selector) {{
+                try {{
+                    document.createDocumentFragment().querySelector(selector);
+                }} catch (e) {{
+                    throw new Error('__wd:invalid selector: ' + selector);
+                }}
+                var matches = [];
+                scopes.forEach(function(scope) {{
+                    search(scope, selector, matches);
+                    if (scope.shadowRoot) search(scope.shadowRoot, selector, matches);
+                }});
+                scopes = matches;
+            }});
+            return scopes;
+        }})(){pick}"
+    )
+}
+
 /// Locator strategies for finding elements
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LocatorStrategy {
     CssSelector,
     LinkText,
     PartialLinkText,
     TagName,
     XPath,
+    /// Matches the configured test id attribute exactly
+    TestId(String),
+    /// Matches computed role and optional accessible name, e.g. `button[name='Save']`
+    Aria,
+    /// CSS selector searching through open shadow roots, e.g. `my-app >>> button.save`
+    DeepCss,
+    Custom(CustomLocator),
 }
 
 impl LocatorStrategy {
-    /// Parse locator strategy from `WebDriver` string
-    pub fn from_string(s: &str) -> Option<Self> {
+    /// Parse locator strategy from `WebDriver` string, falling back to registered custom strategies
+    pub fn from_string(s: &str, registry: &LocatorRegistry) -> Option<Self> {
         match s {
             "css selector" => Some(Self::CssSelector),
             "link text" => Some(Self::LinkText),
             "partial link text" => Some(Self::PartialLinkText),
             "tag name" => Some(Self::TagName),
             "xpath" => Some(Self::XPath),
-            _ => None,
+            "aria" => Some(Self::Aria),
+            "deep css selector" => Some(Self::DeepCss),
+            "test id" => Some(Self::TestId(registry.test_id_attribute.clone())),
+            _ => registry.get(s).map(Self::Custom),
         }
     }
 
     /// Generate JavaScript expression to find element (just the selector, no wrapper)
-    pub fn to_selector_js(self, value: &str) -> String {
+    pub fn to_selector_js(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -50,11 +276,20 @@ impl LocatorStrategy {
                     r"Array.from(document.querySelectorAll('a')).find(a => a.textContent.includes('{escaped}')) || null"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "document.querySelector({})",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("document", value, true),
+            LocatorStrategy::DeepCss => deep_css_js("document", value, true),
+            LocatorStrategy::Custom(custom) => custom.first_js("document", value),
         }
     }
 
     /// Generate JavaScript expression to find multiple elements
-    pub fn to_selector_js_multiple(self, value: &str) -> String {
+    pub fn to_selector_js_multiple(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -87,13 +322,22 @@ impl LocatorStrategy {
                     r"Array.from(document.querySelectorAll('a')).filter(a => a.textContent.includes('{escaped}'))"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "Array.from(document.querySelectorAll({}))",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("document", value, false),
+            LocatorStrategy::DeepCss => deep_css_js("document", value, false),
+            LocatorStrategy::Custom(custom) => custom.all_js("document", value),
         }
     }
 
     /// Generate JavaScript expression to find a single element from a parent element
     /// Returns an expression that evaluates to a single element (or null)
     /// Assumes `parent` variable is defined
-    pub fn to_selector_js_single_from_element(self, value: &str) -> String {
+    pub fn to_selector_js_single_from_element(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -121,13 +365,22 @@ impl LocatorStrategy {
                     r"Array.from(parent.querySelectorAll('a')).find(a => a.textContent.includes('{escaped}')) || null"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "parent.querySelector({})",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("parent", value, true),
+            LocatorStrategy::DeepCss => deep_css_js("parent", value, true),
+            LocatorStrategy::Custom(custom) => custom.first_js("parent", value),
         }
     }
 
     /// Generate JavaScript expression to find multiple elements from a parent element
     /// Returns an expression that evaluates to an array-like collection
     /// Assumes `parent` variable is defined
-    pub fn to_selector_js_from_element(self, value: &str) -> String {
+    pub fn to_selector_js_from_element(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -160,13 +413,22 @@ impl LocatorStrategy {
                     r"Array.from(parent.querySelectorAll('a')).filter(a => a.textContent.includes('{escaped}'))"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "Array.from(parent.querySelectorAll({}))",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("parent", value, false),
+            LocatorStrategy::DeepCss => deep_css_js("parent", value, false),
+            LocatorStrategy::Custom(custom) => custom.all_js("parent", value),
         }
     }
 
     /// Generate JavaScript expression to find a single element from a shadow root
     /// Returns an expression that evaluates to a single element (or null)
     /// Assumes `shadow` variable is defined
-    pub fn to_selector_js_single_from_shadow(self, value: &str) -> String {
+    pub fn to_selector_js_single_from_shadow(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -192,13 +454,22 @@ impl LocatorStrategy {
                     r"Array.from(shadow.querySelectorAll('a')).find(a => a.textContent.includes('{escaped}')) || null"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "shadow.querySelector({})",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("shadow", value, true),
+            LocatorStrategy::DeepCss => deep_css_js("shadow", value, true),
+            LocatorStrategy::Custom(custom) => custom.first_js("shadow", value),
         }
     }
 
     /// Generate JavaScript expression to find multiple elements from a shadow root
     /// Returns an expression that evaluates to an array-like collection
     /// Assumes `shadow` variable is defined
-    pub fn to_selector_js_from_shadow(self, value: &str) -> String {
+    pub fn to_selector_js_from_shadow(&self, value: &str) -> String {
         let escaped = value.replace('\\', "\\\\").replace('\'', "\\'");
 
         match self {
@@ -228,6 +499,15 @@ impl LocatorStrategy {
                     r"Array.from(shadow.querySelectorAll('a')).filter(a => a.textContent.includes('{escaped}'))"
                 )
             }
+            LocatorStrategy::TestId(attribute) => {
+                format!(
+                    "Array.from(shadow.querySelectorAll({}))",
+                    test_id_selector(attribute, &escaped)
+                )
+            }
+            LocatorStrategy::Aria => aria_js("shadow", value, false),
+            LocatorStrategy::DeepCss => deep_css_js("shadow", value, false),
+            LocatorStrategy::Custom(custom) => custom.all_js("shadow", value),
         }
     }
 }
@@ -239,14 +519,104 @@ mod tests {
     #[test]
     fn test_parse_strategy() {
         assert_eq!(
-            LocatorStrategy::from_string("css selector"),
+            LocatorStrategy::from_string("css selector", &LocatorRegistry::default()),
             Some(LocatorStrategy::CssSelector)
         );
         assert_eq!(
-            LocatorStrategy::from_string("xpath"),
+            LocatorStrategy::from_string("xpath", &LocatorRegistry::default()),
             Some(LocatorStrategy::XPath)
         );
-        assert_eq!(LocatorStrategy::from_string("unknown"), None);
+        assert_eq!(
+            LocatorStrategy::from_string("unknown", &LocatorRegistry::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_test_id_strategy() {
+        let mut registry = LocatorRegistry::default();
+        let strategy = LocatorStrategy::from_string("test id", &registry)
+            .expect("test id strategy should resolve");
+        let js = strategy.to_selector_js("save-button");
+
+        assert!(js.contains("document.querySelector"));
+        assert!(js.contains("CSS.escape('data-testid')"));
+        assert!(js.contains("CSS.escape('save-button')"));
+
+        registry.set_test_id_attribute("data-test");
+        let strategy = LocatorStrategy::from_string("test id", &registry)
+            .expect("test id strategy should resolve");
+        let js = strategy.to_selector_js_from_element("save-button");
+
+        assert!(js.contains("parent.querySelectorAll"));
+        assert!(js.contains("CSS.escape('data-test')"));
+    }
+
+    #[test]
+    fn test_parse_aria_selector() {
+        assert_eq!(parse_aria_selector("button"), Some(("button", None)));
+        assert_eq!(
+            parse_aria_selector("button[name='Save']"),
+            Some(("button", Some("Save".to_string())))
+        );
+        assert_eq!(
+            parse_aria_selector(r#"link [ name = "Don\"t go" ]"#),
+            Some(("link", Some("Don\"t go".to_string())))
+        );
+        assert_eq!(parse_aria_selector("button[label='Save']"), None);
+        assert_eq!(parse_aria_selector("button[name='Save'"), None);
+        assert_eq!(parse_aria_selector("[name='Save']"), None);
+    }
+
+    #[test]
+    fn test_aria_js() {
+        let strategy = LocatorStrategy::from_string("aria", &LocatorRegistry::default())
+            .expect("aria strategy should resolve");
+
+        let js = strategy.to_selector_js("button[name='Save']");
+        assert!(js.contains("document.querySelectorAll('*')"));
+        assert!(js.contains("var role = \"button\""));
+        assert!(js.contains("var name = \"Save\""));
+        assert!(js.ends_with("[0] || null"));
+
+        let js = strategy.to_selector_js_from_shadow("button(");
+        assert!(js.contains("invalid selector"));
+    }
+
+    #[test]
+    fn test_deep_css_js() {
+        let strategy =
+            LocatorStrategy::from_string("deep css selector", &LocatorRegistry::default())
+                .expect("deep css strategy should resolve");
+
+        let js = strategy.to_selector_js_from_element("my-app >>> button.save");
+        assert!(js.contains("var scopes = [parent]"));
+        assert!(js.contains("[\"my-app\",\"button.save\"]"));
+        assert!(js.contains("child.shadowRoot"));
+        assert!(!js.ends_with("[0] || null"));
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        let mut registry = LocatorRegistry::default();
+        registry.register(
+            "component",
+            Arc::new(|root: &str, value: &str| {
+                format!("{root}.querySelectorAll('[data-component=' + {value} + ']')")
+            }),
+        );
+
+        let strategy = LocatorStrategy::from_string("component", &registry)
+            .expect("custom strategy should resolve");
+        let js = strategy.to_selector_js_single_from_shadow("it's");
+
+        assert!(js.contains("__root.querySelectorAll"));
+        assert!(js.contains("(shadow)"));
+        assert!(js.contains("\"it's\""));
+        assert_eq!(
+            LocatorStrategy::from_string("component", &LocatorRegistry::default()),
+            None
+        );
     }
 
     #[test]
@@ -1,5 +1,13 @@
+pub mod alert;
+pub mod cookie;
 pub mod element;
+pub mod image;
+pub mod keys;
 pub mod locator;
+pub mod print;
 pub mod session;
+pub mod window;
 
-pub use session::{ActionState, SessionManager, Timeouts};
+pub use alert::{AlertStateManager, DialogTimeout};
+pub use session::{ActionState, InputSourceType, SessionManager, Timeouts};
+pub use window::WebviewTarget;
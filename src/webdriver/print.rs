@@ -0,0 +1,113 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::server::response::WebDriverErrorResponse;
+
+/// An inclusive range of 1-based page numbers to print
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    pub start: u32,
+    /// Last page to print, or `None` to print through the end of the document
+    pub end: Option<u32>,
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) if end == self.start => write!(f, "{end}"),
+            Some(end) => write!(f, "{}-{end}", self.start),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+/// Parse one page range: a page number, `start-end`, `-end` or `start-`
+pub fn parse_page_range(range: &str) -> Result<PageRange, WebDriverErrorResponse> {
+    let invalid =
+        || WebDriverErrorResponse::invalid_argument(&format!("Invalid page range: {range:?}"));
+    let page = |part: &str| -> Result<Option<u32>, WebDriverErrorResponse> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Ok(None);
+        }
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        match part.parse::<u32>() {
+            Ok(0) | Err(_) => Err(invalid()),
+            Ok(page) => Ok(Some(page)),
+        }
+    };
+
+    let parsed = match range.split_once('-') {
+        None => {
+            let page = page(range)?.ok_or_else(invalid)?;
+            PageRange {
+                start: page,
+                end: Some(page),
+            }
+        }
+        Some((start, end)) => PageRange {
+            start: page(start)?.unwrap_or(1),
+            end: page(end)?,
+        },
+    };
+
+    if parsed.end.is_some_and(|end| end < parsed.start) {
+        return Err(invalid());
+    }
+    Ok(parsed)
+}
+
+/// Parse the `pageRanges` print parameter, whose items are page numbers or range strings
+pub fn parse_page_ranges(ranges: &[Value]) -> Result<Vec<PageRange>, WebDriverErrorResponse> {
+    ranges
+        .iter()
+        .map(|range| match range {
+            Value::String(range) => parse_page_range(range),
+            Value::Number(page) if page.is_u64() => parse_page_range(&page.to_string()),
+            _ => Err(WebDriverErrorResponse::invalid_argument(
+                "pageRanges items must be integers or strings",
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_page_ranges() {
+        let ranges = parse_page_ranges(&[json!(2), json!("4-6"), json!(" -3 "), json!("7-")])
+            .expect("ranges should parse");
+        let formatted: Vec<String> = ranges.iter().map(ToString::to_string).collect();
+        assert_eq!(formatted, ["2", "4-6", "1-3", "7-"]);
+        assert_eq!(
+            ranges[3],
+            PageRange {
+                start: 7,
+                end: None
+            }
+        );
+
+        for invalid in [
+            json!("5-2"),
+            json!("1-2-3"),
+            json!("a"),
+            json!(""),
+            json!("+1"),
+            json!(0),
+            json!(-1),
+            json!(1.5),
+            json!(true),
+        ] {
+            assert!(
+                parse_page_ranges(std::slice::from_ref(&invalid)).is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+}
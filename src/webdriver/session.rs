@@ -1,19 +1,122 @@
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
 use uuid::Uuid;
 
 use super::element::ElementStore;
-use crate::platform::FrameId;
+use super::image::Image;
+use super::window::{WebviewTarget, WindowHandleStore};
+use crate::platform::{FrameContext, ModifierState, PointerType};
 use crate::server::response::WebDriverErrorResponse;
 
-/// Tracks currently pressed keys and pointer buttons for action state
+/// Type of an input source used in action sequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSourceType {
+    Key,
+    Pointer(PointerType),
+    Wheel,
+    None,
+}
+
+/// Longest delay between presses that still counts as a multi-click
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Farthest a pointer may move between presses that still count as a multi-click
+const MULTI_CLICK_DISTANCE: i32 = 4;
+
+/// Last press of a pointer source, used to count consecutive clicks
+#[derive(Debug, Clone, Copy)]
+pub struct ClickState {
+    pub button: u32,
+    pub position: (i32, i32),
+    pub pressed_at: Instant,
+    pub count: u32,
+}
+
+/// Input state shared by all Perform Actions calls in a session
 #[derive(Debug, Default, Clone)]
 pub struct ActionState {
+    /// Type of each input source seen so far, by source ID
+    pub source_types: HashMap<String, InputSourceType>,
     /// Currently pressed keyboard keys (`WebDriver` key codes)
     pub pressed_keys: HashSet<String>,
+    /// Modifier keys held by key input sources
+    pub modifiers: ModifierState,
+    /// Pointer ID of each pointer source, assigned in registration order
+    pub pointer_ids: HashMap<String, u32>,
     /// Currently pressed pointer buttons by source ID
     pub pressed_buttons: HashMap<String, HashSet<u32>>,
+    /// Last pointer position in viewport coordinates by source ID
+    pub pointer_positions: HashMap<String, (i32, i32)>,
+    /// Last press by source ID
+    pub clicks: HashMap<String, ClickState>,
+}
+
+impl ActionState {
+    /// Device type of a registered pointer source
+    pub fn pointer_type(&self, id: &str) -> PointerType {
+        match self.source_types.get(id) {
+            Some(InputSourceType::Pointer(pointer_type)) => *pointer_type,
+            _ => PointerType::default(),
+        }
+    }
+
+    /// Record a button press and return its click count: quick presses of the same
+    /// button at about the same position count up, anything else starts over at 1
+    pub fn register_press(&mut self, id: &str, button: u32, position: (i32, i32)) -> u32 {
+        let now = Instant::now();
+        let count = match self.clicks.get(id) {
+            Some(last)
+                if last.button == button
+                    && now.duration_since(last.pressed_at) <= MULTI_CLICK_INTERVAL
+                    && (last.position.0 - position.0).abs() <= MULTI_CLICK_DISTANCE
+                    && (last.position.1 - position.1).abs() <= MULTI_CLICK_DISTANCE =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.clicks.insert(
+            id.to_string(),
+            ClickState {
+                button,
+                position,
+                pressed_at: now,
+                count,
+            },
+        );
+        count
+    }
+
+    /// Click count of the source's current press
+    pub fn click_count(&self, id: &str) -> u32 {
+        self.clicks.get(id).map_or(1, |click| click.count)
+    }
+
+    /// Register an input source, rejecting IDs already used by a source of another type
+    pub fn register_source(
+        &mut self,
+        id: &str,
+        source_type: InputSourceType,
+    ) -> Result<(), WebDriverErrorResponse> {
+        match self.source_types.get(id) {
+            Some(existing) if *existing != source_type => {
+                Err(WebDriverErrorResponse::invalid_argument(&format!(
+                    "Input source {id} is already registered as {existing:?}"
+                )))
+            }
+            Some(_) => Ok(()),
+            None => {
+                if matches!(source_type, InputSourceType::Pointer(_)) {
+                    let pointer_id = u32::try_from(self.pointer_ids.len()).unwrap_or(u32::MAX);
+                    self.pointer_ids.insert(id.to_string(), pointer_id);
+                }
+                self.source_types.insert(id.to_string(), source_type);
+                Ok(())
+            }
+        }
+    }
 }
 
 /// Session timeouts configuration
@@ -40,6 +143,7 @@ impl Default for Timeouts {
 
 /// Represents a `WebDriver` session
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Session {
     /// Unique session identifier
     pub id: String,
@@ -47,25 +151,62 @@ pub struct Session {
     pub timeouts: Timeouts,
     /// Element reference storage
     pub elements: ElementStore,
-    /// Current window handle
-    pub current_window: String,
+    /// Webview targeted by commands in this session
+    pub current_window: WebviewTarget,
+    /// Opaque window handles exposed to the client
+    pub window_handles: WindowHandleStore,
     /// Current frame context (stack of frame selectors)
-    pub frame_context: Vec<FrameId>,
+    pub frame_context: FrameContext,
     /// Action state tracking for pressed keys/buttons
     pub action_state: ActionState,
+    /// Report window rects in physical pixels instead of CSS pixels
+    pub physical_window_rect: bool,
+    /// Create new windows hidden and never raise windows to the foreground
+    pub hidden_windows: bool,
+    /// Expose closed shadow roots recorded by the `attachShadow` patch
+    pub closed_shadow_roots: bool,
+    /// Perform actions with native OS input instead of synthesized DOM events
+    pub native_input: bool,
+    /// Capture the whole scrollable document in Take Screenshot
+    pub full_page_screenshots: bool,
+    /// Scale screenshots from device pixels down to CSS pixels
+    pub css_pixel_screenshots: bool,
+    /// Named baselines for visual comparison, kept on the driver side
+    pub visual_baselines: HashMap<String, Image>,
+    /// Preload scripts added by this session, by id, with the webview they run in
+    pub preload_scripts: HashMap<String, WebviewTarget>,
 }
 
 impl Session {
-    pub fn new(initial_window: String) -> Self {
+    pub fn new(initial_window: WebviewTarget) -> Self {
+        let mut window_handles = WindowHandleStore::new();
+        window_handles.handle_for(&initial_window.webview);
+        let id = Uuid::new_v4().to_string();
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: id.clone(),
             timeouts: Timeouts::default(),
             elements: ElementStore::new(),
             current_window: initial_window,
-            frame_context: Vec::new(),
+            window_handles,
+            frame_context: FrameContext::new(id),
             action_state: ActionState::default(),
+            physical_window_rect: false,
+            hidden_windows: false,
+            closed_shadow_roots: false,
+            native_input: false,
+            full_page_screenshots: false,
+            css_pixel_screenshots: false,
+            visual_baselines: HashMap::new(),
+            preload_scripts: HashMap::new(),
         }
     }
+
+    /// Return to the top-level browsing context after the page navigated.
+    /// References to elements inside frames of the old document become stale.
+    pub fn reset_frame_context(&mut self) {
+        self.frame_context.clear();
+        self.elements.remove_frame_elements();
+    }
 }
 
 /// Manages `WebDriver` sessions
@@ -82,11 +223,13 @@ impl SessionManager {
     }
 
     /// Create a new session
-    pub fn create(&mut self, initial_window: String) -> &Session {
+    pub fn create(&mut self, initial_window: WebviewTarget) -> &mut Session {
         let session = Session::new(initial_window);
         let id = session.id.clone();
         self.sessions.insert(id.clone(), session);
-        self.sessions.get(&id).expect("session was just inserted")
+        self.sessions
+            .get_mut(&id)
+            .expect("session was just inserted")
     }
 
     /// Get a session by ID
@@ -103,8 +246,61 @@ impl SessionManager {
             .ok_or_else(|| WebDriverErrorResponse::invalid_session_id(id))
     }
 
+    /// Reset the frame context of every session targeting a webview that navigated
+    pub fn reset_frame_context_for_webview(&mut self, webview_label: &str) {
+        for session in self.sessions.values_mut() {
+            if session.current_window.webview == webview_label {
+                session.reset_frame_context();
+            }
+        }
+    }
+
+    /// IDs of all active sessions
+    pub fn ids(&self) -> Vec<String> {
+        self.sessions.keys().cloned().collect()
+    }
+
     /// Delete a session
     pub fn delete(&mut self, id: &str) -> bool {
         self.sessions.remove(id).is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_source() {
+        let mut state = ActionState::default();
+        let mouse = InputSourceType::Pointer(PointerType::Mouse);
+        assert!(state.register_source("mouse", mouse).is_ok());
+        assert!(state.register_source("mouse", mouse).is_ok());
+        assert!(state
+            .register_source("mouse", InputSourceType::Key)
+            .is_err());
+        assert!(state
+            .register_source("mouse", InputSourceType::Pointer(PointerType::Touch))
+            .is_err());
+        assert_eq!(state.pointer_type("mouse"), PointerType::Mouse);
+        assert!(state
+            .register_source("finger", InputSourceType::Pointer(PointerType::Touch))
+            .is_ok());
+        assert_eq!(state.pointer_ids.get("mouse"), Some(&0));
+        assert_eq!(state.pointer_ids.get("finger"), Some(&1));
+        assert!(state
+            .register_source("keyboard", InputSourceType::Key)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_register_press() {
+        let mut state = ActionState::default();
+        assert_eq!(state.register_press("mouse", 0, (10, 10)), 1);
+        assert_eq!(state.register_press("mouse", 0, (11, 9)), 2);
+        assert_eq!(state.click_count("mouse"), 2);
+        assert_eq!(state.register_press("mouse", 2, (11, 9)), 1);
+        assert_eq!(state.register_press("mouse", 2, (40, 9)), 1);
+        assert_eq!(state.register_press("pen", 2, (40, 9)), 1);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+#[cfg(desktop)]
+use crate::platform::WindowRect;
+
+/// JSON key identifying a window reference
+pub const WINDOW_KEY: &str = "window-fcc6-11e5-b4f8-330a88ab9d7f";
+
+/// Placeholder the page serializes in place of the current top-level window's handle
+pub const CURRENT_WINDOW_MARKER: &str = "current";
+
+/// A specific webview within a window, identified by its (window label, webview label) pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebviewTarget {
+    /// Label of the window hosting the webview
+    pub window: String,
+    /// Label of the webview
+    pub webview: String,
+}
+
+impl WebviewTarget {
+    pub fn new(window: impl Into<String>, webview: impl Into<String>) -> Self {
+        Self {
+            window: window.into(),
+            webview: webview.into(),
+        }
+    }
+}
+
+/// Session-scoped mapping between opaque `WebDriver` window handles and Tauri webview labels
+#[derive(Debug, Default)]
+pub struct WindowHandleStore {
+    /// Window handle -> Tauri webview label
+    labels: HashMap<String, String>,
+    /// Tauri webview label -> window handle
+    handles: HashMap<String, String>,
+}
+
+impl WindowHandleStore {
+    pub fn new() -> Self {
+        Self {
+            labels: HashMap::new(),
+            handles: HashMap::new(),
+        }
+    }
+
+    /// Get the handle for a webview label, generating a new one if the webview is unknown
+    pub fn handle_for(&mut self, label: &str) -> String {
+        if let Some(handle) = self.handles.get(label) {
+            return handle.clone();
+        }
+
+        let handle = Uuid::new_v4().to_string();
+        self.handles.insert(label.to_string(), handle.clone());
+        self.labels.insert(handle.clone(), label.to_string());
+        handle
+    }
+
+    /// Get the webview label for a handle
+    pub fn label_for(&self, handle: &str) -> Option<&str> {
+        self.labels.get(handle).map(String::as_str)
+    }
+
+    /// Invalidate the handle of a webview that has been closed
+    pub fn remove_label(&mut self, label: &str) {
+        if let Some(handle) = self.handles.remove(label) {
+            self.labels.remove(&handle);
+        }
+    }
+
+    /// Drop handles for webviews that no longer exist
+    pub fn retain_labels(&mut self, live_labels: &[String]) {
+        self.handles.retain(|label, _| live_labels.contains(label));
+        let handles = &self.handles;
+        self.labels.retain(|_, label| handles.contains_key(label));
+    }
+}
+
+/// Collect the handles of all window references in serialized script data
+pub fn window_handles_in(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(window_handles_in).collect(),
+        Value::Object(map) => match map.get(WINDOW_KEY).and_then(Value::as_str) {
+            Some(handle) => vec![handle.to_string()],
+            None => map.values().flat_map(window_handles_in).collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Replace references to the current window in a script result with its `handle`
+pub fn resolve_current_window(value: &mut Value, handle: &str) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                resolve_current_window(item, handle);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(reference) = map.get_mut(WINDOW_KEY) {
+                if reference == CURRENT_WINDOW_MARKER {
+                    *reference = Value::String(handle.to_string());
+                }
+                return;
+            }
+            for item in map.values_mut() {
+                resolve_current_window(item, handle);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keep a requested window rect reachable. A rect overlapping any monitor's work area
+/// is left alone, so windows can span monitors or hang partly off an edge. One that is
+/// entirely off-screen is moved onto the nearest monitor and shrunk to fit it.
+#[cfg(desktop)]
+pub fn fit_to_work_areas(rect: &WindowRect, work_areas: &[WindowRect]) -> WindowRect {
+    if work_areas.iter().any(|area| overlaps(rect, area)) {
+        return rect.clone();
+    }
+    work_areas
+        .iter()
+        .min_by_key(|area| gap_sq(rect, area))
+        .map_or_else(|| rect.clone(), |area| clamp_to(rect, area))
+}
+
+/// Whether two rects share any area
+#[cfg(desktop)]
+fn overlaps(a: &WindowRect, b: &WindowRect) -> bool {
+    let (a_right, a_bottom) = far_corner(a);
+    let (b_right, b_bottom) = far_corner(b);
+    i64::from(a.x) < b_right
+        && i64::from(b.x) < a_right
+        && i64::from(a.y) < b_bottom
+        && i64::from(b.y) < a_bottom
+}
+
+/// Squared distance between the closest points of two rects (0 if they touch or overlap)
+#[cfg(desktop)]
+fn gap_sq(a: &WindowRect, b: &WindowRect) -> i64 {
+    let (a_right, a_bottom) = far_corner(a);
+    let (b_right, b_bottom) = far_corner(b);
+    let dx = (i64::from(b.x) - a_right)
+        .max(i64::from(a.x) - b_right)
+        .max(0);
+    let dy = (i64::from(b.y) - a_bottom)
+        .max(i64::from(a.y) - b_bottom)
+        .max(0);
+    dx * dx + dy * dy
+}
+
+#[cfg(desktop)]
+fn far_corner(rect: &WindowRect) -> (i64, i64) {
+    (
+        i64::from(rect.x) + i64::from(rect.width),
+        i64::from(rect.y) + i64::from(rect.height),
+    )
+}
+
+/// Move and shrink a rect so it fits entirely within `area`
+#[cfg(desktop)]
+fn clamp_to(rect: &WindowRect, area: &WindowRect) -> WindowRect {
+    let width = rect.width.min(area.width);
+    let height = rect.height.min(area.height);
+    let max_x = area.x.saturating_add_unsigned(area.width - width);
+    let max_y = area.y.saturating_add_unsigned(area.height - height);
+    WindowRect {
+        x: rect.x.clamp(area.x, max_x),
+        y: rect.y.clamp(area.y, max_y),
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_is_stable_per_label() {
+        let mut store = WindowHandleStore::new();
+        let first = store.handle_for("main");
+        let second = store.handle_for("main");
+
+        assert_eq!(first, second);
+        assert_ne!(first, "main");
+        assert_eq!(store.label_for(&first), Some("main"));
+    }
+
+    #[test]
+    fn test_closed_window_handle_is_invalidated() {
+        let mut store = WindowHandleStore::new();
+        let main = store.handle_for("main");
+        let other = store.handle_for("other");
+
+        store.retain_labels(&["main".to_string()]);
+        assert_eq!(store.label_for(&main), Some("main"));
+        assert_eq!(store.label_for(&other), None);
+
+        store.remove_label("main");
+        assert_eq!(store.label_for(&main), None);
+        // A reopened window with the same label gets a fresh handle
+        assert_ne!(store.handle_for("main"), main);
+    }
+
+    #[test]
+    fn test_resolve_current_window_reference() {
+        let mut result = serde_json::json!([{ WINDOW_KEY: CURRENT_WINDOW_MARKER }, 1]);
+        resolve_current_window(&mut result, "handle-1");
+        assert_eq!(result, serde_json::json!([{ WINDOW_KEY: "handle-1" }, 1]));
+        assert_eq!(window_handles_in(&result), vec!["handle-1".to_string()]);
+    }
+
+    #[cfg(desktop)]
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> WindowRect {
+        WindowRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    #[cfg(desktop)]
+    fn test_fit_to_work_areas_keeps_visible_rects() {
+        let monitors = [rect(0, 0, 1920, 1080), rect(1920, 0, 2560, 1440)];
+
+        // Partly off the left edge
+        let partly_off = rect(-200, 100, 800, 600);
+        assert_eq!(fit_to_work_areas(&partly_off, &monitors), partly_off);
+        // Spanning both monitors
+        let spanning = rect(1500, 200, 1000, 600);
+        assert_eq!(fit_to_work_areas(&spanning, &monitors), spanning);
+        // Entirely on the second monitor
+        let second = rect(2500, 300, 800, 600);
+        assert_eq!(fit_to_work_areas(&second, &monitors), second);
+        // No monitors reported
+        assert_eq!(fit_to_work_areas(&partly_off, &[]), partly_off);
+    }
+
+    #[test]
+    #[cfg(desktop)]
+    fn test_fit_to_work_areas_moves_off_screen_rects() {
+        let monitors = [rect(0, 0, 1920, 1080), rect(1920, 0, 2560, 1440)];
+
+        // Off the right edge of the second monitor
+        assert_eq!(
+            fit_to_work_areas(&rect(5000, 100, 800, 600), &monitors),
+            rect(3680, 100, 800, 600)
+        );
+        // Above the first monitor
+        assert_eq!(
+            fit_to_work_areas(&rect(100, -2000, 800, 600), &monitors),
+            rect(100, 0, 800, 600)
+        );
+        // Below the first monitor, where the second is taller and nearer
+        assert_eq!(
+            fit_to_work_areas(&rect(1800, 1500, 400, 400), &monitors),
+            rect(1920, 1040, 400, 400)
+        );
+    }
+
+    #[test]
+    #[cfg(desktop)]
+    fn test_fit_to_work_areas_shrinks_to_monitor() {
+        let monitors = [rect(0, 0, 1920, 1080)];
+        assert_eq!(
+            fit_to_work_areas(&rect(3000, 3000, 4000, 3000), &monitors),
+            rect(0, 0, 1920, 1080)
+        );
+        // A larger rect that still overlaps the monitor is left alone
+        let oversized = rect(-100, -100, 4000, 3000);
+        assert_eq!(fit_to_work_areas(&oversized, &monitors), oversized);
+    }
+}